@@ -0,0 +1,406 @@
+use crate::commitment::Commitment;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+use tracing::{info, warn};
+
+const MAGIC: &[u8; 8] = b"SLOTLOG1";
+// slot (8) + kind (1) + commitment (1) + checksum (4)
+const RECORD_LEN: usize = 14;
+// The log is rewritten once it holds this many records and at least twice as many as live slots
+const COMPACT_MIN_RECORDS: u64 = 100_000;
+
+/// What the store knows about a slot, with the commitment it was observed at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoredSlot {
+    Confirmed(Commitment),
+    Skipped(Commitment),
+}
+
+impl StoredSlot {
+    pub fn commitment(&self) -> Commitment {
+        match self {
+            StoredSlot::Confirmed(commitment) | StoredSlot::Skipped(commitment) => *commitment,
+        }
+    }
+
+    // Whether an observation should replace the stored one, following the LRU cache rules
+    fn supersedes(&self, stored: &StoredSlot) -> bool {
+        match (self, stored) {
+            (StoredSlot::Confirmed(new), StoredSlot::Confirmed(old)) => new > old,
+            (StoredSlot::Confirmed(_), StoredSlot::Skipped(_)) => true,
+            (StoredSlot::Skipped(new), StoredSlot::Confirmed(old)) => new > old,
+            (StoredSlot::Skipped(new), StoredSlot::Skipped(old)) => new > old,
+        }
+    }
+}
+
+/// Slots kept by the store, at most `max_slots` of them, the least recently written
+/// ones are evicted first
+struct Index {
+    slots: HashMap<u64, IndexEntry>,
+    // Write sequence to slot, oldest write first
+    order: BTreeMap<u64, u64>,
+    next_write: u64,
+    max_slots: usize,
+}
+
+struct IndexEntry {
+    stored: StoredSlot,
+    written: u64,
+}
+
+impl Index {
+    fn new(max_slots: usize) -> Self {
+        Self {
+            slots: HashMap::new(),
+            order: BTreeMap::new(),
+            next_write: 0,
+            max_slots: max_slots.max(1),
+        }
+    }
+
+    fn get(&self, slot: &u64) -> Option<StoredSlot> {
+        self.slots.get(slot).map(|entry| entry.stored)
+    }
+
+    fn adds(&self, slot: u64, stored: &StoredSlot) -> bool {
+        self.get(&slot)
+            .is_none_or(|current| stored.supersedes(&current))
+    }
+
+    fn insert(&mut self, slot: u64, stored: StoredSlot) {
+        if !self.adds(slot, &stored) {
+            return;
+        }
+        let written = self.next_write;
+        self.next_write += 1;
+        if let Some(previous) = self.slots.insert(slot, IndexEntry { stored, written }) {
+            self.order.remove(&previous.written);
+        }
+        self.order.insert(written, slot);
+
+        while self.slots.len() > self.max_slots
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            self.slots.remove(&oldest);
+        }
+    }
+
+    // Stored slots, oldest write first, the order the log replays them in
+    fn entries(&self) -> impl Iterator<Item = (u64, StoredSlot)> + '_ {
+        self.order
+            .values()
+            .map(|slot| (*slot, self.slots[slot].stored))
+    }
+}
+
+struct LogWriter {
+    log: File,
+    // Records in the log, including superseded and evicted ones
+    records: u64,
+}
+
+struct InnerDiskStore {
+    index: RwLock<Index>,
+    // Serializes appends and compactions, lookups only ever wait on the index
+    writer: Mutex<LogWriter>,
+    path: PathBuf,
+}
+
+/// Historical tier behind [`super::LruCache`] that remembers the slots the service has
+/// learned, across evictions and restarts, up to a maximum number of slots.
+///
+/// Observations are appended to a log of fixed-size checksummed records and indexed in
+/// memory. Past the maximum, the slots written longest ago are evicted. A torn record
+/// left by a crash is dropped on open, and the log is compacted into a fresh file once
+/// superseded or evicted records make up most of it. File I/O
+/// runs on the blocking thread pool, never while holding the lock lookups take.
+pub struct DiskStore {
+    inner: Arc<InnerDiskStore>,
+}
+
+impl DiskStore {
+    /// Opens the log at `path`, creating it if needed, and rebuilds the index from it,
+    /// keeping the `max_slots` most recently written slots
+    pub fn open(path: impl AsRef<Path>, max_slots: usize) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&path)?;
+        let len = log.metadata()?.len();
+        if len == 0 {
+            log.write_all(MAGIC)?;
+            log.sync_all()?;
+        } else {
+            let mut magic = [0; MAGIC.len()];
+            if log.read_exact(&mut magic).is_err() || &magic != MAGIC {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("{} is not a slot log", path.display()),
+                ));
+            }
+        }
+
+        let mut index = Index::new(max_slots);
+        let mut records = 0;
+        let mut valid_len = MAGIC.len() as u64;
+        if len > valid_len {
+            let mut reader = BufReader::new(&log);
+            let mut record = [0; RECORD_LEN];
+            while reader.read_exact(&mut record).is_ok() {
+                let Some((slot, stored)) = decode_record(&record) else {
+                    break;
+                };
+                index.insert(slot, stored);
+                records += 1;
+                valid_len += RECORD_LEN as u64;
+            }
+        }
+        if valid_len < len {
+            warn!(
+                "Dropping {} bytes of torn or corrupt records at the end of {}",
+                len - valid_len,
+                path.display()
+            );
+            log.set_len(valid_len)?;
+            log.sync_all()?;
+        }
+        log.seek(SeekFrom::End(0))?;
+
+        info!(
+            "Opened slot log {} with {} slots in {} records",
+            path.display(),
+            index.slots.len(),
+            records
+        );
+        Ok(Self {
+            inner: Arc::new(InnerDiskStore {
+                index: RwLock::new(index),
+                writer: Mutex::new(LogWriter { log, records }),
+                path,
+            }),
+        })
+    }
+
+    pub async fn get(&self, slot: &u64) -> Option<StoredSlot> {
+        self.inner.index.read().unwrap().get(slot)
+    }
+
+    /// Whether the slot is stored as confirmed at the given commitment or a stronger one
+    pub async fn is_confirmed_at(&self, slot: &u64, commitment: Commitment) -> bool {
+        matches!(self.get(slot).await, Some(StoredSlot::Confirmed(stored)) if stored >= commitment)
+    }
+
+    /// Whether the slot is stored as skipped at the given commitment or a stronger one
+    pub async fn is_skipped_at(&self, slot: &u64, commitment: Commitment) -> bool {
+        matches!(self.get(slot).await, Some(StoredSlot::Skipped(stored)) if stored >= commitment)
+    }
+
+    pub async fn len(&self) -> usize {
+        self.inner.index.read().unwrap().slots.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    pub async fn record_confirmed(&self, slot: u64, commitment: Commitment) {
+        self.record_all([(slot, StoredSlot::Confirmed(commitment))])
+            .await;
+    }
+
+    pub async fn record_skipped(&self, slot: u64, commitment: Commitment) {
+        self.record_all([(slot, StoredSlot::Skipped(commitment))])
+            .await;
+    }
+
+    /// Appends the observations that add to what is stored, with a single write.
+    /// Returns whether all of them are stored afterwards. Failures are logged, the slots
+    /// are then simply looked up over RPC again later.
+    pub async fn record_all(
+        &self,
+        observations: impl IntoIterator<Item = (u64, StoredSlot)>,
+    ) -> bool {
+        let observations: Vec<_> = observations.into_iter().collect();
+        let added: Vec<_> = {
+            let index = self.inner.index.read().unwrap();
+            observations
+                .iter()
+                .filter(|(slot, stored)| index.adds(*slot, stored))
+                .copied()
+                .collect()
+        };
+        if !added.is_empty() {
+            let inner = self.inner.clone();
+            if let Err(e) = tokio::task::spawn_blocking(move || inner.append(added)).await {
+                warn!("Slot log append task failed: {}", e);
+            }
+        }
+
+        // A failed append or an eviction by the same batch leaves some of them out
+        let index = self.inner.index.read().unwrap();
+        observations
+            .iter()
+            .all(|(slot, stored)| !index.adds(*slot, stored))
+    }
+
+    /// Rewrites the log with one record per stored slot
+    pub async fn compact(&self) -> io::Result<()> {
+        let inner = self.inner.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut writer = inner.writer.lock().unwrap();
+            inner.compact(&mut writer)
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+}
+
+impl InnerDiskStore {
+    fn append(&self, observations: Vec<(u64, StoredSlot)>) {
+        let mut writer = self.writer.lock().unwrap();
+
+        // Checked again, another append may have stored the same slots in the meantime
+        let appended: Vec<_> = {
+            let index = self.index.read().unwrap();
+            observations
+                .into_iter()
+                .filter(|(slot, stored)| index.adds(*slot, stored))
+                .collect()
+        };
+        if appended.is_empty() {
+            return;
+        }
+        let mut buffer = Vec::with_capacity(appended.len() * RECORD_LEN);
+        for &(slot, stored) in &appended {
+            buffer.extend_from_slice(&encode_record(slot, stored));
+        }
+
+        if let Err(e) = writer.log.write_all(&buffer) {
+            warn!(
+                "Failed to append {} records to {}: {}",
+                appended.len(),
+                self.path.display(),
+                e
+            );
+            // Cut a partial write so later appends stay aligned on record boundaries
+            let valid_len = (MAGIC.len() as u64) + writer.records * RECORD_LEN as u64;
+            let _ = writer.log.set_len(valid_len);
+            return;
+        }
+        writer.records += appended.len() as u64;
+        let live = {
+            let mut index = self.index.write().unwrap();
+            for (slot, stored) in appended {
+                index.insert(slot, stored);
+            }
+            index.slots.len() as u64
+        };
+
+        if writer.records >= COMPACT_MIN_RECORDS
+            && writer.records >= 2 * live
+            && let Err(e) = self.compact(&mut writer)
+        {
+            warn!("Failed to compact {}: {}", self.path.display(), e);
+        }
+    }
+
+    // The new log is written next to the current one and renamed over it,
+    // so a crash during compaction leaves either the old or the new log intact.
+    // Lookups keep going on the index while the file is rewritten.
+    fn compact(&self, writer: &mut LogWriter) -> io::Result<()> {
+        let mut tmp_path = self.path.as_os_str().to_owned();
+        tmp_path.push(".compact");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let bytes = {
+            let index = self.index.read().unwrap();
+            let mut bytes = Vec::with_capacity(MAGIC.len() + index.slots.len() * RECORD_LEN);
+            bytes.extend_from_slice(MAGIC);
+            for (slot, stored) in index.entries() {
+                bytes.extend_from_slice(&encode_record(slot, stored));
+            }
+            bytes
+        };
+        let live = ((bytes.len() - MAGIC.len()) / RECORD_LEN) as u64;
+
+        let mut tmp = File::create(&tmp_path)?;
+        tmp.write_all(&bytes)?;
+        tmp.sync_all()?;
+        fs::rename(&tmp_path, &self.path)?;
+
+        writer.log = OpenOptions::new().append(true).open(&self.path)?;
+        info!(
+            "Compacted {} from {} to {} records",
+            self.path.display(),
+            writer.records,
+            live
+        );
+        writer.records = live;
+        Ok(())
+    }
+}
+
+fn encode_record(slot: u64, stored: StoredSlot) -> [u8; RECORD_LEN] {
+    let (kind, commitment) = match stored {
+        StoredSlot::Confirmed(commitment) => (0, commitment),
+        StoredSlot::Skipped(commitment) => (1, commitment),
+    };
+
+    let mut record = [0; RECORD_LEN];
+    record[..8].copy_from_slice(&slot.to_le_bytes());
+    record[8] = kind;
+    record[9] = match commitment {
+        Commitment::Processed => 0,
+        Commitment::Confirmed => 1,
+        Commitment::Finalized => 2,
+    };
+    let checksum = checksum(&record[..10]);
+    record[10..].copy_from_slice(&checksum.to_le_bytes());
+    record
+}
+
+fn decode_record(record: &[u8]) -> Option<(u64, StoredSlot)> {
+    if record.len() != RECORD_LEN {
+        return None;
+    }
+    let checksum_bytes: [u8; 4] = record[10..].try_into().ok()?;
+    if u32::from_le_bytes(checksum_bytes) != checksum(&record[..10]) {
+        return None;
+    }
+
+    let slot = u64::from_le_bytes(record[..8].try_into().ok()?);
+    let commitment = match record[9] {
+        0 => Commitment::Processed,
+        1 => Commitment::Confirmed,
+        2 => Commitment::Finalized,
+        _ => return None,
+    };
+    let stored = match record[8] {
+        0 => StoredSlot::Confirmed(commitment),
+        1 => StoredSlot::Skipped(commitment),
+        _ => return None,
+    };
+    Some((slot, stored))
+}
+
+// FNV-1a, enough to tell a torn or garbled record from a valid one
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, &byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
@@ -1,13 +1,32 @@
-use std::collections::{HashMap, VecDeque};
-use std::collections::hash_map::Entry;
+use crate::commitment::Commitment;
+use crate::slot_info::SlotInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
 use tokio::sync::Mutex;
 
+// What is known about a slot, with the commitment it was observed at
+#[derive(Debug, Clone, PartialEq)]
+enum SlotState {
+    Block(SlotInfo),
+    Skipped(Commitment),
+}
+
+/// A cached key as exported by [`LruCache::entries`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LruEntry {
+    Block(SlotInfo),
+    Skipped { slot: u64, commitment: Commitment },
+}
+
 // Simplistic implementation of LRU,
 // without doubly-linked list and without unsafe code
 struct InnerLruCache {
-    map: HashMap<u64, ()>,
+    map: HashMap<u64, SlotState>,
     order: VecDeque<u64>,
     capacity: usize,
+    // Keys never chosen for eviction, they still count towards the capacity
+    pinned: HashSet<u64>,
 }
 
 pub struct LruCache {
@@ -20,7 +39,42 @@ impl InnerLruCache {
             map: HashMap::with_capacity(capacity),
             order: VecDeque::with_capacity(capacity),
             capacity,
+            pinned: HashSet::new(),
+        }
+    }
+
+    // Marks the key as recently used and returns its state
+    fn touch(&mut self, key: &u64) -> Option<SlotState> {
+        let state = self.map.get(key).cloned();
+        if state.is_some() {
+            self.move_to_front(*key);
+        }
+        state
+    }
+
+    fn insert_new(&mut self, key: u64, state: SlotState) {
+        self.map.insert(key, state);
+        self.order.push_front(key);
+        self.evict_overflow();
+    }
+
+    // Evicts the least recently used unpinned keys beyond the capacity, returns how many
+    fn evict_overflow(&mut self) -> usize {
+        let mut evicted = 0;
+        while self.order.len() > self.capacity {
+            let Some(pos) = self
+                .order
+                .iter()
+                .rposition(|key| !self.pinned.contains(key))
+            else {
+                break;
+            };
+            if let Some(lru_key) = self.order.remove(pos) {
+                self.map.remove(&lru_key);
+                evicted += 1;
+            }
         }
+        evicted
     }
 
     fn move_to_front(&mut self, key: u64) {
@@ -39,37 +93,184 @@ impl LruCache {
         }
     }
 
-    pub async fn get(&self, key: &u64) -> bool {
+    pub async fn capacity(&self) -> usize {
+        self.inner.lock().await.capacity
+    }
+
+    /// Number of cached keys, skipped slots included
+    pub async fn len(&self) -> usize {
+        self.inner.lock().await.order.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Changes the capacity, evicting the least recently used unpinned keys beyond it.
+    /// Returns the number of evicted keys.
+    pub async fn resize(&self, capacity: usize) -> usize {
+        let mut inner = self.inner.lock().await;
+        inner.capacity = capacity.max(1);
+        inner.evict_overflow()
+    }
+
+    /// Drops every key, pinned ones included, and returns how many were dropped
+    pub async fn clear(&self) -> usize {
+        let mut inner = self.inner.lock().await;
+        let cleared = inner.order.len();
+        inner.map.clear();
+        inner.order.clear();
+        inner.pinned.clear();
+        cleared
+    }
+
+    /// Forgets the key and its pin, returns false if it was not cached
+    pub async fn remove(&self, key: &u64) -> bool {
+        let mut inner = self.inner.lock().await;
+        inner.pinned.remove(key);
+        if inner.map.remove(key).is_none() {
+            return false;
+        }
+        inner.order.retain(|cached| cached != key);
+        true
+    }
+
+    /// Exempts a cached key from eviction, returns false if it is not cached
+    pub async fn pin(&self, key: u64) -> bool {
+        let mut inner = self.inner.lock().await;
+        if !inner.map.contains_key(&key) {
+            return false;
+        }
+        inner.pinned.insert(key);
+        true
+    }
+
+    /// Makes a pinned key evictable again, returns false if it was not pinned
+    pub async fn unpin(&self, key: &u64) -> bool {
         let mut inner = self.inner.lock().await;
-        if inner.map.contains_key(key) {
-            inner.move_to_front(*key);
-            true
-        } else {
-            false
+        let unpinned = inner.pinned.remove(key);
+        inner.evict_overflow();
+        unpinned
+    }
+
+    /// Pinned keys in ascending order
+    pub async fn pinned(&self) -> Vec<u64> {
+        let inner = self.inner.lock().await;
+        let mut pinned: Vec<u64> = inner.pinned.iter().copied().collect();
+        pinned.sort_unstable();
+        pinned
+    }
+
+    pub async fn get(&self, key: &u64) -> bool {
+        self.get_commitment(key)
+            .await
+            .is_some_and(|commitment| commitment >= Commitment::Confirmed)
+    }
+
+    /// Returns the highest commitment recorded for the key and marks it as recently used
+    pub async fn get_commitment(&self, key: &u64) -> Option<Commitment> {
+        match self.inner.lock().await.touch(key)? {
+            SlotState::Block(info) => Some(info.commitment),
+            SlotState::Skipped(_) => None,
+        }
+    }
+
+    /// Returns what is known about the key's block and marks it as recently used
+    pub async fn get_info(&self, key: &u64) -> Option<SlotInfo> {
+        match self.inner.lock().await.touch(key)? {
+            SlotState::Block(info) => Some(info),
+            SlotState::Skipped(_) => None,
         }
     }
 
+    /// Whether the key is known to be skipped at the given commitment or a stronger one,
+    /// marks it as recently used
+    pub async fn is_skipped_at(&self, key: &u64, commitment: Commitment) -> bool {
+        matches!(
+            self.inner.lock().await.touch(key),
+            Some(SlotState::Skipped(skipped)) if skipped >= commitment
+        )
+    }
+
     pub async fn put(&self, key: u64) {
+        self.put_with_commitment(key, Commitment::Confirmed).await;
+    }
+
+    /// Inserts the key, or upgrades its commitment if it is already present at a lower level
+    pub async fn put_with_commitment(&self, key: u64, commitment: Commitment) {
+        self.put_info(SlotInfo::new(key, commitment)).await;
+    }
+
+    /// Inserts the slot's block, or merges it into what is already known about the key
+    pub async fn put_info(&self, info: SlotInfo) {
         let mut inner = self.inner.lock().await;
+        let key = info.slot;
 
-        match inner.map.entry(key) {
-            Entry::Occupied(_) => {
+        match inner.map.get_mut(&key) {
+            Some(state) => {
+                match state {
+                    SlotState::Block(cached) => cached.merge(info),
+                    // An observed block overrides a skip observation
+                    SlotState::Skipped(_) => *state = SlotState::Block(info),
+                }
                 inner.move_to_front(key);
             }
-            Entry::Vacant(entry) => {
-                entry.insert(());
-                inner.order.push_front(key);
-
-                if inner.order.len() > inner.capacity {
-                    if let Some(lru_key) = inner.order.pop_back() {
-                        inner.map.remove(&lru_key);
-                    }
+            None => inner.insert_new(key, SlotState::Block(info)),
+        }
+    }
+
+    /// Remembers that the key has no block at the given commitment.
+    /// Ignored if a block is already recorded at that level or a stronger one.
+    pub async fn put_skipped(&self, key: u64, commitment: Commitment) {
+        let mut inner = self.inner.lock().await;
+
+        match inner.map.get_mut(&key) {
+            Some(state) => {
+                match state {
+                    SlotState::Block(cached) if cached.commitment >= commitment => {}
+                    SlotState::Skipped(cached) if *cached >= commitment => {}
+                    _ => *state = SlotState::Skipped(commitment),
                 }
+                inner.move_to_front(key);
+            }
+            None => inner.insert_new(key, SlotState::Skipped(commitment)),
+        }
+    }
+
+    /// All cached keys, most recently used first
+    pub async fn entries(&self) -> Vec<LruEntry> {
+        let inner = self.inner.lock().await;
+        inner
+            .order
+            .iter()
+            .filter_map(|key| match inner.map.get(key)? {
+                SlotState::Block(info) => Some(LruEntry::Block(info.clone())),
+                SlotState::Skipped(commitment) => Some(LruEntry::Skipped {
+                    slot: *key,
+                    commitment: *commitment,
+                }),
+            })
+            .collect()
+    }
+
+    /// Inserts exported entries, keeping their recency order
+    pub async fn restore(&self, entries: Vec<LruEntry>) {
+        for entry in entries.into_iter().rev() {
+            match entry {
+                LruEntry::Block(info) => self.put_info(info).await,
+                LruEntry::Skipped { slot, commitment } => self.put_skipped(slot, commitment).await,
             }
         }
     }
 
+    /// Returns the keys holding a block, most recently used first
     pub async fn get_all_slots(&self) -> Vec<u64> {
-        self.inner.lock().await.order.iter().cloned().collect()
+        let inner = self.inner.lock().await;
+        inner
+            .order
+            .iter()
+            .filter(|key| matches!(inner.map.get(key), Some(SlotState::Block(_))))
+            .cloned()
+            .collect()
     }
 }
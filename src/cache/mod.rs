@@ -1,5 +1,10 @@
+pub mod disk_store;
 pub mod lru_cache;
+pub mod resp;
+pub mod shared_cache;
 pub mod slot_cache;
 
-pub use lru_cache::LruCache;
+pub use disk_store::{DiskStore, StoredSlot};
+pub use lru_cache::{LruCache, LruEntry};
+pub use shared_cache::{SharedCache, SharedCacheSettings};
 pub use slot_cache::SlotCache;
@@ -0,0 +1,93 @@
+//! Minimal client side of the Redis serialization protocol (RESP2), enough for the
+//! shared cache tier: commands are sent as arrays of bulk strings and replies parsed
+//! into [`RespValue`]s.
+
+use std::{future::Future, io, pin::Pin};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RespValue {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<RespValue>>),
+}
+
+/// Appends a command to `out`, several commands written back to back form a pipeline
+pub fn encode_command<A: AsRef<[u8]>>(args: &[A], out: &mut Vec<u8>) {
+    out.extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+    for arg in args {
+        let arg = arg.as_ref();
+        out.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        out.extend_from_slice(arg);
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+/// Reads one reply
+pub fn read_value<'a, R>(
+    reader: &'a mut R,
+) -> Pin<Box<dyn Future<Output = io::Result<RespValue>> + Send + 'a>>
+where
+    R: AsyncBufRead + Unpin + Send,
+{
+    Box::pin(async move {
+        let line = read_line(reader).await?;
+        let (kind, rest) = line
+            .split_at_checked(1)
+            .ok_or_else(|| invalid_data(format!("malformed reply line {:?}", line)))?;
+        match kind {
+            "+" => Ok(RespValue::Simple(rest.to_string())),
+            "-" => Ok(RespValue::Error(rest.to_string())),
+            ":" => Ok(RespValue::Integer(parse_length(rest)?)),
+            "$" => {
+                let len = parse_length(rest)?;
+                if len < 0 {
+                    return Ok(RespValue::Bulk(None));
+                }
+                let mut data = vec![0; len as usize + 2];
+                reader.read_exact(&mut data).await?;
+                data.truncate(len as usize);
+                Ok(RespValue::Bulk(Some(data)))
+            }
+            "*" => {
+                let len = parse_length(rest)?;
+                if len < 0 {
+                    return Ok(RespValue::Array(None));
+                }
+                let mut items = Vec::with_capacity(len as usize);
+                for _ in 0..len {
+                    items.push(read_value(reader).await?);
+                }
+                Ok(RespValue::Array(Some(items)))
+            }
+            _ => Err(invalid_data(format!("unexpected reply type {:?}", kind))),
+        }
+    })
+}
+
+async fn read_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> io::Result<String> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).await? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "connection closed by the server",
+        ));
+    }
+    let line = line.trim_end_matches(['\r', '\n']);
+    if line.is_empty() {
+        return Err(invalid_data("empty reply line".to_string()));
+    }
+    Ok(line.to_string())
+}
+
+fn parse_length(value: &str) -> io::Result<i64> {
+    value
+        .parse()
+        .map_err(|_| invalid_data(format!("invalid length {:?}", value)))
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
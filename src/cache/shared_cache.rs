@@ -0,0 +1,295 @@
+use crate::{
+    cache::{
+        StoredSlot,
+        resp::{RespValue, encode_command, read_value},
+    },
+    commitment::Commitment,
+    config::Config,
+};
+use std::{
+    io,
+    sync::Mutex as StdMutex,
+    time::{Duration, Instant},
+};
+use tokio::{
+    io::{AsyncWriteExt, BufStream},
+    net::TcpStream,
+    sync::Mutex,
+    time::timeout,
+};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone)]
+pub struct SharedCacheSettings {
+    /// `host:port` of the Redis-protocol server
+    pub address: String,
+    pub password: Option<String>,
+    pub database: Option<u32>,
+    /// Prepended to every key, so several deployments can share a server
+    pub key_prefix: String,
+    /// Lifetime of entries below finalized, which may still change
+    pub ttl: Duration,
+    /// Upper bound for connecting and for every round trip
+    pub timeout: Duration,
+    /// How long the tier is bypassed after a failure before it is tried again
+    pub retry_interval: Duration,
+}
+
+impl SharedCacheSettings {
+    /// Returns `None` when no `REDIS_URL` is configured, the URL itself is validated
+    /// with the configuration
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let (address, password, database) = parse_redis_url(config.redis_url.as_deref()?)?;
+        Some(Self {
+            address,
+            password,
+            database,
+            key_prefix: config.shared_cache_key_prefix.clone(),
+            ttl: config.shared_cache_ttl,
+            timeout: config.shared_cache_timeout,
+            retry_interval: config.shared_cache_retry_interval,
+        })
+    }
+}
+
+/// Splits `redis://[:password@]host[:port][/database]` into its parts
+pub fn parse_redis_url(url: &str) -> Option<(String, Option<String>, Option<u32>)> {
+    let rest = url.strip_prefix("redis://").unwrap_or(url);
+    let (credentials, rest) = match rest.rsplit_once('@') {
+        Some((credentials, rest)) => (Some(credentials), rest),
+        None => (None, rest),
+    };
+    let (host, database) = match rest.split_once('/') {
+        Some((host, "")) => (host, None),
+        Some((host, database)) => (host, Some(database.parse().ok()?)),
+        None => (rest, None),
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let address = if host.contains(':') {
+        host.to_string()
+    } else {
+        format!("{}:6379", host)
+    };
+    // The user part of `user:password` is ignored, AUTH with a password only
+    let password = credentials
+        .map(|credentials| credentials.rsplit(':').next().unwrap_or(credentials))
+        .filter(|password| !password.is_empty())
+        .map(str::to_string);
+    Some((address, password, database))
+}
+
+/// Cache tier shared by all replicas, kept in a server speaking the Redis protocol.
+///
+/// Lookups are answered after the in-process tiers and before RPC. Every operation is
+/// bounded by the configured timeout, and after a failure the tier is bypassed for the
+/// retry interval, so an unreachable server only costs cache misses.
+pub struct SharedCache {
+    settings: SharedCacheSettings,
+    connection: Mutex<Option<BufStream<TcpStream>>>,
+    bypass_until: StdMutex<Option<Instant>>,
+}
+
+impl SharedCache {
+    /// Connects lazily on the first operation
+    pub fn new(settings: SharedCacheSettings) -> Self {
+        info!(
+            "Using shared cache at {} with key prefix {:?}",
+            settings.address, settings.key_prefix
+        );
+        Self {
+            settings,
+            connection: Mutex::new(None),
+            bypass_until: StdMutex::new(None),
+        }
+    }
+
+    /// Whether the tier is currently used, `false` while it is bypassed after a failure
+    pub fn is_available(&self) -> bool {
+        self.bypass_until
+            .lock()
+            .unwrap()
+            .is_none_or(|until| Instant::now() >= until)
+    }
+
+    pub async fn get(&self, slot: u64) -> Option<StoredSlot> {
+        self.get_many(&[slot]).await.pop().flatten()
+    }
+
+    /// Looks the slots up in one round trip, answers are in input order
+    pub async fn get_many(&self, slots: &[u64]) -> Vec<Option<StoredSlot>> {
+        if slots.is_empty() {
+            return Vec::new();
+        }
+
+        let mut command = vec![b"MGET".to_vec()];
+        command.extend(slots.iter().map(|&slot| self.key(slot).into_bytes()));
+        let values = match self.pipeline(vec![command]).await {
+            Some(mut replies) => match replies.pop() {
+                Some(RespValue::Array(Some(values))) if values.len() == slots.len() => values,
+                _ => return vec![None; slots.len()],
+            },
+            None => return vec![None; slots.len()],
+        };
+
+        values
+            .into_iter()
+            .map(|value| match value {
+                RespValue::Bulk(Some(bytes)) => decode_value(&bytes),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Stores the observations with one pipelined round trip. Finalized ones are kept
+    /// for good and overwrite weaker entries, the others expire after the TTL and never
+    /// replace an existing entry. Returns whether the server took the writes.
+    pub async fn put_many(&self, observations: &[(u64, StoredSlot)]) -> bool {
+        if observations.is_empty() {
+            return true;
+        }
+
+        let ttl = self.settings.ttl.as_secs().max(1).to_string();
+        let commands = observations
+            .iter()
+            .map(|&(slot, stored)| {
+                let mut command = vec![
+                    b"SET".to_vec(),
+                    self.key(slot).into_bytes(),
+                    encode_value(stored).into_bytes(),
+                ];
+                if stored.commitment() < Commitment::Finalized {
+                    command.extend([b"EX".to_vec(), ttl.clone().into_bytes(), b"NX".to_vec()]);
+                }
+                command
+            })
+            .collect();
+        self.pipeline(commands).await.is_some()
+    }
+
+    fn key(&self, slot: u64) -> String {
+        format!("{}:slot:{}", self.settings.key_prefix, slot)
+    }
+
+    // Sends the commands back to back and reads all replies, `None` if the tier is bypassed
+    // or the round trip failed
+    async fn pipeline(&self, commands: Vec<Vec<Vec<u8>>>) -> Option<Vec<RespValue>> {
+        if !self.is_available() {
+            return None;
+        }
+
+        // Waiting for the connection counts against the timeout, so requests queued behind
+        // a hung round trip give up together instead of one after another
+        let attempt = async {
+            let mut connection = self.connection.lock().await;
+            // Checked again, the round trip this one waited for may have failed
+            if !self.is_available() {
+                return None;
+            }
+            // The stream may hold a partial reply after a failed or timed out round trip,
+            // it only goes back once the replies were read in full
+            let mut stream = connection.take();
+            let result = self.round_trip(&mut stream, &commands).await;
+            if result.is_ok() {
+                *connection = stream;
+            }
+            Some(result)
+        };
+        let result = match timeout(self.settings.timeout, attempt).await {
+            Ok(None) => return None,
+            Ok(Some(result)) => result,
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "shared cache timed out",
+            )),
+        };
+
+        match result {
+            Ok(replies) => Some(replies),
+            Err(e) => {
+                *self.bypass_until.lock().unwrap() =
+                    Some(Instant::now() + self.settings.retry_interval);
+                warn!(
+                    "Shared cache at {} is unavailable, bypassing it for {:?}: {}",
+                    self.settings.address, self.settings.retry_interval, e
+                );
+                None
+            }
+        }
+    }
+
+    async fn round_trip(
+        &self,
+        connection: &mut Option<BufStream<TcpStream>>,
+        commands: &[Vec<Vec<u8>>],
+    ) -> io::Result<Vec<RespValue>> {
+        if connection.is_none() {
+            *connection = Some(self.connect().await?);
+        }
+        let stream = connection
+            .as_mut()
+            .expect("connection was just established");
+
+        let mut buffer = Vec::new();
+        for command in commands {
+            encode_command(command, &mut buffer);
+        }
+        stream.write_all(&buffer).await?;
+        stream.flush().await?;
+
+        let mut replies = Vec::with_capacity(commands.len());
+        for _ in commands {
+            replies.push(read_value(stream).await?);
+        }
+        Ok(replies)
+    }
+
+    async fn connect(&self) -> io::Result<BufStream<TcpStream>> {
+        let mut stream = BufStream::new(TcpStream::connect(&self.settings.address).await?);
+
+        let mut handshake = Vec::new();
+        if let Some(password) = &self.settings.password {
+            handshake.push(vec![b"AUTH".to_vec(), password.clone().into_bytes()]);
+        }
+        if let Some(database) = self.settings.database {
+            handshake.push(vec![b"SELECT".to_vec(), database.to_string().into_bytes()]);
+        }
+        if !handshake.is_empty() {
+            let mut buffer = Vec::new();
+            for command in &handshake {
+                encode_command(command, &mut buffer);
+            }
+            stream.write_all(&buffer).await?;
+            stream.flush().await?;
+            for _ in &handshake {
+                if let RespValue::Error(message) = read_value(&mut stream).await? {
+                    return Err(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        format!("shared cache handshake failed: {}", message),
+                    ));
+                }
+            }
+        }
+        info!("Connected to shared cache at {}", self.settings.address);
+        Ok(stream)
+    }
+}
+
+fn encode_value(stored: StoredSlot) -> String {
+    match stored {
+        StoredSlot::Confirmed(commitment) => format!("confirmed:{}", commitment),
+        StoredSlot::Skipped(commitment) => format!("skipped:{}", commitment),
+    }
+}
+
+fn decode_value(bytes: &[u8]) -> Option<StoredSlot> {
+    let value = std::str::from_utf8(bytes).ok()?;
+    let (kind, commitment) = value.split_once(':')?;
+    let commitment = commitment.parse().ok()?;
+    match kind {
+        "confirmed" => Some(StoredSlot::Confirmed(commitment)),
+        "skipped" => Some(StoredSlot::Skipped(commitment)),
+        _ => None,
+    }
+}
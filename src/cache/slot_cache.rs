@@ -1,13 +1,21 @@
+use crate::commitment::Commitment;
+use crate::slot_info::SlotInfo;
 use scc::HashMap;
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::RwLock;
 use tracing::trace;
 
 #[derive(Debug)]
 pub struct SlotCache {
-    slots: HashMap<u64, ()>,
+    slots: HashMap<u64, SlotInfo>,
     order: RwLock<VecDeque<u64>>,
-    capacity: usize,
+    // Slots known to be skipped, with the commitment at which they were observed missing
+    skipped: HashMap<u64, Commitment>,
+    skipped_order: RwLock<VecDeque<u64>>,
+    // Highest slot recorded at each commitment level
+    latest: RwLock<BTreeMap<Commitment, u64>>,
+    capacity: AtomicUsize,
 }
 
 impl SlotCache {
@@ -15,33 +23,268 @@ impl SlotCache {
         Self {
             slots: HashMap::new(),
             order: RwLock::new(VecDeque::with_capacity(capacity)),
-            capacity,
+            skipped: HashMap::new(),
+            skipped_order: RwLock::new(VecDeque::new()),
+            latest: RwLock::new(BTreeMap::new()),
+            capacity: AtomicUsize::new(capacity),
         }
     }
 
+    pub fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::SeqCst)
+    }
+
+    /// Changes the capacity, evicting the oldest slots beyond it.
+    /// Returns the number of evicted slots with a block.
+    pub async fn resize(&self, capacity: usize) -> usize {
+        self.capacity.store(capacity, Ordering::SeqCst);
+
+        let mut order = self.order.write().await;
+        let evicted = order.len().saturating_sub(capacity);
+        for slot in order.drain(..evicted) {
+            self.slots.remove(&slot);
+        }
+        drop(order);
+
+        let mut skipped_order = self.skipped_order.write().await;
+        let evicted_skips = skipped_order.len().saturating_sub(capacity);
+        for slot in skipped_order.drain(..evicted_skips) {
+            self.skipped.remove(&slot);
+        }
+        evicted
+    }
+
+    /// Drops every cached slot, returns the number of slots with a block that were dropped
+    pub async fn clear(&self) -> usize {
+        let mut order = self.order.write().await;
+        let mut skipped_order = self.skipped_order.write().await;
+        let cleared = order.len();
+        order.clear();
+        skipped_order.clear();
+        self.slots.clear();
+        self.skipped.clear();
+        self.latest.write().await.clear();
+        cleared
+    }
+
+    /// Forgets the slot, block or skip observation, returns false if it was not cached
+    pub async fn remove(&self, slot: &u64) -> bool {
+        // Under the order locks, so a concurrent insert of the slot cannot end up in only one
+        // of the map and its order
+        let mut order = self.order.write().await;
+        let had_block = self.slots.remove(slot).is_some();
+        if had_block {
+            order.retain(|cached| cached != slot);
+        }
+        drop(order);
+
+        let mut skipped_order = self.skipped_order.write().await;
+        let had_skip = self.skipped.remove(slot).is_some();
+        if had_skip {
+            skipped_order.retain(|cached| cached != slot);
+        }
+        had_block || had_skip
+    }
+
+    /// Number of cached slots with a block, skipped slots are not counted
+    pub async fn len(&self) -> usize {
+        self.order.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
     pub async fn contains(&self, slot: &u64) -> bool {
-        self.slots.contains(slot)
+        self.contains_at(slot, Commitment::Confirmed).await
+    }
+
+    /// Whether the slot is cached with at least the given commitment
+    pub async fn contains_at(&self, slot: &u64, commitment: Commitment) -> bool {
+        self.get_commitment(slot)
+            .await
+            .is_some_and(|cached| cached >= commitment)
+    }
+
+    pub async fn get_commitment(&self, slot: &u64) -> Option<Commitment> {
+        self.slots.read(slot, |_, info| info.commitment)
+    }
+
+    pub async fn get_info(&self, slot: &u64) -> Option<SlotInfo> {
+        self.slots.read(slot, |_, info| info.clone())
+    }
+
+    /// Whether the slot is known to be skipped at the given commitment or a stronger one
+    pub async fn is_skipped_at(&self, slot: &u64, commitment: Commitment) -> bool {
+        self.skipped
+            .read(slot, |_, skipped| *skipped >= commitment)
+            .unwrap_or(false)
+    }
+
+    /// Inclusive ranges of the slots in `start..=end` that are neither cached nor known
+    /// to be skipped at the given commitment or a stronger one
+    pub async fn missing_ranges(
+        &self,
+        start: u64,
+        end: u64,
+        commitment: Commitment,
+    ) -> Vec<(u64, u64)> {
+        let mut ranges: Vec<(u64, u64)> = Vec::new();
+        for slot in start..=end {
+            if self.contains_at(&slot, commitment).await
+                || self.is_skipped_at(&slot, commitment).await
+            {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some((_, range_end)) if *range_end + 1 == slot => *range_end = slot,
+                _ => ranges.push((slot, slot)),
+            }
+        }
+        ranges
     }
 
     pub async fn get_latest_cached_slot(&self) -> Option<u64> {
-        self.order.read().await.back().cloned()
+        self.get_latest_slot_at(Commitment::Confirmed).await
+    }
+
+    /// Highest cached slot that reached at least the given commitment
+    pub async fn get_latest_slot_at(&self, commitment: Commitment) -> Option<u64> {
+        self.latest
+            .read()
+            .await
+            .range(commitment..)
+            .map(|(_, slot)| *slot)
+            .max()
     }
 
     pub async fn insert(&self, slot: u64) {
-        if self.slots.insert(slot, ()).is_ok() {
-            let mut order = self.order.write().await;
-            order.push_back(slot);
+        self.insert_with_commitment(slot, Commitment::Confirmed)
+            .await;
+    }
+
+    /// Inserts the slot, or upgrades its commitment if it is already cached at a lower level
+    pub async fn insert_with_commitment(&self, slot: u64, commitment: Commitment) {
+        self.insert_info(SlotInfo::new(slot, commitment)).await;
+    }
+
+    /// Inserts the slot record, or merges it into the cached one
+    pub async fn insert_info(&self, info: SlotInfo) {
+        let (slot, commitment) = (info.slot, info.commitment);
+        // A block seen at least as strongly as the skip observation means the fork changed
+        let mut skipped_order = self.skipped_order.write().await;
+        if self
+            .skipped
+            .remove_if(&slot, |skipped| *skipped <= commitment)
+            .is_some()
+        {
+            skipped_order.retain(|skipped| *skipped != slot);
+        }
+        drop(skipped_order);
+
+        let mut order = self.order.write().await;
+        match self.slots.insert(slot, info) {
+            Ok(()) => {
+                order.push_back(slot);
 
-            if order.len() > self.capacity {
-                if let Some(oldest_slot) = order.pop_front() {
+                if order.len() > self.capacity()
+                    && let Some(oldest_slot) = order.pop_front()
+                {
                     self.slots.remove(&oldest_slot);
                     trace!("Remove slot: {}", oldest_slot);
                 }
             }
+            Err((_, info)) => {
+                self.slots.update(&slot, |_, cached| cached.merge(info));
+            }
+        }
+        drop(order);
+
+        self.record_latest(slot, commitment).await;
+    }
+
+    /// Upgrades the commitment of an already cached slot, returns false if it is not cached
+    pub async fn upgrade(&self, slot: u64, commitment: Commitment) -> bool {
+        let updated = self
+            .slots
+            .update(&slot, |_, info| info.upgrade(commitment))
+            .is_some();
+
+        if updated {
+            self.record_latest(slot, commitment).await;
+        }
+        updated
+    }
+
+    /// Merges a newer observation into an already cached slot and returns the result,
+    /// `None` if the slot is not cached
+    pub async fn merge_info(&self, info: SlotInfo) -> Option<SlotInfo> {
+        let (slot, commitment) = (info.slot, info.commitment);
+        let merged = self.slots.update(&slot, |_, cached| {
+            cached.merge(info);
+            cached.clone()
+        })?;
+
+        self.record_latest(slot, commitment).await;
+        Some(merged)
+    }
+
+    /// Remembers that the slot has no block at the given commitment.
+    /// Ignored if the slot is already cached with a block at that level or a stronger one.
+    pub async fn insert_skipped(&self, slot: u64, commitment: Commitment) {
+        if self.contains_at(&slot, commitment).await {
+            return;
+        }
+
+        let mut skipped_order = self.skipped_order.write().await;
+        if self.skipped.insert(slot, commitment).is_ok() {
+            skipped_order.push_back(slot);
+
+            if skipped_order.len() > self.capacity()
+                && let Some(oldest_slot) = skipped_order.pop_front()
+            {
+                self.skipped.remove(&oldest_slot);
+                trace!("Remove skipped slot: {}", oldest_slot);
+            }
+        } else {
+            self.skipped.update(&slot, |_, skipped| {
+                if commitment > *skipped {
+                    *skipped = commitment;
+                }
+            });
         }
     }
 
+    /// Records of all cached slots, oldest first
+    pub async fn entries(&self) -> Vec<SlotInfo> {
+        let order = self.order.read().await;
+        order
+            .iter()
+            .filter_map(|slot| self.slots.read(slot, |_, info| info.clone()))
+            .collect()
+    }
+
+    /// Known skipped slots with the commitment they were observed at, oldest first
+    pub async fn skipped_entries(&self) -> Vec<(u64, Commitment)> {
+        let skipped_order = self.skipped_order.read().await;
+        skipped_order
+            .iter()
+            .filter_map(|slot| {
+                self.skipped
+                    .read(slot, |_, commitment| (*slot, *commitment))
+            })
+            .collect()
+    }
+
     pub async fn get_all_slots(&self) -> Vec<u64> {
         self.order.read().await.iter().cloned().collect()
     }
+
+    async fn record_latest(&self, slot: u64, commitment: Commitment) {
+        let mut latest = self.latest.write().await;
+        let entry = latest.entry(commitment).or_insert(slot);
+        if slot > *entry {
+            *entry = slot;
+        }
+    }
 }
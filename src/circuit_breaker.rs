@@ -1,25 +1,60 @@
+use serde::Serialize;
 use std::{
     error::Error,
     fmt,
     future::Future,
-    sync::atomic::{AtomicU32, Ordering},
+    sync::{
+        Mutex,
+        atomic::{AtomicU32, Ordering},
+    },
     time::{Duration, Instant},
 };
 use tokio::sync::RwLock;
-use tracing::warn;
+use tracing::{Span, field, instrument, warn};
 
 #[derive(Debug, Clone, Copy)]
 enum State {
     Closed,
     Open { until: Instant },
     HalfOpen,
+    // Opened by an operator, stays open until closed by one
+    ForcedOpen,
+}
+
+/// Externally visible state of the circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+impl From<State> for CircuitState {
+    fn from(state: State) -> Self {
+        match state {
+            State::Closed => CircuitState::Closed,
+            State::Open { .. } | State::ForcedOpen => CircuitState::Open,
+            State::HalfOpen => CircuitState::HalfOpen,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct CircuitBreaker {
     state: RwLock<State>,
-    failure_threshold: u32,
-    open_duration: Duration,
+    failure_threshold: AtomicU32,
+    open_duration: Mutex<Duration>,
     consecutive_failures: AtomicU32,
 }
 
@@ -51,34 +86,87 @@ impl CircuitBreaker {
     pub fn new(failure_threshold: u32, open_duration: Duration) -> Self {
         Self {
             state: RwLock::new(State::Closed),
-            failure_threshold,
-            open_duration,
+            failure_threshold: AtomicU32::new(failure_threshold),
+            open_duration: Mutex::new(open_duration),
             consecutive_failures: AtomicU32::new(0),
         }
     }
 
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold.load(Ordering::SeqCst)
+    }
+
+    pub fn open_duration(&self) -> Duration {
+        *self.open_duration.lock().unwrap()
+    }
+
+    /// Replaces the thresholds, failures counted so far and a running open period are kept
+    pub fn reconfigure(&self, failure_threshold: u32, open_duration: Duration) {
+        self.failure_threshold
+            .store(failure_threshold, Ordering::SeqCst);
+        *self.open_duration.lock().unwrap() = open_duration;
+    }
+
+    #[instrument(name = "circuit_breaker", skip_all, fields(breaker.state = field::Empty))]
     pub async fn execute<F, Fut, T, E>(&self, operation: F) -> Result<T, CircuitBreakerError<E>>
     where
         F: Fn() -> Fut,
         Fut: Future<Output = Result<T, E>>,
     {
-        match self.check_state().await {
+        let state = self.check_state().await;
+        Span::current().record("breaker.state", CircuitState::from(state).as_str());
+        match state {
             State::Closed | State::HalfOpen => {
                 let result = operation().await;
                 self.handle_result(&result).await;
                 result.map_err(CircuitBreakerError::Inner)
             }
-            State::Open { .. } => Err(CircuitBreakerError::CircuitOpen),
+            State::Open { .. } | State::ForcedOpen => Err(CircuitBreakerError::CircuitOpen),
+        }
+    }
+
+    /// Whether calls are currently rejected. Does not start the half-open probe, which is
+    /// left to the next call going through `execute`.
+    pub async fn is_open(&self) -> bool {
+        self.state().await == CircuitState::Open
+    }
+
+    /// Current state, an open circuit whose open duration has elapsed is reported as half-open
+    pub async fn state(&self) -> CircuitState {
+        match *self.state.read().await {
+            State::Closed => CircuitState::Closed,
+            State::ForcedOpen => CircuitState::Open,
+            State::Open { until } if Instant::now() < until => CircuitState::Open,
+            State::Open { .. } | State::HalfOpen => CircuitState::HalfOpen,
         }
     }
 
+    /// Whether the circuit was opened by `force_open` rather than by failures
+    pub async fn is_forced_open(&self) -> bool {
+        matches!(*self.state.read().await, State::ForcedOpen)
+    }
+
+    /// Rejects all calls until `force_closed` is called, regardless of the open duration
+    pub async fn force_open(&self) {
+        warn!("Circuit breaker forced to Open state.");
+        *self.state.write().await = State::ForcedOpen;
+    }
+
+    /// Closes the circuit and forgets the failures counted so far
+    pub async fn force_closed(&self) {
+        warn!("Circuit breaker forced to Closed state.");
+        let mut state = self.state.write().await;
+        *state = State::Closed;
+        self.consecutive_failures.store(0, Ordering::SeqCst);
+    }
+
     async fn check_state(&self) -> State {
         let mut state = self.state.write().await;
-        if let State::Open { until } = *state {
-            if Instant::now() >= until {
-                warn!("Circuit breaker transitioning to HalfOpen state.");
-                *state = State::HalfOpen;
-            }
+        if let State::Open { until } = *state
+            && Instant::now() >= until
+        {
+            warn!("Circuit breaker transitioning to HalfOpen state.");
+            *state = State::HalfOpen;
         }
         *state
     }
@@ -101,15 +189,15 @@ impl CircuitBreaker {
                 State::HalfOpen => {
                     warn!("Circuit breaker probe failed, transitioning back to Open.");
                     *state = State::Open {
-                        until: Instant::now() + self.open_duration,
+                        until: Instant::now() + self.open_duration(),
                     };
                 }
                 State::Closed => {
                     let failures = self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
-                    if failures >= self.failure_threshold {
+                    if failures >= self.failure_threshold() {
                         warn!("Failure threshold reached. Circuit breaker transitioning to Open.");
                         *state = State::Open {
-                            until: Instant::now() + self.open_duration,
+                            until: Instant::now() + self.open_duration(),
                         };
                     }
                 }
@@ -0,0 +1,97 @@
+use super::sources::{ConfigSources, accepted_keys};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use std::{ffi::OsString, path::PathBuf};
+
+/// Default `.env` file, read from the working directory when present
+const DEFAULT_ENV_FILE: &str = ".env";
+
+/// Command-line arguments of the service
+#[derive(Debug, Clone, PartialEq)]
+pub struct CliArgs {
+    pub sources: ConfigSources,
+    /// Print the effective configuration and exit instead of starting
+    pub check_config: bool,
+}
+
+impl CliArgs {
+    /// Parses the process arguments, printing help or the error and exiting on failure
+    pub fn parse() -> Self {
+        Self::from_matches(&command().get_matches())
+    }
+
+    pub fn try_parse_from<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        Ok(Self::from_matches(&command().try_get_matches_from(args)?))
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Self {
+        let overrides = accepted_keys()
+            .filter_map(|key| {
+                let value = matches.get_one::<String>(key)?;
+                Some((key.to_string(), value.clone()))
+            })
+            .collect();
+        let env_file = matches.get_one::<PathBuf>("env-file");
+        Self {
+            sources: ConfigSources {
+                config_file: matches.get_one::<PathBuf>("config").cloned(),
+                env_file: Some(
+                    env_file
+                        .cloned()
+                        .unwrap_or_else(|| PathBuf::from(DEFAULT_ENV_FILE)),
+                ),
+                env_file_required: env_file.is_some(),
+                use_environment: true,
+                overrides,
+            },
+            check_config: matches.get_flag("check-config"),
+        }
+    }
+}
+
+/// Flag overriding a key, e.g. `--cache-capacity` for CACHE_CAPACITY
+pub fn flag_name(key: &str) -> String {
+    key.to_ascii_lowercase().replace('_', "-")
+}
+
+fn command() -> Command {
+    let command = Command::new(env!("CARGO_PKG_NAME"))
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Caches confirmed Solana slots and answers confirmation queries")
+        .after_help(
+            "Settings are layered, each overriding the previous one: defaults, the --config TOML \
+             file, the .env file, environment variables and the flags below.",
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("TOML file with settings, keys as in .env or lowercase in tables"),
+        )
+        .arg(
+            Arg::new("env-file")
+                .long("env-file")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("The .env file to read [default: .env, skipped if missing]"),
+        )
+        .arg(
+            Arg::new("check-config")
+                .long("check-config")
+                .action(ArgAction::SetTrue)
+                .help("Validate and print the effective configuration with secrets redacted, then exit"),
+        );
+    accepted_keys().fold(command, |command, key| {
+        command.arg(
+            Arg::new(key)
+                .long(flag_name(key))
+                .value_name("VALUE")
+                .help(format!("Overrides {}", key))
+                .help_heading("Settings"),
+        )
+    })
+}
@@ -0,0 +1,767 @@
+pub mod cli;
+pub mod sources;
+
+use crate::cache::shared_cache::parse_redis_url;
+use crate::commitment::Commitment;
+use crate::logging::{LogFormat, parse_log_filter};
+use crate::metrics::MetricsBackend;
+use crate::rpc::upstream::ApiKeyAuth;
+use reqwest::header::{HeaderName, HeaderValue};
+use sources::{ConfigLayers, ConfigSources};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{Error, ErrorKind};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Keys whose values are never printed
+pub const SECRET_KEYS: &[&str] = &[
+    "API_KEY",
+    "ADMIN_TOKEN",
+    "SOLANA_RPC_FALLBACK_URLS",
+    "REDIS_URL",
+    "CLIENT_API_KEYS",
+];
+
+// Debug is implemented by hand, so a logged config never shows the secrets
+#[derive(Clone, PartialEq)]
+pub struct Config {
+    pub rpc_url: String,
+    pub api_key: String,
+    pub api_key_auth: ApiKeyAuth,
+    pub api_key_header: HeaderName,
+    pub rpc_fallback_urls: Vec<String>,
+    pub ws_url: Option<String>,
+    pub ws_reconnect_backoff: Duration,
+    pub poll_interval: Duration,
+    pub cache_capacity: usize,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub circuit_failure_threshold: u32,
+    pub circuit_open_duration: Duration,
+    pub lru_cache_capacity: usize,
+    pub poll_commitments: Vec<Commitment>,
+    pub fetch_block_metadata: bool,
+    pub poll_backfill_chunk_size: u64,
+    pub poll_backfill_concurrency: usize,
+    pub snapshot_path: Option<PathBuf>,
+    pub snapshot_interval: Duration,
+    pub disk_store_path: Option<PathBuf>,
+    pub disk_store_max_slots: usize,
+    pub redis_url: Option<String>,
+    pub shared_cache_key_prefix: String,
+    pub shared_cache_ttl: Duration,
+    pub shared_cache_timeout: Duration,
+    pub shared_cache_retry_interval: Duration,
+    pub backfill_chunk_size: u64,
+    pub backfill_max_rpc_per_sec: u32,
+    pub backfill_retry_backoff: Duration,
+    pub backfill_checkpoint_path: Option<PathBuf>,
+    pub metrics_backend: MetricsBackend,
+    pub health_max_poller_lag: u64,
+    pub health_max_poll_age: Duration,
+    pub health_fail_on_open_circuit: bool,
+    pub log_format: LogFormat,
+    pub log_filter: String,
+    pub log_sample_every: u64,
+    pub otlp_endpoint: Option<String>,
+    pub otel_service_name: String,
+    pub trace_sample_ratio: f64,
+    pub admin_token: Option<String>,
+    pub batch_window: Duration,
+    pub batch_max_size: usize,
+    pub batch_max_slot_gap: u64,
+    pub batch_max_range: u64,
+    pub bind_address: SocketAddr,
+    pub tls_cert_path: Option<PathBuf>,
+    pub tls_key_path: Option<PathBuf>,
+    pub tls_reload_interval: Duration,
+    pub unix_socket_path: Option<PathBuf>,
+    /// `(name, key)` pairs
+    pub client_api_keys: Vec<(String, String)>,
+    pub client_api_keys_file: Option<PathBuf>,
+    pub client_keys_reload_interval: Duration,
+    pub client_rate_limit_per_sec: u32,
+    pub client_request_quota: u64,
+    pub client_rpc_fallback_quota: u64,
+    pub client_quota_window: Duration,
+}
+
+impl Config {
+    /// Reads only the given `.env` file, see `load` for the layered sources
+    pub fn from_env_file(path: &str) -> Result<Self, Error> {
+        Ok(Self::load(&ConfigSources::from_env_file(path))?)
+    }
+
+    /// Reads and validates every source, reporting all problems at once
+    pub fn load(sources: &ConfigSources) -> Result<Self, ConfigError> {
+        Self::from_layers(&sources.load()?)
+    }
+
+    pub fn from_layers(layers: &ConfigLayers) -> Result<Self, ConfigError> {
+        let mut vars = Parser::new(layers);
+
+        let rpc_url = vars.required("SOLANA_RPC_URL");
+        let api_key = vars.required("API_KEY");
+
+        let api_key_auth = vars.parse_or("API_KEY_AUTH", ApiKeyAuth::default());
+
+        let api_key_header = vars.parse_or("API_KEY_HEADER", HeaderName::from_static("x-api-key"));
+
+        if api_key_auth != ApiKeyAuth::Url
+            && vars.all_valid(&["API_KEY", "API_KEY_AUTH"])
+            && HeaderValue::from_str(&api_key).is_err()
+        {
+            vars.invalid(
+                "API_KEY",
+                "contains characters not allowed in an HTTP header, use API_KEY_AUTH=url",
+            );
+        }
+
+        let rpc_fallback_urls = vars
+            .string("SOLANA_RPC_FALLBACK_URLS")
+            .map(|s| {
+                s.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let ws_url = vars.string("SOLANA_WS_URL");
+
+        let ws_reconnect_backoff =
+            Duration::from_millis(vars.parse_or("WS_RECONNECT_BACKOFF_MS", 500));
+
+        let poll_interval_seconds = vars.parse_or("POLL_INTERVAL_SECONDS", 15);
+        let poll_interval = Duration::from_secs(poll_interval_seconds);
+
+        let cache_capacity = vars.parse_or("CACHE_CAPACITY", 1000);
+
+        let max_retries = vars.parse_or("MAX_RETRIES", 3);
+
+        let initial_backoff_ms = vars.parse_or("INITIAL_BACKOFF_MS", 500);
+        let initial_backoff = Duration::from_millis(initial_backoff_ms);
+
+        // Calculate max possible retries
+        let max_retry_sleep_duration_ms: u64 = (0..max_retries)
+            .map(|i| initial_backoff_ms.saturating_mul(2_u64.saturating_pow(i)))
+            .fold(0, u64::saturating_add);
+
+        // Compare max possible retries with requested max retries
+        // If the requested is smaller of max possible, error is returned
+        if vars.all_valid(&["POLL_INTERVAL_SECONDS", "MAX_RETRIES", "INITIAL_BACKOFF_MS"])
+            && max_retry_sleep_duration_ms >= poll_interval.as_millis() as u64
+        {
+            vars.invalid(
+                "MAX_RETRIES",
+                "Max possible retries exceeds the polling interval: either increase POLL_INTERVAL_SECONDS or decrease MAX_RETRIES/ INITIAL_BACKOFF_MS",
+            );
+        }
+
+        let circuit_failure_threshold = vars.parse_or("CIRCUIT_FAILURE_THRESHOLD", 5);
+
+        let circuit_open_duration =
+            Duration::from_secs(vars.parse_or("CIRCUIT_OPEN_DURATION_SECS", 30));
+
+        let lru_cache_capacity = vars.parse_or("LRU_CACHE_CAPACITY", 100);
+
+        let poll_commitments = vars
+            .parse_with("POLL_COMMITMENTS", parse_poll_commitments)
+            .unwrap_or_else(|| vec![Commitment::Confirmed, Commitment::Finalized]);
+
+        let fetch_block_metadata = vars.parse_or("FETCH_BLOCK_METADATA", true);
+
+        let poll_backfill_chunk_size = vars.parse_or("POLL_BACKFILL_CHUNK_SLOTS", 1000);
+
+        let poll_backfill_concurrency = vars.parse_or("POLL_BACKFILL_CONCURRENCY", 4);
+
+        let snapshot_path = vars.string("SNAPSHOT_PATH").map(PathBuf::from);
+
+        let snapshot_interval = Duration::from_secs(vars.parse_or("SNAPSHOT_INTERVAL_SECS", 60));
+
+        let disk_store_path = vars.string("DISK_STORE_PATH").map(PathBuf::from);
+
+        let disk_store_max_slots = vars.parse_or("DISK_STORE_MAX_SLOTS", 1_000_000);
+        if disk_store_max_slots == 0 {
+            vars.invalid("DISK_STORE_MAX_SLOTS", "must be at least 1");
+        }
+
+        let redis_url = vars.string("REDIS_URL");
+        if redis_url
+            .as_deref()
+            .is_some_and(|url| parse_redis_url(url).is_none())
+        {
+            vars.invalid(
+                "REDIS_URL",
+                "must look like redis://[:password@]host[:port][/database]",
+            );
+        }
+
+        let shared_cache_key_prefix = vars
+            .string("SHARED_CACHE_KEY_PREFIX")
+            .unwrap_or_else(|| "solana-caching-service".to_string());
+
+        let shared_cache_ttl = Duration::from_secs(vars.parse_or("SHARED_CACHE_TTL_SECS", 60));
+
+        let shared_cache_timeout =
+            Duration::from_millis(vars.parse_or("SHARED_CACHE_TIMEOUT_MS", 50));
+
+        let shared_cache_retry_interval =
+            Duration::from_secs(vars.parse_or("SHARED_CACHE_RETRY_SECS", 5));
+
+        let backfill_chunk_size = vars.parse_or("BACKFILL_CHUNK_SLOTS", 1000);
+
+        let backfill_max_rpc_per_sec = vars.parse_or("BACKFILL_MAX_RPC_PER_SEC", 5);
+
+        let backfill_retry_backoff =
+            Duration::from_secs(vars.parse_or("BACKFILL_RETRY_BACKOFF_SECS", 5));
+
+        let backfill_checkpoint_path = vars.string("BACKFILL_CHECKPOINT_PATH").map(PathBuf::from);
+
+        let metrics_backend = vars.parse_or("METRICS_BACKEND", MetricsBackend::default());
+
+        let health_max_poller_lag = vars.parse_or("HEALTH_MAX_POLLER_LAG_SLOTS", 150);
+
+        let health_max_poll_age =
+            Duration::from_secs(vars.parse_or("HEALTH_MAX_POLL_AGE_SECS", 60));
+        if vars.all_valid(&["HEALTH_MAX_POLL_AGE_SECS", "POLL_INTERVAL_SECONDS"])
+            && health_max_poll_age <= poll_interval
+        {
+            vars.invalid(
+                "HEALTH_MAX_POLL_AGE_SECS",
+                "must exceed POLL_INTERVAL_SECONDS, or every instance would flap between ready and unready",
+            );
+        }
+
+        let health_fail_on_open_circuit = vars.parse_or("HEALTH_FAIL_ON_OPEN_CIRCUIT", false);
+
+        let log_format = vars.parse_or("LOG_FORMAT", LogFormat::default());
+
+        let log_filter = vars
+            .parse_with("LOG_FILTER", |directives| {
+                parse_log_filter(directives).map(|_| directives.to_string())
+            })
+            .unwrap_or_else(|| "info".to_string());
+
+        let log_sample_every = vars.parse_or("LOG_SAMPLE_EVERY", 10);
+
+        let otlp_endpoint = vars.string("OTEL_EXPORTER_OTLP_ENDPOINT");
+
+        let otel_service_name = vars
+            .string("OTEL_SERVICE_NAME")
+            .unwrap_or_else(|| "solana-caching-service".to_string());
+
+        let trace_sample_ratio = vars.parse_or("TRACE_SAMPLE_RATIO", 1.0);
+
+        let admin_token = vars.string("ADMIN_TOKEN");
+
+        let batch_window = Duration::from_millis(vars.parse_or("FALLBACK_BATCH_WINDOW_MS", 5));
+
+        let batch_max_size = vars.parse_or("FALLBACK_BATCH_MAX_SIZE", 256);
+
+        let batch_max_slot_gap = vars.parse_or("FALLBACK_BATCH_MAX_GAP", 100);
+
+        let batch_max_range = vars.parse_or("FALLBACK_BATCH_MAX_RANGE", 5000);
+
+        let bind_address = vars.parse_or("BIND_ADDRESS", SocketAddr::from(([127, 0, 0, 1], 8000)));
+
+        let tls_cert_path = vars.string("TLS_CERT_PATH").map(PathBuf::from);
+        let tls_key_path = vars.string("TLS_KEY_PATH").map(PathBuf::from);
+        match (&tls_cert_path, &tls_key_path) {
+            (Some(_), None) => {
+                vars.invalid("TLS_KEY_PATH", "must be set together with TLS_CERT_PATH")
+            }
+            (None, Some(_)) => {
+                vars.invalid("TLS_CERT_PATH", "must be set together with TLS_KEY_PATH")
+            }
+            _ => {}
+        }
+
+        // Security policy: plaintext is only served on loopback, anything else goes over TLS
+        if tls_cert_path.is_none()
+            && tls_key_path.is_none()
+            && vars.all_valid(&["BIND_ADDRESS"])
+            && !bind_address.ip().is_loopback()
+        {
+            vars.invalid(
+                "BIND_ADDRESS",
+                &format!(
+                    "plaintext listeners are only allowed on loopback addresses, set TLS_CERT_PATH and TLS_KEY_PATH to serve on {}",
+                    bind_address
+                ),
+            );
+        }
+
+        let tls_reload_interval =
+            Duration::from_secs(vars.parse_or("TLS_RELOAD_INTERVAL_SECS", 30));
+
+        let unix_socket_path = vars.string("UNIX_SOCKET_PATH").map(PathBuf::from);
+
+        let client_api_keys = vars
+            .parse_with("CLIENT_API_KEYS", parse_client_keys)
+            .unwrap_or_default();
+
+        let client_api_keys_file = vars.string("CLIENT_API_KEYS_FILE").map(PathBuf::from);
+
+        let client_keys_reload_interval =
+            Duration::from_secs(vars.parse_or("CLIENT_KEYS_RELOAD_SECS", 10));
+
+        let client_rate_limit_per_sec = vars.parse_or("CLIENT_RATE_LIMIT_PER_SEC", 0);
+
+        let client_request_quota = vars.parse_or("CLIENT_REQUEST_QUOTA", 0);
+
+        let client_rpc_fallback_quota = vars.parse_or("CLIENT_RPC_FALLBACK_QUOTA", 0);
+
+        let client_quota_window =
+            Duration::from_secs(vars.parse_or("CLIENT_QUOTA_WINDOW_SECS", 86400));
+        if client_quota_window.is_zero() {
+            vars.invalid("CLIENT_QUOTA_WINDOW_SECS", "must be at least 1");
+        }
+
+        vars.finish()?;
+        Ok(Config {
+            rpc_url,
+            api_key,
+            api_key_auth,
+            api_key_header,
+            rpc_fallback_urls,
+            ws_url,
+            ws_reconnect_backoff,
+            poll_interval,
+            cache_capacity,
+            max_retries,
+            initial_backoff,
+            circuit_failure_threshold,
+            circuit_open_duration,
+            lru_cache_capacity,
+            poll_commitments,
+            fetch_block_metadata,
+            poll_backfill_chunk_size,
+            poll_backfill_concurrency,
+            snapshot_path,
+            snapshot_interval,
+            disk_store_path,
+            disk_store_max_slots,
+            redis_url,
+            shared_cache_key_prefix,
+            shared_cache_ttl,
+            shared_cache_timeout,
+            shared_cache_retry_interval,
+            backfill_chunk_size,
+            backfill_max_rpc_per_sec,
+            backfill_retry_backoff,
+            backfill_checkpoint_path,
+            metrics_backend,
+            health_max_poller_lag,
+            health_max_poll_age,
+            health_fail_on_open_circuit,
+            log_format,
+            log_filter,
+            log_sample_every,
+            otlp_endpoint,
+            otel_service_name,
+            trace_sample_ratio,
+            admin_token,
+            batch_window,
+            batch_max_size,
+            batch_max_slot_gap,
+            batch_max_range,
+            bind_address,
+            tls_cert_path,
+            tls_key_path,
+            tls_reload_interval,
+            unix_socket_path,
+            client_api_keys,
+            client_api_keys_file,
+            client_keys_reload_interval,
+            client_rate_limit_per_sec,
+            client_request_quota,
+            client_rpc_fallback_quota,
+            client_quota_window,
+        })
+    }
+
+    /// Every setting by key, in the format it is configured with
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let secs = |d: &Duration| d.as_secs().to_string();
+        let millis = |d: &Duration| d.as_millis().to_string();
+        let optional = |value: &Option<String>| value.clone().unwrap_or_default();
+        let path = |value: &Option<PathBuf>| {
+            value
+                .as_deref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        };
+        vec![
+            ("SOLANA_RPC_URL", self.rpc_url.clone()),
+            ("API_KEY", self.api_key.clone()),
+            ("API_KEY_AUTH", self.api_key_auth.to_string()),
+            ("API_KEY_HEADER", self.api_key_header.to_string()),
+            ("SOLANA_RPC_FALLBACK_URLS", self.rpc_fallback_urls.join(",")),
+            ("SOLANA_WS_URL", optional(&self.ws_url)),
+            (
+                "WS_RECONNECT_BACKOFF_MS",
+                millis(&self.ws_reconnect_backoff),
+            ),
+            ("POLL_INTERVAL_SECONDS", secs(&self.poll_interval)),
+            ("CACHE_CAPACITY", self.cache_capacity.to_string()),
+            ("MAX_RETRIES", self.max_retries.to_string()),
+            ("INITIAL_BACKOFF_MS", millis(&self.initial_backoff)),
+            (
+                "CIRCUIT_FAILURE_THRESHOLD",
+                self.circuit_failure_threshold.to_string(),
+            ),
+            (
+                "CIRCUIT_OPEN_DURATION_SECS",
+                secs(&self.circuit_open_duration),
+            ),
+            ("LRU_CACHE_CAPACITY", self.lru_cache_capacity.to_string()),
+            (
+                "POLL_COMMITMENTS",
+                self.poll_commitments
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            (
+                "FETCH_BLOCK_METADATA",
+                self.fetch_block_metadata.to_string(),
+            ),
+            (
+                "POLL_BACKFILL_CHUNK_SLOTS",
+                self.poll_backfill_chunk_size.to_string(),
+            ),
+            (
+                "POLL_BACKFILL_CONCURRENCY",
+                self.poll_backfill_concurrency.to_string(),
+            ),
+            ("SNAPSHOT_PATH", path(&self.snapshot_path)),
+            ("SNAPSHOT_INTERVAL_SECS", secs(&self.snapshot_interval)),
+            ("DISK_STORE_PATH", path(&self.disk_store_path)),
+            (
+                "DISK_STORE_MAX_SLOTS",
+                self.disk_store_max_slots.to_string(),
+            ),
+            ("REDIS_URL", optional(&self.redis_url)),
+            (
+                "SHARED_CACHE_KEY_PREFIX",
+                self.shared_cache_key_prefix.clone(),
+            ),
+            ("SHARED_CACHE_TTL_SECS", secs(&self.shared_cache_ttl)),
+            (
+                "SHARED_CACHE_TIMEOUT_MS",
+                millis(&self.shared_cache_timeout),
+            ),
+            (
+                "SHARED_CACHE_RETRY_SECS",
+                secs(&self.shared_cache_retry_interval),
+            ),
+            ("BACKFILL_CHUNK_SLOTS", self.backfill_chunk_size.to_string()),
+            (
+                "BACKFILL_MAX_RPC_PER_SEC",
+                self.backfill_max_rpc_per_sec.to_string(),
+            ),
+            (
+                "BACKFILL_RETRY_BACKOFF_SECS",
+                secs(&self.backfill_retry_backoff),
+            ),
+            (
+                "BACKFILL_CHECKPOINT_PATH",
+                path(&self.backfill_checkpoint_path),
+            ),
+            ("METRICS_BACKEND", self.metrics_backend.to_string()),
+            (
+                "HEALTH_MAX_POLLER_LAG_SLOTS",
+                self.health_max_poller_lag.to_string(),
+            ),
+            ("HEALTH_MAX_POLL_AGE_SECS", secs(&self.health_max_poll_age)),
+            (
+                "HEALTH_FAIL_ON_OPEN_CIRCUIT",
+                self.health_fail_on_open_circuit.to_string(),
+            ),
+            ("LOG_FORMAT", self.log_format.to_string()),
+            ("LOG_FILTER", self.log_filter.clone()),
+            ("LOG_SAMPLE_EVERY", self.log_sample_every.to_string()),
+            ("OTEL_EXPORTER_OTLP_ENDPOINT", optional(&self.otlp_endpoint)),
+            ("OTEL_SERVICE_NAME", self.otel_service_name.clone()),
+            ("TRACE_SAMPLE_RATIO", self.trace_sample_ratio.to_string()),
+            ("ADMIN_TOKEN", optional(&self.admin_token)),
+            ("FALLBACK_BATCH_WINDOW_MS", millis(&self.batch_window)),
+            ("FALLBACK_BATCH_MAX_SIZE", self.batch_max_size.to_string()),
+            (
+                "FALLBACK_BATCH_MAX_GAP",
+                self.batch_max_slot_gap.to_string(),
+            ),
+            ("FALLBACK_BATCH_MAX_RANGE", self.batch_max_range.to_string()),
+            ("BIND_ADDRESS", self.bind_address.to_string()),
+            ("TLS_CERT_PATH", path(&self.tls_cert_path)),
+            ("TLS_KEY_PATH", path(&self.tls_key_path)),
+            ("TLS_RELOAD_INTERVAL_SECS", secs(&self.tls_reload_interval)),
+            ("UNIX_SOCKET_PATH", path(&self.unix_socket_path)),
+            (
+                "CLIENT_API_KEYS",
+                self.client_api_keys
+                    .iter()
+                    .map(|(name, key)| format!("{}:{}", name, key))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            ("CLIENT_API_KEYS_FILE", path(&self.client_api_keys_file)),
+            (
+                "CLIENT_KEYS_RELOAD_SECS",
+                secs(&self.client_keys_reload_interval),
+            ),
+            (
+                "CLIENT_RATE_LIMIT_PER_SEC",
+                self.client_rate_limit_per_sec.to_string(),
+            ),
+            (
+                "CLIENT_REQUEST_QUOTA",
+                self.client_request_quota.to_string(),
+            ),
+            (
+                "CLIENT_RPC_FALLBACK_QUOTA",
+                self.client_rpc_fallback_quota.to_string(),
+            ),
+            ("CLIENT_QUOTA_WINDOW_SECS", secs(&self.client_quota_window)),
+        ]
+    }
+
+    /// Like `settings`, with the values of `SECRET_KEYS` replaced. An unset secret stays empty.
+    pub fn redacted_settings(&self) -> Vec<(&'static str, String)> {
+        self.settings()
+            .into_iter()
+            .map(|(key, value)| {
+                if SECRET_KEYS.contains(&key) && !value.is_empty() {
+                    (key, "<redacted>".to_string())
+                } else {
+                    (key, value)
+                }
+            })
+            .collect()
+    }
+
+    /// Effective configuration as `KEY=VALUE` lines with the layer each value came from,
+    /// secrets redacted
+    pub fn describe(&self, layers: &ConfigLayers) -> String {
+        self.redacted_settings()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}  # {}\n", key, value, layers.origin(key)))
+            .collect()
+    }
+}
+
+impl fmt::Debug for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut config = f.debug_struct("Config");
+        for (key, value) in self.redacted_settings() {
+            config.field(key, &value);
+        }
+        config.finish()
+    }
+}
+
+/// A single invalid, missing or unknown setting
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub key: String,
+    pub message: String,
+    pub kind: ErrorKind,
+}
+
+impl ConfigProblem {
+    pub fn invalid(key: &str, message: String) -> Self {
+        Self {
+            key: key.to_string(),
+            message,
+            kind: ErrorKind::InvalidInput,
+        }
+    }
+
+    fn missing(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            message: "is not set".to_string(),
+            kind: ErrorKind::NotFound,
+        }
+    }
+
+    fn unreadable(path: &Path, e: Error) -> Self {
+        Self {
+            key: path.display().to_string(),
+            message: e.to_string(),
+            kind: e.kind(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)
+    }
+}
+
+/// Every problem found while loading the configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl ConfigError {
+    pub fn new(problems: Vec<ConfigProblem>) -> Self {
+        Self { problems }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Config error: {} invalid setting(s)",
+            self.problems.len()
+        )?;
+        for problem in &self.problems {
+            write!(f, "\n  {}", problem)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<ConfigError> for Error {
+    fn from(e: ConfigError) -> Self {
+        let kind = match e.problems.first() {
+            Some(first) if e.problems.iter().all(|p| p.kind == first.kind) => first.kind,
+            _ => ErrorKind::InvalidInput,
+        };
+        Error::new(kind, e)
+    }
+}
+
+// Reads values off the layers, collecting a problem for every invalid one instead of
+// stopping at the first. Invalid values fall back to the default so parsing can go on.
+struct Parser<'a> {
+    layers: &'a ConfigLayers,
+    problems: Vec<ConfigProblem>,
+    invalid_keys: HashSet<&'static str>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(layers: &'a ConfigLayers) -> Self {
+        Self {
+            layers,
+            problems: Vec::new(),
+            invalid_keys: HashSet::new(),
+        }
+    }
+
+    fn required(&mut self, key: &'static str) -> String {
+        self.string(key).unwrap_or_else(|| {
+            self.invalid_keys.insert(key);
+            self.problems.push(ConfigProblem::missing(key));
+            String::new()
+        })
+    }
+
+    fn string(&self, key: &'static str) -> Option<String> {
+        self.layers.get(key).map(|(value, _)| value.to_string())
+    }
+
+    fn parse_or<T>(&mut self, key: &'static str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.parse_with(key, |value| value.parse::<T>().map_err(|e| e.to_string()))
+            .unwrap_or(default)
+    }
+
+    // `None` when the key is unset or its value invalid
+    fn parse_with<T>(
+        &mut self,
+        key: &'static str,
+        parse: impl FnOnce(&str) -> Result<T, String>,
+    ) -> Option<T> {
+        let (value, origin) = self.layers.get(key)?;
+        match parse(value) {
+            Ok(parsed) => Some(parsed),
+            Err(e) => {
+                let shown = if SECRET_KEYS.contains(&key) {
+                    "<redacted>"
+                } else {
+                    value
+                };
+                self.invalid(
+                    key,
+                    &format!("invalid value '{}' from {}: {}", shown, origin, e),
+                );
+                None
+            }
+        }
+    }
+
+    fn invalid(&mut self, key: &'static str, message: &str) {
+        self.invalid_keys.insert(key);
+        self.problems
+            .push(ConfigProblem::invalid(key, message.to_string()));
+    }
+
+    // Cross-key checks only run on valid values, a bad value is reported once
+    fn all_valid(&self, keys: &[&str]) -> bool {
+        keys.iter().all(|key| !self.invalid_keys.contains(key))
+    }
+
+    fn finish(self) -> Result<(), ConfigError> {
+        if self.problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::new(self.problems))
+        }
+    }
+}
+
+// getBlocks only answers at confirmed or finalized commitment,
+// so those are the only levels the poller can track
+fn parse_poll_commitments(value: &str) -> Result<Vec<Commitment>, String> {
+    let mut commitments = Vec::new();
+    for item in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let commitment: Commitment = item.parse()?;
+        if commitment == Commitment::Processed {
+            return Err("processed is not supported, use confirmed and/or finalized".to_string());
+        }
+        if !commitments.contains(&commitment) {
+            commitments.push(commitment);
+        }
+    }
+
+    if commitments.is_empty() {
+        return Err("must list at least one commitment level".to_string());
+    }
+    commitments.sort();
+    Ok(commitments)
+}
+
+// Comma-separated `name:key` pairs. Errors name the entry by position, never the key.
+fn parse_client_keys(value: &str) -> Result<Vec<(String, String)>, String> {
+    let mut keys: Vec<(String, String)> = Vec::new();
+    for (i, entry) in value.split(',').map(str::trim).enumerate() {
+        let Some((name, key)) = entry
+            .split_once(':')
+            .map(|(name, key)| (name.trim(), key.trim()))
+            .filter(|(name, key)| !name.is_empty() && !key.is_empty())
+        else {
+            return Err(format!("entry {} is not NAME:KEY", i + 1));
+        };
+        if keys.iter().any(|(existing, _)| existing == name) {
+            return Err(format!("duplicate client name '{}'", name));
+        }
+        keys.push((name.to_string(), key.to_string()));
+    }
+    Ok(keys)
+}
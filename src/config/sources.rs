@@ -0,0 +1,314 @@
+use super::{ConfigError, ConfigProblem};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt, fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// Every setting the service reads, under its `.env` and environment variable name
+pub const KEYS: &[&str] = &[
+    "SOLANA_RPC_URL",
+    "API_KEY",
+    "API_KEY_AUTH",
+    "API_KEY_HEADER",
+    "SOLANA_RPC_FALLBACK_URLS",
+    "SOLANA_WS_URL",
+    "WS_RECONNECT_BACKOFF_MS",
+    "POLL_INTERVAL_SECONDS",
+    "CACHE_CAPACITY",
+    "MAX_RETRIES",
+    "INITIAL_BACKOFF_MS",
+    "CIRCUIT_FAILURE_THRESHOLD",
+    "CIRCUIT_OPEN_DURATION_SECS",
+    "LRU_CACHE_CAPACITY",
+    "POLL_COMMITMENTS",
+    "FETCH_BLOCK_METADATA",
+    "POLL_BACKFILL_CHUNK_SLOTS",
+    "POLL_BACKFILL_CONCURRENCY",
+    "SNAPSHOT_PATH",
+    "SNAPSHOT_INTERVAL_SECS",
+    "DISK_STORE_PATH",
+    "DISK_STORE_MAX_SLOTS",
+    "REDIS_URL",
+    "SHARED_CACHE_KEY_PREFIX",
+    "SHARED_CACHE_TTL_SECS",
+    "SHARED_CACHE_TIMEOUT_MS",
+    "SHARED_CACHE_RETRY_SECS",
+    "BACKFILL_CHUNK_SLOTS",
+    "BACKFILL_MAX_RPC_PER_SEC",
+    "BACKFILL_RETRY_BACKOFF_SECS",
+    "BACKFILL_CHECKPOINT_PATH",
+    "METRICS_BACKEND",
+    "HEALTH_MAX_POLLER_LAG_SLOTS",
+    "HEALTH_MAX_POLL_AGE_SECS",
+    "HEALTH_FAIL_ON_OPEN_CIRCUIT",
+    "LOG_FORMAT",
+    "LOG_FILTER",
+    "LOG_SAMPLE_EVERY",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+    "OTEL_SERVICE_NAME",
+    "TRACE_SAMPLE_RATIO",
+    "ADMIN_TOKEN",
+    "FALLBACK_BATCH_WINDOW_MS",
+    "FALLBACK_BATCH_MAX_SIZE",
+    "FALLBACK_BATCH_MAX_GAP",
+    "FALLBACK_BATCH_MAX_RANGE",
+    "BIND_ADDRESS",
+    "TLS_CERT_PATH",
+    "TLS_KEY_PATH",
+    "TLS_RELOAD_INTERVAL_SECS",
+    "UNIX_SOCKET_PATH",
+    "CLIENT_API_KEYS",
+    "CLIENT_API_KEYS_FILE",
+    "CLIENT_KEYS_RELOAD_SECS",
+    "CLIENT_RATE_LIMIT_PER_SEC",
+    "CLIENT_REQUEST_QUOTA",
+    "CLIENT_RPC_FALLBACK_QUOTA",
+    "CLIENT_QUOTA_WINDOW_SECS",
+];
+
+/// Keys naming a file to read a secret from instead, e.g. a mounted Docker or Kubernetes
+/// secret, paired with the key the content is used for. Surrounding whitespace is trimmed.
+pub const SECRET_FILE_KEYS: &[(&str, &str)] = &[
+    ("API_KEY_FILE", "API_KEY"),
+    ("ADMIN_TOKEN_FILE", "ADMIN_TOKEN"),
+    ("REDIS_URL_FILE", "REDIS_URL"),
+    ("SOLANA_RPC_FALLBACK_URLS_FILE", "SOLANA_RPC_FALLBACK_URLS"),
+];
+
+/// Every key accepted by the sources: the settings and the secret files
+pub fn accepted_keys() -> impl Iterator<Item = &'static str> {
+    KEYS.iter()
+        .copied()
+        .chain(SECRET_FILE_KEYS.iter().map(|&(file_key, _)| file_key))
+}
+
+/// Layer a setting was taken from, from lowest to highest precedence
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Origin {
+    Default,
+    ConfigFile,
+    EnvFile,
+    Environment,
+    CommandLine,
+    /// Read from the file named by its `_FILE` key, which takes the precedence of the
+    /// layer that key was set in
+    SecretFile,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::Default => f.write_str("default"),
+            Origin::ConfigFile => f.write_str("config file"),
+            Origin::EnvFile => f.write_str(".env file"),
+            Origin::Environment => f.write_str("environment"),
+            Origin::CommandLine => f.write_str("command line"),
+            Origin::SecretFile => f.write_str("secret file"),
+        }
+    }
+}
+
+/// Where the configuration is read from, kept so it can be read again on reload
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ConfigSources {
+    /// TOML file, an error if set but missing
+    pub config_file: Option<PathBuf>,
+    /// `.env` file, skipped if missing unless required
+    pub env_file: Option<PathBuf>,
+    pub env_file_required: bool,
+    /// Whether process environment variables override the files
+    pub use_environment: bool,
+    /// Values given as command-line flags, by key
+    pub overrides: BTreeMap<String, String>,
+}
+
+impl ConfigSources {
+    /// Only the given `.env` file, which must exist
+    pub fn from_env_file(path: impl Into<PathBuf>) -> Self {
+        Self {
+            env_file: Some(path.into()),
+            env_file_required: true,
+            ..Self::default()
+        }
+    }
+
+    /// Reads every layer, reporting unreadable files and unknown keys all at once
+    pub fn load(&self) -> Result<ConfigLayers, ConfigError> {
+        let mut problems = Vec::new();
+        let mut layers = ConfigLayers::default();
+
+        if let Some(path) = &self.config_file {
+            match read_toml(path) {
+                Ok(vars) => layers.insert(Origin::ConfigFile, vars, &mut problems),
+                Err(problem) => problems.push(problem),
+            }
+        }
+        if let Some(path) = &self.env_file {
+            match fs::read_to_string(path) {
+                Ok(content) => {
+                    layers.insert(Origin::EnvFile, parse_dotenv(&content), &mut problems)
+                }
+                Err(e) if e.kind() == ErrorKind::NotFound && !self.env_file_required => {}
+                Err(e) => problems.push(ConfigProblem::unreadable(path, e)),
+            }
+        }
+        if self.use_environment {
+            let vars = accepted_keys()
+                .filter_map(|key| Some((key.to_string(), std::env::var(key).ok()?)))
+                .collect();
+            layers.insert(Origin::Environment, vars, &mut problems);
+        }
+        layers.insert(
+            Origin::CommandLine,
+            self.overrides.clone().into_iter().collect(),
+            &mut problems,
+        );
+        layers.read_secret_files(&mut problems);
+
+        if problems.is_empty() {
+            Ok(layers)
+        } else {
+            Err(ConfigError::new(problems))
+        }
+    }
+}
+
+/// Raw values of every layer, looked up from the highest precedence down
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLayers {
+    values: HashMap<String, (String, Origin)>,
+}
+
+impl ConfigLayers {
+    /// Value of the key and the layer it came from, empty values count as unset
+    pub fn get(&self, key: &str) -> Option<(&str, Origin)> {
+        self.values
+            .get(key)
+            .filter(|(value, _)| !value.is_empty())
+            .map(|(value, origin)| (value.as_str(), *origin))
+    }
+
+    /// Layer the effective value of the key came from
+    pub fn origin(&self, key: &str) -> Origin {
+        self.get(key).map_or(Origin::Default, |(_, origin)| origin)
+    }
+
+    // Layers are inserted from the lowest precedence up, so later ones win
+    fn insert(
+        &mut self,
+        origin: Origin,
+        vars: HashMap<String, String>,
+        problems: &mut Vec<ConfigProblem>,
+    ) {
+        for (key, value) in vars {
+            if accepted_keys().any(|known| known == key) {
+                self.values.insert(key, (value, origin));
+            } else {
+                problems.push(ConfigProblem::invalid(
+                    &key,
+                    format!("unknown key in the {}", origin),
+                ));
+            }
+        }
+    }
+
+    // A secret file replaces a value set in a lower layer and is ignored below a value set in
+    // a higher one, so e.g. a secret mounted through the environment overrides the `.env` file
+    fn read_secret_files(&mut self, problems: &mut Vec<ConfigProblem>) {
+        for &(file_key, key) in SECRET_FILE_KEYS {
+            let Some((path, file_origin)) = self.get(file_key) else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            match self.get(key) {
+                Some((_, origin)) if origin > file_origin => continue,
+                Some((_, origin)) if origin == file_origin => {
+                    problems.push(ConfigProblem::invalid(
+                        key,
+                        format!("is set together with {} in the {}", file_key, origin),
+                    ));
+                    continue;
+                }
+                _ => {}
+            }
+            match fs::read_to_string(&path) {
+                Ok(content) if content.trim().is_empty() => problems.push(ConfigProblem::invalid(
+                    file_key,
+                    format!("{} is empty", path.display()),
+                )),
+                Ok(content) => {
+                    self.values.insert(
+                        key.to_string(),
+                        (content.trim().to_string(), Origin::SecretFile),
+                    );
+                }
+                Err(e) => problems.push(ConfigProblem::unreadable(&path, e)),
+            }
+        }
+    }
+}
+
+/// Parses `KEY=VALUE` lines, skipping blank lines and `#` comments
+pub fn parse_dotenv(content: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        if let Some((key, value)) = line.split_once('=') {
+            vars.insert(key.trim().to_string(), value.trim().to_string());
+        }
+    }
+    vars
+}
+
+// Keys are matched case-insensitively and tables are flattened, so `[health]` with
+// `max_poll_age_secs = 90` sets HEALTH_MAX_POLL_AGE_SECS. Arrays become comma-separated lists.
+fn read_toml(path: &Path) -> Result<HashMap<String, String>, ConfigProblem> {
+    let content = fs::read_to_string(path).map_err(|e| ConfigProblem::unreadable(path, e))?;
+    let table: toml::Table = content.parse().map_err(|e: toml::de::Error| {
+        ConfigProblem::invalid(&path.display().to_string(), e.message().to_string())
+    })?;
+    let mut vars = HashMap::new();
+    flatten_toml("", &table, &mut vars)
+        .map_err(|key| ConfigProblem::invalid(&key, "unsupported TOML value".to_string()))?;
+    Ok(vars)
+}
+
+fn flatten_toml(
+    prefix: &str,
+    table: &toml::Table,
+    vars: &mut HashMap<String, String>,
+) -> Result<(), String> {
+    for (name, value) in table {
+        let key = format!("{}{}", prefix, name.to_ascii_uppercase().replace('-', "_"));
+        match value {
+            toml::Value::Table(table) => flatten_toml(&format!("{}_", key), table, vars)?,
+            toml::Value::Array(items) => {
+                let items = items
+                    .iter()
+                    .map(toml_scalar)
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| key.clone())?;
+                vars.insert(key, items.join(","));
+            }
+            scalar => {
+                let value = toml_scalar(scalar).ok_or_else(|| key.clone())?;
+                vars.insert(key, value);
+            }
+        }
+    }
+    Ok(())
+}
+
+fn toml_scalar(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Integer(i) => Some(i.to_string()),
+        toml::Value::Float(f) => Some(f.to_string()),
+        toml::Value::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    service::admin_service::{
+        BreakerUpdate, CapacityUpdate, MemoryTier, SlotPin, evict_slot, flush_tier, force_breaker,
+        get_breaker_status, pin_slot, pinned_slots, resize_tier, set_poller_paused, unpin_slot,
+    },
+    service::client_service::{ClientUsage, client_usage, get_client_usage, reset_client_usage},
+    service::reload_service::{ConfigReload, reload_config},
+    state::AppState,
+};
+use axum::{
+    extract::{Path, Request, State},
+    http::{HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use subtle::ConstantTimeEq;
+
+/// Lets requests through only with `Authorization: Bearer <ADMIN_TOKEN>`. Answers 401 for a
+/// missing or wrong token, and 403 when no token is configured, which disables the admin API.
+pub async fn require_admin_token(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(expected) = &app_state.admin_token else {
+        return (
+            StatusCode::FORBIDDEN,
+            "Admin API is disabled, set ADMIN_TOKEN to enable it",
+        )
+            .into_response();
+    };
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| bool::from(token.as_bytes().ct_eq(expected.as_bytes())));
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))],
+        )
+            .into_response();
+    }
+    next.run(request).await
+}
+
+/// Drops every slot of the `primary` or `lru` tier
+pub async fn flush_cache_handler(
+    State(app_state): State<AppState>,
+    Path(tier): Path<MemoryTier>,
+) -> Response {
+    Json(flush_tier(&app_state, tier).await).into_response()
+}
+
+/// Changes the capacity of a tier, 400 for a capacity of 0
+pub async fn resize_cache_handler(
+    State(app_state): State<AppState>,
+    Path(tier): Path<MemoryTier>,
+    Json(update): Json<CapacityUpdate>,
+) -> Response {
+    match resize_tier(&app_state, tier, update.capacity).await {
+        Ok(result) => Json(result).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+/// Evicts a slot from both in-memory tiers, 404 if neither had it
+pub async fn evict_slot_handler(
+    State(app_state): State<AppState>,
+    Path(slot): Path<u64>,
+) -> StatusCode {
+    if evict_slot(&app_state, slot).await {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+/// Lists the pinned slots
+pub async fn list_pins_handler(State(app_state): State<AppState>) -> Json<Vec<u64>> {
+    Json(pinned_slots(&app_state).await)
+}
+
+/// Pins a cached slot in the LRU cache, 404 if it is not cached
+pub async fn pin_slot_handler(
+    State(app_state): State<AppState>,
+    Path(slot): Path<u64>,
+) -> Response {
+    if pin_slot(&app_state, slot).await {
+        Json(SlotPin { slot, pinned: true }).into_response()
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// Unpins a slot, 404 if it was not pinned
+pub async fn unpin_slot_handler(
+    State(app_state): State<AppState>,
+    Path(slot): Path<u64>,
+) -> Response {
+    if unpin_slot(&app_state, slot).await {
+        Json(SlotPin {
+            slot,
+            pinned: false,
+        })
+        .into_response()
+    } else {
+        StatusCode::NOT_FOUND.into_response()
+    }
+}
+
+/// Returns the circuit breaker state and whether it was forced open
+pub async fn get_breaker_handler(State(app_state): State<AppState>) -> Response {
+    Json(get_breaker_status(&app_state).await).into_response()
+}
+
+/// Forces the circuit breaker `open` or `closed`
+pub async fn force_breaker_handler(
+    State(app_state): State<AppState>,
+    Json(update): Json<BreakerUpdate>,
+) -> Response {
+    Json(force_breaker(&app_state, update.state).await).into_response()
+}
+
+/// Stops the background poller from polling until resumed
+pub async fn pause_poller_handler(State(app_state): State<AppState>) -> Response {
+    Json(set_poller_paused(&app_state, true)).into_response()
+}
+
+pub async fn resume_poller_handler(State(app_state): State<AppState>) -> Response {
+    Json(set_poller_paused(&app_state, false)).into_response()
+}
+
+/// Re-reads the configuration and applies what can change at runtime, 400 if it does not
+/// validate, 404 if the service runs without a configuration source
+pub async fn reload_config_handler(State(app_state): State<AppState>) -> Response {
+    match reload_config(&app_state).await {
+        ConfigReload::Applied(report) => Json(report).into_response(),
+        ConfigReload::Rejected(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+        ConfigReload::Unavailable => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Usage of every client key, 404 if client keys are not enabled
+pub async fn list_clients_handler(State(app_state): State<AppState>) -> Response {
+    match client_usage(&app_state) {
+        Some(usage) => Json(usage).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Usage of one client key, 404 if there is no such client
+pub async fn get_client_handler(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    client_response(get_client_usage(&app_state, &name))
+}
+
+/// Starts a new quota window for the client, 404 if there is no such client
+pub async fn reset_client_usage_handler(
+    State(app_state): State<AppState>,
+    Path(name): Path<String>,
+) -> Response {
+    client_response(reset_client_usage(&app_state, &name))
+}
+
+fn client_response(usage: Option<ClientUsage>) -> Response {
+    match usage {
+        Some(usage) => Json(usage).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
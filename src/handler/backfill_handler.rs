@@ -0,0 +1,49 @@
+use crate::{
+    service::backfill_service::{
+        BackfillJob, BackfillRequest, cancel_backfill, get_backfill, list_backfills, start_backfill,
+    },
+    state::AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+/// Starts a job pre-loading a slot or epoch range, answered with the job as accepted
+pub async fn start_backfill_handler(
+    State(app_state): State<AppState>,
+    Json(request): Json<BackfillRequest>,
+) -> Response {
+    match start_backfill(&app_state, &request).await {
+        Ok(job) => (StatusCode::ACCEPTED, Json(job)).into_response(),
+        Err(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+    }
+}
+
+/// Lists all known backfill jobs
+pub async fn list_backfills_handler(State(app_state): State<AppState>) -> Json<Vec<BackfillJob>> {
+    Json(list_backfills(&app_state).await)
+}
+
+/// Returns the progress of a backfill job
+pub async fn get_backfill_handler(
+    State(app_state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Response {
+    match get_backfill(&app_state, id).await {
+        Some(job) => Json(job).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Cancels a backfill job, the slots it already loaded stay cached
+pub async fn cancel_backfill_handler(
+    State(app_state): State<AppState>,
+    Path(id): Path<u64>,
+) -> Response {
+    match cancel_backfill(&app_state, id).await {
+        Some(job) => Json(job).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
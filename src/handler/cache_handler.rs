@@ -1,5 +1,8 @@
 use crate::{
-    service::cache_service::{get_all_latest_slots, get_all_lru_slots},
+    service::{
+        cache_service::{get_all_latest_slots, get_all_lru_slots, get_poller_status},
+        slot_poller::LevelStatus,
+    },
     state::AppState,
 };
 use axum::{extract::State, response::Json};
@@ -13,3 +16,10 @@ pub async fn get_latest_slots_handler(State(app_state): State<AppState>) -> Json
 pub async fn get_lru_slots_handler(State(app_state): State<AppState>) -> Json<Vec<u64>> {
     Json(get_all_lru_slots(&app_state).await)
 }
+
+/// Handler to get the poller cursors and catch-up progress
+pub async fn get_poller_status_handler(
+    State(app_state): State<AppState>,
+) -> Json<Vec<LevelStatus>> {
+    Json(get_poller_status(&app_state).await)
+}
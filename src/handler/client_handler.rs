@@ -0,0 +1,80 @@
+use crate::{
+    service::client_service::{Admission, RequestClient},
+    state::AppState,
+};
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::time::Duration;
+
+/// Header carrying a client key, as an alternative to `Authorization: Bearer <key>`
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Response extension of handlers that report refused RPC fallbacks per item, so their
+/// partial results are sent instead of being replaced by a 429
+#[derive(Debug, Clone, Copy)]
+pub struct PartialResults;
+
+/// Lets requests through only with a known client key once client keys are configured.
+/// Answers 401 for a missing or unknown key and 429 with `Retry-After` once the key hit its
+/// rate limit, its request quota, or its RPC fallback quota while serving the request.
+/// Responses marked with [`PartialResults`] are kept when the RPC fallback quota ran out.
+pub async fn require_client_key(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(registry) = &app_state.client_registry else {
+        return next.run(request).await;
+    };
+    match registry.admit(presented_key(request.headers())) {
+        Admission::Admitted(client) => serve_as(client, request, next).await,
+        Admission::Unknown => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"))],
+        )
+            .into_response(),
+        Admission::RateLimited(retry_after) => {
+            too_many_requests(retry_after, "Rate limit exceeded")
+        }
+        Admission::QuotaExceeded(retry_after) => {
+            too_many_requests(retry_after, "Request quota exceeded")
+        }
+    }
+}
+
+async fn serve_as(client: RequestClient, request: Request, next: Next) -> Response {
+    let response = client.clone().run(next.run(request)).await;
+    match client.rpc_denied() {
+        Some(retry_after) if response.extensions().get::<PartialResults>().is_none() => {
+            too_many_requests(retry_after, "RPC fallback quota exceeded")
+        }
+        _ => response,
+    }
+}
+
+fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let header_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+    header_key.or_else(|| {
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+    })
+}
+
+// Retry-After is in whole seconds, rounded up so a retry does not come too early
+fn too_many_requests(retry_after: Duration, message: &'static str) -> Response {
+    let seconds = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, seconds.max(1).to_string())],
+        message,
+    )
+        .into_response()
+}
@@ -0,0 +1,211 @@
+use crate::{
+    handler::{client_handler::PartialResults, slot_handler::CommitmentParams},
+    service::client_service::with_current_client,
+    service::confirmation_service::{ConfirmationStatus, confirm_many},
+    state::AppState,
+};
+use axum::{
+    Extension,
+    body::{Body, Bytes},
+    extract::{FromRequest, Query, Request, State},
+    http::{HeaderMap, StatusCode, header},
+    response::{IntoResponse, Json, Response},
+};
+use futures_util::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Largest list accepted by the JSON variant, bigger inputs should be streamed as NDJSON
+pub const MAX_BATCH_SLOTS: usize = 10_000;
+/// Longest NDJSON input line, longer lines are answered with an error and skipped unread
+pub const MAX_NDJSON_LINE_BYTES: usize = 1024;
+
+const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+// Number of streamed slots resolved together
+const NDJSON_CHUNK_SLOTS: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchConfirmationRequest {
+    pub slots: Vec<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlotConfirmation {
+    pub slot: u64,
+    pub status: ConfirmationStatus,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchConfirmationResponse {
+    pub results: Vec<SlotConfirmation>,
+}
+
+/// Checks a list of slots in one request.
+///
+/// A JSON body `{"slots": [...]}` is answered with a JSON list of per-slot statuses.
+/// An `application/x-ndjson` body with one slot per line is answered with a stream of
+/// NDJSON status lines, written as the input is consumed. Slots the client's RPC fallback
+/// quota did not cover are answered with `quota_exceeded` in either case.
+pub async fn batch_confirmation_handler(
+    State(app_state): State<AppState>,
+    Query(params): Query<CommitmentParams>,
+    request: Request,
+) -> Response {
+    if is_ndjson(request.headers()) {
+        return stream_confirmations(app_state, params, request.into_body());
+    }
+
+    let Json(batch) = match Json::<BatchConfirmationRequest>::from_request(request, &()).await {
+        Ok(batch) => batch,
+        Err(rejection) => return rejection.into_response(),
+    };
+    if batch.slots.len() > MAX_BATCH_SLOTS {
+        return (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "At most {} slots per request, use an {} body for larger inputs",
+                MAX_BATCH_SLOTS, NDJSON_CONTENT_TYPE
+            ),
+        )
+            .into_response();
+    }
+
+    let commitment = params.commitment.unwrap_or_default();
+    let statuses = confirm_many(&app_state, &batch.slots, commitment).await;
+    let results = batch
+        .slots
+        .into_iter()
+        .zip(statuses)
+        .map(|(slot, status)| SlotConfirmation { slot, status })
+        .collect();
+
+    (
+        Extension(PartialResults),
+        Json(BatchConfirmationResponse { results }),
+    )
+        .into_response()
+}
+
+fn is_ndjson(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(NDJSON_CONTENT_TYPE))
+}
+
+fn stream_confirmations(app_state: AppState, params: CommitmentParams, body: Body) -> Response {
+    let commitment = params.commitment.unwrap_or_default();
+    let (tx, rx) = mpsc::channel::<Bytes>(4);
+
+    // Slots resolved over RPC are still charged to the client of the request
+    tokio::spawn(with_current_client(async move {
+        let mut input = body.into_data_stream();
+        let mut buffer = Vec::new();
+        let mut slots = Vec::with_capacity(NDJSON_CHUNK_SLOTS);
+        let mut line_number = 0_usize;
+        // Set while skipping the rest of a line that already exceeded the limit
+        let mut discarding = false;
+
+        loop {
+            let chunk = input.next().await;
+            let finished = !matches!(chunk, Some(Ok(_)));
+            match chunk {
+                Some(Ok(bytes)) => buffer.extend_from_slice(&bytes),
+                Some(Err(e)) => warn!("Failed to read NDJSON confirmation request: {}", e),
+                None => {}
+            }
+
+            // The last line does not need a trailing newline
+            if finished && !buffer.is_empty() {
+                buffer.push(b'\n');
+            }
+            while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = buffer.drain(..=end).collect();
+                if discarding {
+                    // The tail of the oversized line, already reported
+                    discarding = false;
+                    continue;
+                }
+                line_number += 1;
+                if line.len() > MAX_NDJSON_LINE_BYTES {
+                    if tx.send(line_too_long(line_number)).await.is_err() {
+                        return;
+                    }
+                    continue;
+                }
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                match line.parse::<u64>() {
+                    Ok(slot) => slots.push(slot),
+                    Err(_) => {
+                        let error = serde_json::json!({
+                            "line": line_number,
+                            "error": "expected a slot number",
+                        });
+                        if tx.send(ndjson_line(&error)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+
+            // Without a newline in sight the partial line is not kept around
+            if buffer.len() > MAX_NDJSON_LINE_BYTES {
+                buffer.clear();
+                if !discarding {
+                    discarding = true;
+                    line_number += 1;
+                    if tx.send(line_too_long(line_number)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            if slots.len() >= NDJSON_CHUNK_SLOTS || (finished && !slots.is_empty()) {
+                let statuses = confirm_many(&app_state, &slots, commitment).await;
+                let mut output = Vec::new();
+                for (slot, status) in slots.drain(..).zip(statuses) {
+                    output.extend_from_slice(&ndjson_line(&SlotConfirmation { slot, status }));
+                }
+                if tx.send(Bytes::from(output)).await.is_err() {
+                    // The client went away
+                    return;
+                }
+            }
+
+            if finished {
+                return;
+            }
+        }
+    }));
+
+    let output = stream::unfold(rx, |mut rx| async move {
+        rx.recv()
+            .await
+            .map(|bytes| (Ok::<_, Infallible>(bytes), rx))
+    });
+    (
+        [(header::CONTENT_TYPE, NDJSON_CONTENT_TYPE)],
+        Extension(PartialResults),
+        Body::from_stream(output),
+    )
+        .into_response()
+}
+
+fn line_too_long(line_number: usize) -> Bytes {
+    ndjson_line(&serde_json::json!({
+        "line": line_number,
+        "error": format!("line is longer than {} bytes", MAX_NDJSON_LINE_BYTES),
+    }))
+}
+
+fn ndjson_line<T: Serialize>(value: &T) -> Bytes {
+    let mut line = serde_json::to_vec(value).expect("confirmation lines always serialize");
+    line.push(b'\n');
+    Bytes::from(line)
+}
@@ -0,0 +1,25 @@
+use crate::{
+    service::health_service::{HealthReport, liveness, readiness},
+    state::AppState,
+};
+use axum::{extract::State, http::StatusCode, response::Json};
+
+/// Liveness probe, answers as long as the process serves requests
+pub async fn liveness_handler(State(app_state): State<AppState>) -> Json<HealthReport> {
+    Json(liveness(&app_state).await)
+}
+
+/// Readiness probe for load balancers, fails until the primary cache is warmed up and
+/// whenever the poller is stuck or lagging. An open circuit breaker only fails it when
+/// `HEALTH_FAIL_ON_OPEN_CIRCUIT` is set.
+pub async fn readiness_handler(
+    State(app_state): State<AppState>,
+) -> (StatusCode, Json<HealthReport>) {
+    let report = readiness(&app_state).await;
+    let status = if report.failures.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
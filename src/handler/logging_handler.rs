@@ -0,0 +1,29 @@
+use crate::{
+    service::logging_service::{LogFilter, LogFilterUpdate, get_log_filter, set_log_filter},
+    state::AppState,
+};
+use axum::{
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+
+/// Returns the log filter directives in effect
+pub async fn get_log_filter_handler(State(app_state): State<AppState>) -> Response {
+    match get_log_filter(&app_state) {
+        Some(filter) => Json(filter).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Changes the log levels without a restart, 400 if the directives do not parse
+pub async fn set_log_filter_handler(
+    State(app_state): State<AppState>,
+    Json(update): Json<LogFilter>,
+) -> Response {
+    match set_log_filter(&app_state, &update) {
+        LogFilterUpdate::Applied(filter) => Json(filter).into_response(),
+        LogFilterUpdate::Rejected(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+        LogFilterUpdate::Unavailable => StatusCode::NOT_FOUND.into_response(),
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{service::metrics_service::render_metrics, state::AppState};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{StatusCode, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Exposes the metrics in the Prometheus text format, 404 when another backend is configured
+pub async fn get_metrics_handler(State(app_state): State<AppState>) -> Response {
+    match render_metrics(&app_state).await {
+        Some(body) => ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], body).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Counts every request by its route pattern and response status
+pub async fn track_http_requests(
+    State(app_state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Route patterns keep the label set bounded, raw paths would not
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let response = next.run(request).await;
+    app_state
+        .metrics
+        .record_http_request(&route, response.status().as_u16());
+    response
+}
@@ -1,3 +1,11 @@
+pub mod admin_handler;
+pub mod backfill_handler;
 pub mod cache_handler;
+pub mod client_handler;
+pub mod confirmations_handler;
+pub mod health_handler;
+pub mod logging_handler;
+pub mod metrics_handler;
 pub mod ping_handler;
 pub mod slot_handler;
+pub mod trace_handler;
@@ -1,20 +1,81 @@
 use crate::{
-    service::confirmation_service::{ConfirmationStatus, confirm_with_lru_and_breaker},
+    commitment::Commitment,
+    service::confirmation_service::{ConfirmationStatus, confirm_with_commitment},
+    service::coverage_service::{MAX_COVERAGE_RANGE, get_range_coverage},
+    service::slot_info_service::{SlotInfoLookup, get_slot_info},
     state::AppState,
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Json, Response},
 };
+use serde::Deserialize;
+use tracing::instrument;
 
+#[derive(Debug, Default, Deserialize)]
+pub struct CommitmentParams {
+    pub commitment: Option<Commitment>,
+}
+
+#[instrument(name = "is_slot_confirmed", skip_all, fields(slot = slot))]
 pub async fn check_slot_confirmation_handler(
     State(app_state): State<AppState>,
     Path(slot): Path<u64>,
+    Query(params): Query<CommitmentParams>,
 ) -> impl IntoResponse {
-    match confirm_with_lru_and_breaker(&app_state, slot).await {
+    let commitment = params.commitment.unwrap_or_default();
+    match confirm_with_commitment(&app_state, slot, commitment).await {
         ConfirmationStatus::Confirmed => StatusCode::OK,
         ConfirmationStatus::NotConfirmed => StatusCode::NOT_FOUND,
         ConfirmationStatus::Error => StatusCode::INTERNAL_SERVER_ERROR,
+        // Answered with `Retry-After` by the client key middleware
+        ConfirmationStatus::QuotaExceeded => StatusCode::TOO_MANY_REQUESTS,
+    }
+}
+
+/// Returns the cached record of a slot: blockhash, parent, height, time and commitment
+#[instrument(name = "slot_info", skip_all, fields(slot = slot))]
+pub async fn get_slot_info_handler(
+    State(app_state): State<AppState>,
+    Path(slot): Path<u64>,
+    Query(params): Query<CommitmentParams>,
+) -> Response {
+    let commitment = params.commitment.unwrap_or_default();
+    match get_slot_info(&app_state, slot, commitment).await {
+        SlotInfoLookup::Found(info) => Json(info).into_response(),
+        SlotInfoLookup::NotConfirmed => StatusCode::NOT_FOUND.into_response(),
+        SlotInfoLookup::Error => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RangeParams {
+    pub start: u64,
+    pub end: u64,
+    pub commitment: Option<Commitment>,
+}
+
+/// Reports which slots of an inclusive range are confirmed, skipped or unknown
+#[instrument(name = "slot_range", skip_all, fields(start = params.start, end = params.end))]
+pub async fn get_slot_range_handler(
+    State(app_state): State<AppState>,
+    Query(params): Query<RangeParams>,
+) -> Response {
+    if params.start > params.end {
+        return (StatusCode::BAD_REQUEST, "start must not exceed end").into_response();
+    }
+    if params.end - params.start >= MAX_COVERAGE_RANGE {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("At most {} slots per range", MAX_COVERAGE_RANGE),
+        )
+            .into_response();
+    }
+
+    let commitment = params.commitment.unwrap_or_default();
+    match get_range_coverage(&app_state, params.start, params.end, commitment).await {
+        Some(coverage) => Json(coverage).into_response(),
+        None => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
     }
 }
@@ -0,0 +1,31 @@
+use crate::telemetry::parent_context;
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+use tracing::{Instrument, field, info_span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+/// Wraps every request in a server span, continuing the trace of an incoming `traceparent`
+pub async fn trace_http_requests(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+    let span = info_span!(
+        "http_request",
+        otel.name = format!("{} {}", request.method(), route),
+        otel.kind = "server",
+        http.request.method = %request.method(),
+        http.route = route,
+        http.response.status_code = field::Empty,
+    );
+    // Without a tracing layer installed there is nothing to attach the parent to
+    let _ = span.set_parent(parent_context(request.headers()));
+
+    let response = next.run(request).instrument(span.clone()).await;
+    span.record("http.response.status_code", response.status().as_u16());
+    response
+}
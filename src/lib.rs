@@ -1,11 +1,16 @@
 pub mod cache;
 pub mod circuit_breaker;
+pub mod commitment;
 pub mod config;
 pub mod handler;
+pub mod listener;
+pub mod logging;
 pub mod metrics;
 pub mod routes;
 pub mod rpc;
 pub mod service;
 pub mod signals;
+pub mod slot_info;
 pub mod state;
+pub mod telemetry;
 pub mod utils;
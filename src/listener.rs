@@ -0,0 +1,350 @@
+use crate::config::Config;
+use axum::{Router, serve::Listener};
+use rustls::ServerConfig;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+use std::{
+    fs, io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::{Semaphore, broadcast, mpsc},
+    task::JoinHandle,
+    time::{MissedTickBehavior, interval, sleep, timeout},
+};
+use tokio_rustls::{TlsAcceptor, server::TlsStream};
+use tracing::{info, warn};
+
+/// Longest a client may take to complete the TLS handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Handshaken connections waiting for the server to pick them up
+const ACCEPT_QUEUE: usize = 64;
+
+/// Connections in the middle of a TLS handshake, further clients wait in the kernel backlog
+const MAX_PENDING_HANDSHAKES: usize = 256;
+
+#[derive(Debug, Clone)]
+pub struct TlsSettings {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+    /// How often the files are checked for a renewed certificate
+    pub reload_interval: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListenerSettings {
+    pub bind_address: SocketAddr,
+    /// Terminates TLS on the TCP listener when set
+    pub tls: Option<TlsSettings>,
+    /// Additional plaintext listener on a Unix domain socket
+    pub unix_socket_path: Option<PathBuf>,
+}
+
+impl ListenerSettings {
+    pub fn from_config(config: &Config) -> Self {
+        let tls = match (&config.tls_cert_path, &config.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => Some(TlsSettings {
+                cert_path: cert_path.clone(),
+                key_path: key_path.clone(),
+                reload_interval: config.tls_reload_interval,
+            }),
+            _ => None,
+        };
+        Self {
+            bind_address: config.bind_address,
+            tls,
+            unix_socket_path: config.unix_socket_path.clone(),
+        }
+    }
+}
+
+/// Server certificate and key, swapped in place when the files change on disk.
+/// Connections already established keep the certificate they were accepted with.
+#[derive(Debug)]
+pub struct ReloadableTls {
+    settings: TlsSettings,
+    current: RwLock<Arc<ServerConfig>>,
+    modified: Mutex<(SystemTime, SystemTime)>,
+}
+
+impl ReloadableTls {
+    pub fn load(settings: TlsSettings) -> io::Result<Self> {
+        let modified = modified_times(&settings)?;
+        let config = load_server_config(&settings.cert_path, &settings.key_path)?;
+        Ok(Self {
+            settings,
+            current: RwLock::new(Arc::new(config)),
+            modified: Mutex::new(modified),
+        })
+    }
+
+    pub fn acceptor(&self) -> TlsAcceptor {
+        TlsAcceptor::from(self.current.read().unwrap().clone())
+    }
+
+    /// Reloads the certificate and key if either file changed since the last load.
+    /// Returns whether a new certificate is in use, the current one stays on error.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        let modified = modified_times(&self.settings)?;
+        if modified == *self.modified.lock().unwrap() {
+            return Ok(false);
+        }
+        let config = load_server_config(&self.settings.cert_path, &self.settings.key_path)?;
+        *self.current.write().unwrap() = Arc::new(config);
+        *self.modified.lock().unwrap() = modified;
+        Ok(true)
+    }
+}
+
+fn modified_times(settings: &TlsSettings) -> io::Result<(SystemTime, SystemTime)> {
+    Ok((
+        fs::metadata(&settings.cert_path)?.modified()?,
+        fs::metadata(&settings.key_path)?.modified()?,
+    ))
+}
+
+/// Reads a PEM certificate chain and private key into a TLS server configuration
+pub fn load_server_config(cert_path: &Path, key_path: &Path) -> io::Result<ServerConfig> {
+    let invalid = |path: &Path, e: &dyn std::fmt::Display| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e),
+        )
+    };
+    let certs = CertificateDer::pem_file_iter(cert_path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| invalid(cert_path, &e))?;
+    if certs.is_empty() {
+        return Err(invalid(cert_path, &"no certificate found"));
+    }
+    let key = PrivateKeyDer::from_pem_file(key_path).map_err(|e| invalid(key_path, &e))?;
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let mut config = ServerConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|e| invalid(cert_path, &e))?;
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+/// Checks the certificate files every `reload_interval` until shutdown
+pub fn watch_certificates(
+    tls: Arc<ReloadableTls>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = interval(tls.settings.reload_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.recv() => break,
+                _ = ticker.tick() => match tls.reload_if_changed() {
+                    Ok(true) => info!("Reloaded TLS certificate from {}", tls.settings.cert_path.display()),
+                    Ok(false) => {}
+                    Err(e) => warn!("Failed to reload TLS certificate, keeping the current one: {}", e),
+                },
+            }
+        }
+    })
+}
+
+/// TCP listener terminating TLS. Handshakes run in their own tasks, so a slow client does
+/// not hold up the others, and only completed connections are handed to the server. At most
+/// `MAX_PENDING_HANDSHAKES` run at once, connections are not accepted beyond that.
+pub struct TlsListener {
+    incoming: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+    local_addr: SocketAddr,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, tls: Arc<ReloadableTls>) -> io::Result<Self> {
+        let local_addr = listener.local_addr()?;
+        let (tx, incoming) = mpsc::channel(ACCEPT_QUEUE);
+        let handshakes = Arc::new(Semaphore::new(MAX_PENDING_HANDSHAKES));
+        tokio::spawn(async move {
+            loop {
+                let permit = tokio::select! {
+                    _ = tx.closed() => break,
+                    permit = handshakes.clone().acquire_owned() => {
+                        permit.expect("the handshake semaphore is never closed")
+                    }
+                };
+                let (stream, addr) = tokio::select! {
+                    _ = tx.closed() => break,
+                    accepted = listener.accept() => match accepted {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            warn!("Failed to accept connection: {}", e);
+                            sleep(Duration::from_secs(1)).await;
+                            continue;
+                        }
+                    },
+                };
+                let acceptor = tls.acceptor();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    // Held until the connection is handed over or dropped
+                    let _permit = permit;
+                    match timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(stream)) => {
+                            let _ = tx.send((stream, addr)).await;
+                        }
+                        Ok(Err(e)) => info!("TLS handshake with {} failed: {}", addr, e),
+                        Err(_) => info!("TLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+        Ok(Self {
+            incoming,
+            local_addr,
+        })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.incoming.recv().await {
+            Some(accepted) => accepted,
+            // The accept task only stops once this listener is dropped
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+/// Serves the router on the TCP listener, over TLS when configured, and on the Unix socket
+/// if one is set. Every listener drains its connections once `shutdown_tx` fires.
+///
+/// All listeners are bound before any of them is served, so a failed start leaves nothing
+/// running behind.
+pub async fn serve(
+    app: Router,
+    settings: &ListenerSettings,
+    shutdown_tx: &broadcast::Sender<()>,
+) -> io::Result<()> {
+    let tls = match &settings.tls {
+        Some(tls_settings) => Some(Arc::new(ReloadableTls::load(tls_settings.clone())?)),
+        None => None,
+    };
+    let tcp_listener = TcpListener::bind(settings.bind_address).await?;
+    let local_addr = tcp_listener.local_addr()?;
+    let unix_listener = match &settings.unix_socket_path {
+        Some(path) => Some((bind_unix(path)?, path.clone())),
+        None => None,
+    };
+
+    let tcp = match tls {
+        Some(tls) => {
+            watch_certificates(tls.clone(), shutdown_tx.subscribe());
+            info!("Running server on https://{}", local_addr);
+            let listener = TlsListener::new(tcp_listener, tls)?;
+            tokio::spawn(serve_until_shutdown(listener, app.clone(), shutdown_tx))
+        }
+        None => {
+            info!("Running server on http://{}", local_addr);
+            tokio::spawn(serve_until_shutdown(tcp_listener, app.clone(), shutdown_tx))
+        }
+    };
+    let unix = unix_listener.map(|(listener, path)| {
+        info!("Running server on unix:{}", path.display());
+        (serve_unix(listener, app, shutdown_tx), path)
+    });
+
+    let result = tcp.await.map_err(io::Error::other)?;
+    if let Some((unix, path)) = unix {
+        let unix_result = unix.await.map_err(io::Error::other);
+        let _ = fs::remove_file(path);
+        unix_result??;
+    }
+    result
+}
+
+#[cfg(unix)]
+fn bind_unix(path: &Path) -> io::Result<tokio::net::UnixListener> {
+    use std::os::unix::{fs::FileTypeExt, net::UnixStream};
+
+    // A socket left behind by a previous run would make the bind fail. It is only removed
+    // once nothing answers on it, anything else at the path is not ours to remove.
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => match UnixStream::connect(path) {
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AddrInUse,
+                    format!("{} is in use by another process", path.display()),
+                ));
+            }
+            Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path)?,
+            Err(e) => return Err(e),
+        },
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            ));
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+    tokio::net::UnixListener::bind(path)
+}
+
+#[cfg(unix)]
+fn serve_unix(
+    listener: tokio::net::UnixListener,
+    app: Router,
+    shutdown_tx: &broadcast::Sender<()>,
+) -> JoinHandle<io::Result<()>> {
+    tokio::spawn(serve_until_shutdown(listener, app, shutdown_tx))
+}
+
+#[cfg(not(unix))]
+fn bind_unix(_path: &Path) -> io::Result<std::convert::Infallible> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Unix domain sockets are not supported on this platform",
+    ))
+}
+
+#[cfg(not(unix))]
+fn serve_unix(
+    listener: std::convert::Infallible,
+    _app: Router,
+    _shutdown_tx: &broadcast::Sender<()>,
+) -> JoinHandle<io::Result<()>> {
+    match listener {}
+}
+
+fn serve_until_shutdown<L>(
+    listener: L,
+    app: Router,
+    shutdown_tx: &broadcast::Sender<()>,
+) -> impl Future<Output = io::Result<()>> + Send + 'static
+where
+    L: Listener,
+    L::Addr: std::fmt::Debug,
+{
+    let mut shutdown_rx = shutdown_tx.subscribe();
+    async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move {
+                let _ = shutdown_rx.recv().await;
+            })
+            .await
+    }
+}
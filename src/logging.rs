@@ -0,0 +1,187 @@
+use crate::utils::redact::Redactor;
+use std::{
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+use tracing_subscriber::{
+    EnvFilter, Layer, Registry, fmt as log_fmt, fmt::MakeWriter, layer::SubscriberExt, reload,
+    util::SubscriberInitExt,
+};
+
+pub type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Format of the log lines written to stdout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl fmt::Display for LogFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogFormat::Text => f.write_str("text"),
+            LogFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("Unknown log format: {}", other)),
+        }
+    }
+}
+
+/// Parses `RUST_LOG`-style directives, e.g. `info,solana_caching_service::service=debug`
+pub fn parse_log_filter(directives: &str) -> Result<EnvFilter, String> {
+    EnvFilter::builder()
+        .parse(directives)
+        .map_err(|e| format!("Invalid log filter '{}': {}", directives, e))
+}
+
+/// Installs the global subscriber writing logs to stdout, filtered by the directives, next to
+/// the extra layer (e.g. the span exporter), which the log filter does not apply to.
+/// Every secret known to the redactor is replaced in the written lines.
+/// Returns the handle changing the log levels at runtime.
+pub fn init_logging(
+    format: LogFormat,
+    directives: &str,
+    extra_layer: Option<BoxedLayer>,
+    redactor: Arc<Redactor>,
+) -> Result<LogLevels, String> {
+    let (filter, handle) = reload::Layer::new(parse_log_filter(directives)?);
+    let stdout = RedactingMakeWriter::new(io::stdout, redactor);
+    let output = match format {
+        LogFormat::Text => log_fmt::layer().with_writer(stdout).boxed(),
+        LogFormat::Json => log_fmt::layer()
+            .json()
+            .flatten_event(true)
+            .with_writer(stdout)
+            .boxed(),
+    };
+    let mut layers = vec![output.with_filter(filter).boxed()];
+    layers.extend(extra_layer);
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .map_err(|e| format!("Failed to install the log subscriber: {}", e))?;
+    Ok(LogLevels::new(handle, directives))
+}
+
+/// Hands out writers that hold a log line until it is complete and then write it to the
+/// inner writer with the secrets replaced, so a secret split over several writes is caught too
+pub struct RedactingMakeWriter<M> {
+    inner: M,
+    redactor: Arc<Redactor>,
+}
+
+impl<M> RedactingMakeWriter<M> {
+    pub fn new(inner: M, redactor: Arc<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingMakeWriter<M> {
+    type Writer = RedactedLine<'a, M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactedLine {
+            inner: self.inner.make_writer(),
+            redactor: &self.redactor,
+            line: Vec::new(),
+        }
+    }
+}
+
+/// A log line being written, passed on redacted when flushed or dropped
+pub struct RedactedLine<'a, W: Write> {
+    inner: W,
+    redactor: &'a Redactor,
+    line: Vec<u8>,
+}
+
+impl<W: Write> Write for RedactedLine<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.line.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.line.is_empty() {
+            let line = String::from_utf8_lossy(&self.line);
+            self.inner
+                .write_all(self.redactor.redact(&line).as_bytes())?;
+            self.line.clear();
+        }
+        self.inner.flush()
+    }
+}
+
+impl<W: Write> Drop for RedactedLine<'_, W> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Handle on the filter of the log output, so levels can be changed without a restart
+pub struct LogLevels {
+    handle: reload::Handle<EnvFilter, Registry>,
+    current: Mutex<String>,
+}
+
+impl LogLevels {
+    pub fn new(handle: reload::Handle<EnvFilter, Registry>, directives: &str) -> Self {
+        Self {
+            handle,
+            current: Mutex::new(directives.to_string()),
+        }
+    }
+
+    /// Directives currently in effect
+    pub fn current(&self) -> String {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Replaces the filter, the previous one stays in effect if the directives do not parse
+    pub fn set(&self, directives: &str) -> Result<(), String> {
+        let filter = parse_log_filter(directives)?;
+        let mut current = self.current.lock().unwrap();
+        self.handle
+            .reload(filter)
+            .map_err(|e| format!("Failed to apply log filter: {}", e))?;
+        *current = directives.to_string();
+        Ok(())
+    }
+}
+
+/// Lets one in every `every` occurrences of a repeated log message through
+#[derive(Debug, Default)]
+pub struct LogSampler {
+    seen: AtomicU64,
+}
+
+impl LogSampler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns how many occurrences were suppressed since the last one let through,
+    /// or `None` if this one should be suppressed. An `every` of 0 or 1 lets all through.
+    pub fn sample(&self, every: u64) -> Option<u64> {
+        let every = every.max(1);
+        let seen = self.seen.fetch_add(1, Ordering::Relaxed);
+        seen.is_multiple_of(every).then(|| seen.min(every - 1))
+    }
+}
@@ -1,71 +1,205 @@
 use solana_caching_service::circuit_breaker::CircuitBreaker;
-use solana_caching_service::metrics::LoggingMetrics;
-use solana_caching_service::metrics::Metrics;
+use solana_caching_service::metrics::{LoggingMetrics, Metrics, MetricsBackend, PrometheusMetrics};
 use solana_caching_service::{
-    cache::{LruCache, SlotCache},
-    config::Config,
+    cache::{DiskStore, LruCache, SharedCache, SharedCacheSettings, SlotCache},
+    config::{Config, cli::CliArgs},
+    listener::{ListenerSettings, serve},
+    logging::init_logging,
     routes::create_router,
-    rpc::RpcApi,
-    service::slot_poller::poll_with_transient_retry_and_signals_and_breaker,
+    rpc::{RedactingRpc, RpcApi, RpcPool, upstream::UpstreamAuth},
+    service::backfill_service::{
+        BackfillManager, BackfillSettings, epoch_schedule, resume_backfills,
+    },
+    service::client_service::{ClientRegistry, ClientSettings, watch_client_keys},
+    service::fallback_batcher::{BatcherSettings, FallbackBatcher},
+    service::health_service::HealthSettings,
+    service::reload_service::{ConfigReloader, reload_on_sighup},
+    service::slot_poller::{PollerSettings, poll_with_commitments},
+    service::slot_source::{PubsubSlotSource, ingest_slot_updates},
+    service::snapshot_service::{spawn_snapshot_task, warm_start},
     signals::shutdown_signal,
     state::AppState,
+    telemetry::{TracingSettings, init_tracer_provider, otel_layer},
+    utils::redact::Redactor,
 };
 use solana_client::nonblocking::rpc_client::RpcClient;
-use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::net::TcpListener;
 use tokio::sync::broadcast;
-use tracing::info;
+use tracing::{info, warn};
+use tracing_subscriber::{Layer, filter::LevelFilter};
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tracing_subscriber::fmt::init();
+    let args = CliArgs::parse();
+    let layers = args.sources.load();
+    let config = match layers
+        .and_then(|layers| Config::from_layers(&layers).map(|config| (config, layers)))
+    {
+        Ok((config, layers)) if args.check_config => {
+            print!("{}", config.describe(&layers));
+            return Ok(());
+        }
+        Ok((config, _)) => config,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
 
-    let config = Config::from_env_file(".env").expect("Failed to load config");
+    let tracer_provider = TracingSettings::from_config(&config)
+        .map(|settings| init_tracer_provider(&settings).expect("Failed to build OTLP exporter"));
+    let span_layer = tracer_provider
+        .as_ref()
+        .map(|provider| otel_layer(provider).with_filter(LevelFilter::INFO).boxed());
+    let redactor = Arc::new(Redactor::from_config(&config));
+    let log_levels = init_logging(
+        config.log_format,
+        &config.log_filter,
+        span_layer,
+        redactor.clone(),
+    )
+    .expect("Failed to initialize logging");
 
     let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
 
-    let rpc_url = format!("{}{}", config.rpc_url, config.api_key);
-    let rpc_client: Arc<dyn RpcApi + Send + Sync> = Arc::new(RpcClient::new(rpc_url));
+    let upstream = UpstreamAuth::from_config(&config).expect("Invalid API_KEY");
+    let primary = upstream
+        .rpc_client(&config.rpc_url)
+        .expect("Failed to build the RPC client");
+    let mut endpoints: Vec<(String, Arc<dyn RpcApi + Send + Sync>)> = vec![(
+        "primary".to_string(),
+        Arc::new(RedactingRpc::new(Arc::new(primary), redactor.clone())),
+    )];
+    for (i, url) in config.rpc_fallback_urls.iter().enumerate() {
+        endpoints.push((
+            format!("fallback-{}", i + 1),
+            Arc::new(RedactingRpc::new(
+                Arc::new(RpcClient::new(url.clone())),
+                redactor.clone(),
+            )),
+        ));
+    }
+    let rpc_client: Arc<dyn RpcApi + Send + Sync> = Arc::new(RpcPool::new(endpoints));
     let cache = Arc::new(SlotCache::new(config.cache_capacity));
     let lru_cache = Arc::new(LruCache::new(config.lru_cache_capacity));
-    let metrics: Arc<dyn Metrics + Send + Sync> = Arc::new(LoggingMetrics);
+    let metrics: Arc<dyn Metrics + Send + Sync> = match config.metrics_backend {
+        MetricsBackend::Prometheus => Arc::new(PrometheusMetrics::new()),
+        MetricsBackend::Logging => Arc::new(LoggingMetrics),
+    };
     let circuit_breaker = Arc::new(CircuitBreaker::new(
         config.circuit_failure_threshold,
         config.circuit_open_duration,
     ));
 
-    poll_with_transient_retry_and_signals_and_breaker(
-        rpc_client.clone(),
-        cache.clone(),
-        metrics.clone(),
-        circuit_breaker.clone(),
-        config.poll_interval,
-        config.max_retries,
-        config.initial_backoff,
+    let backfill = Arc::new(BackfillManager::new(BackfillSettings::from_config(&config)));
+
+    let mut app_state = AppState::new(rpc_client, cache, lru_cache, metrics, circuit_breaker)
+        .with_backfill(backfill)
+        .with_health_settings(HealthSettings::from_config(&config))
+        .with_log_levels(Arc::new(log_levels))
+        .with_config_reloader(Arc::new(ConfigReloader::new(args.sources, config.clone())));
+    if let Some(token) = &config.admin_token {
+        app_state = app_state.with_admin_token(Arc::from(token.as_str()));
+    } else {
+        info!("ADMIN_TOKEN is not set, the admin API is disabled.");
+    }
+    let client_registry = match ClientSettings::from_config(&config) {
+        Some(settings) => {
+            let registry =
+                Arc::new(ClientRegistry::load(settings).expect("Failed to load client keys"));
+            info!(
+                "Client keys required for {} clients",
+                registry.client_names().len()
+            );
+            app_state = app_state.with_client_registry(registry.clone());
+            Some(registry)
+        }
+        None => {
+            warn!("No client keys configured, the API is open to every caller.");
+            None
+        }
+    };
+    if let Some(path) = &config.disk_store_path {
+        let disk_store =
+            DiskStore::open(path, config.disk_store_max_slots).expect("Failed to open disk store");
+        app_state = app_state.with_disk_store(Arc::new(disk_store));
+    }
+    if let Some(settings) = SharedCacheSettings::from_config(&config) {
+        app_state = app_state.with_shared_cache(Arc::new(SharedCache::new(settings)));
+    }
+    if let Some(settings) = BatcherSettings::from_config(&config) {
+        let batcher = FallbackBatcher::new(app_state.clone(), settings);
+        app_state = app_state.with_batcher(Arc::new(batcher));
+    }
+
+    // Restore before the poller starts, so it resumes from the snapshot cursors
+    let snapshot_task = match &config.snapshot_path {
+        Some(path) => {
+            warm_start(&app_state, path).await;
+            Some(spawn_snapshot_task(
+                app_state.clone(),
+                path.clone(),
+                config.snapshot_interval,
+                shutdown_tx.subscribe(),
+            ))
+        }
+        None => None,
+    };
+
+    poll_with_commitments(
+        app_state.clone(),
+        PollerSettings::from_config(&config),
         shutdown_rx,
     );
 
-    let app_state = AppState {
-        rpc_client,
-        cache,
-        lru_cache,
-        metrics,
-        circuit_breaker,
-    };
+    reload_on_sighup(app_state.clone(), shutdown_tx.subscribe());
+
+    if let Some(registry) = client_registry
+        && config.client_api_keys_file.is_some()
+    {
+        watch_client_keys(registry, shutdown_tx.subscribe());
+    }
+
+    // Epoch ranges of backfill requests are translated with the cluster's own schedule
+    if let Err(e) = epoch_schedule(&app_state).await {
+        warn!("{}, asking again on the next epoch range.", e);
+    }
+    let resumed = resume_backfills(&app_state).await;
+    if resumed > 0 {
+        info!("Resumed {} backfill jobs", resumed);
+    }
+
+    if let Some(ws_url) = &config.ws_url {
+        let source =
+            Arc::new(PubsubSlotSource::new(upstream.url(ws_url)).with_headers(upstream.headers()));
+        ingest_slot_updates(
+            source,
+            app_state.cache.clone(),
+            config.ws_reconnect_backoff,
+            shutdown_tx.subscribe(),
+        );
+    }
 
     let app = create_router(app_state);
-    let addr = SocketAddr::from(([0, 0, 0, 0], 8000));
-    let listener = TcpListener::bind(addr).await?;
 
-    info!("Running server on {}", addr);
+    let signal_tx = shutdown_tx.clone();
+    tokio::spawn(async move {
+        shutdown_signal().await;
+        let _ = signal_tx.send(());
+    });
+
+    serve(app, &ListenerSettings::from_config(&config), &shutdown_tx).await?;
+
+    if let Some(snapshot_task) = snapshot_task {
+        let _ = snapshot_task.await;
+    }
 
-    axum::serve(listener, app)
-        .with_graceful_shutdown(async move {
-            shutdown_signal().await;
-            let _ = shutdown_tx.send(());
-        })
-        .await?;
+    // Flushes the spans still waiting for the next batch
+    if let Some(tracer_provider) = tracer_provider
+        && let Err(e) = tracer_provider.shutdown()
+    {
+        warn!("Failed to flush traces on shutdown: {}", e);
+    }
 
     Ok(())
 }
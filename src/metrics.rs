@@ -1,10 +1,83 @@
-use std::time::Duration;
+use crate::{circuit_breaker::CircuitState, commitment::Commitment};
+use prometheus::{
+    Encoder, Histogram, HistogramOpts, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder, exponential_buckets,
+};
+use std::{fmt, str::FromStr, time::Duration};
 use tracing::info;
 
 pub trait Metrics: Send + Sync {
     fn record_latest_slot(&self, slot: u64);
     fn record_get_blocks_elapsed(&self, elapsed: Duration);
     fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+
+    /// Whether a lookup was answered by the given cache tier or fell through to the next one
+    fn record_cache_lookup(&self, _tier: CacheTier, _hit: bool) {}
+
+    /// Retries an RPC operation needed before it succeeded or gave up
+    fn record_retries(&self, _operation: &str, _retries: u32) {}
+
+    /// Distance between the chain tip and the latest cached slot at the commitment
+    fn record_poller_lag(&self, _commitment: Commitment, _lag: u64) {}
+
+    fn record_circuit_state(&self, _state: CircuitState) {}
+
+    /// A served HTTP request, `route` is the matched route pattern, not the raw path
+    fn record_http_request(&self, _route: &str, _status: u16) {}
+
+    /// Text exposition of everything recorded, `None` for backends that are not scraped
+    fn render(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Cache tiers consulted before RPC, in lookup order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheTier {
+    Primary,
+    Lru,
+    Disk,
+    Shared,
+}
+
+impl CacheTier {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CacheTier::Primary => "primary",
+            CacheTier::Lru => "lru",
+            CacheTier::Disk => "disk",
+            CacheTier::Shared => "shared",
+        }
+    }
+}
+
+/// Which `Metrics` implementation the service runs with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MetricsBackend {
+    #[default]
+    Prometheus,
+    Logging,
+}
+
+impl fmt::Display for MetricsBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetricsBackend::Prometheus => f.write_str("prometheus"),
+            MetricsBackend::Logging => f.write_str("logging"),
+        }
+    }
+}
+
+impl FromStr for MetricsBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "prometheus" => Ok(MetricsBackend::Prometheus),
+            "logging" => Ok(MetricsBackend::Logging),
+            other => Err(format!("Unknown metrics backend: {}", other)),
+        }
+    }
 }
 
 pub struct LoggingMetrics;
@@ -21,4 +94,169 @@ impl Metrics for LoggingMetrics {
     fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration) {
         info!(target: "metrics", elapsed_us = elapsed.as_micros(), "Recorded is_slot_confirmed duration");
     }
+
+    fn record_retries(&self, operation: &str, retries: u32) {
+        info!(target: "metrics", operation, retries, "Recorded RPC retries");
+    }
+
+    fn record_poller_lag(&self, commitment: Commitment, lag: u64) {
+        info!(target: "metrics", commitment = commitment.as_str(), lag, "Recorded poller lag");
+    }
+}
+
+/// Keeps every metric in a Prometheus registry, exposed through `GET /metrics`
+pub struct PrometheusMetrics {
+    registry: Registry,
+    latest_slot: IntGauge,
+    get_blocks_duration: Histogram,
+    is_slot_confirmed_duration: Histogram,
+    cache_lookups: IntCounterVec,
+    rpc_retries: IntCounterVec,
+    poller_lag: IntGaugeVec,
+    circuit_state: IntGaugeVec,
+    http_requests: IntCounterVec,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let latest_slot = IntGauge::new("latest_slot", "Latest slot seen by the poller").unwrap();
+        let get_blocks_duration = Histogram::with_opts(HistogramOpts::new(
+            "get_blocks_duration_seconds",
+            "Duration of get_blocks RPC calls, retries included",
+        ))
+        .unwrap();
+        // Cache hits answer in microseconds, RPC fallbacks in up to seconds
+        let is_slot_confirmed_duration = Histogram::with_opts(
+            HistogramOpts::new(
+                "is_slot_confirmed_duration_seconds",
+                "Duration of slot confirmation checks",
+            )
+            .buckets(exponential_buckets(0.0001, 4.0, 9).unwrap()),
+        )
+        .unwrap();
+        let cache_lookups = IntCounterVec::new(
+            Opts::new("cache_lookups_total", "Cache lookups by tier and result"),
+            &["tier", "result"],
+        )
+        .unwrap();
+        let rpc_retries = IntCounterVec::new(
+            Opts::new("rpc_retries_total", "Retried RPC calls by operation"),
+            &["operation"],
+        )
+        .unwrap();
+        let poller_lag = IntGaugeVec::new(
+            Opts::new(
+                "poller_lag_slots",
+                "Chain tip minus latest cached slot by commitment",
+            ),
+            &["commitment"],
+        )
+        .unwrap();
+        let circuit_state = IntGaugeVec::new(
+            Opts::new(
+                "circuit_breaker_state",
+                "1 for the current state of the RPC circuit breaker, 0 for the others",
+            ),
+            &["state"],
+        )
+        .unwrap();
+        let http_requests = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["route", "status"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(latest_slot.clone())).unwrap();
+        registry
+            .register(Box::new(get_blocks_duration.clone()))
+            .unwrap();
+        registry
+            .register(Box::new(is_slot_confirmed_duration.clone()))
+            .unwrap();
+        registry.register(Box::new(cache_lookups.clone())).unwrap();
+        registry.register(Box::new(rpc_retries.clone())).unwrap();
+        registry.register(Box::new(poller_lag.clone())).unwrap();
+        registry.register(Box::new(circuit_state.clone())).unwrap();
+        registry.register(Box::new(http_requests.clone())).unwrap();
+
+        Self {
+            registry,
+            latest_slot,
+            get_blocks_duration,
+            is_slot_confirmed_duration,
+            cache_lookups,
+            rpc_retries,
+            poller_lag,
+            circuit_state,
+            http_requests,
+        }
+    }
+}
+
+impl Default for PrometheusMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics for PrometheusMetrics {
+    fn record_latest_slot(&self, slot: u64) {
+        self.latest_slot.set(slot as i64);
+    }
+
+    fn record_get_blocks_elapsed(&self, elapsed: Duration) {
+        self.get_blocks_duration.observe(elapsed.as_secs_f64());
+    }
+
+    fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration) {
+        self.is_slot_confirmed_duration
+            .observe(elapsed.as_secs_f64());
+    }
+
+    fn record_cache_lookup(&self, tier: CacheTier, hit: bool) {
+        let result = if hit { "hit" } else { "miss" };
+        self.cache_lookups
+            .with_label_values(&[tier.as_str(), result])
+            .inc();
+    }
+
+    fn record_retries(&self, operation: &str, retries: u32) {
+        self.rpc_retries
+            .with_label_values(&[operation])
+            .inc_by(retries as u64);
+    }
+
+    fn record_poller_lag(&self, commitment: Commitment, lag: u64) {
+        self.poller_lag
+            .with_label_values(&[commitment.as_str()])
+            .set(lag as i64);
+    }
+
+    fn record_circuit_state(&self, state: CircuitState) {
+        for candidate in [
+            CircuitState::Closed,
+            CircuitState::Open,
+            CircuitState::HalfOpen,
+        ] {
+            self.circuit_state
+                .with_label_values(&[candidate.as_str()])
+                .set((candidate == state) as i64);
+        }
+    }
+
+    fn record_http_request(&self, route: &str, status: u16) {
+        self.http_requests
+            .with_label_values(&[route, &status.to_string()])
+            .inc();
+    }
+
+    fn render(&self) -> Option<String> {
+        let mut buffer = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buffer)
+            .ok()?;
+        String::from_utf8(buffer).ok()
+    }
 }
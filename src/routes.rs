@@ -1,19 +1,100 @@
 use crate::{
-    handler::cache_handler::{get_latest_slots_handler, get_lru_slots_handler},
+    handler::admin_handler::{
+        evict_slot_handler, flush_cache_handler, force_breaker_handler, get_breaker_handler,
+        get_client_handler, list_clients_handler, list_pins_handler, pause_poller_handler,
+        pin_slot_handler, reload_config_handler, require_admin_token, reset_client_usage_handler,
+        resize_cache_handler, resume_poller_handler, unpin_slot_handler,
+    },
+    handler::backfill_handler::{
+        cancel_backfill_handler, get_backfill_handler, list_backfills_handler,
+        start_backfill_handler,
+    },
+    handler::cache_handler::{
+        get_latest_slots_handler, get_lru_slots_handler, get_poller_status_handler,
+    },
+    handler::client_handler::require_client_key,
+    handler::confirmations_handler::batch_confirmation_handler,
+    handler::health_handler::{liveness_handler, readiness_handler},
+    handler::logging_handler::{get_log_filter_handler, set_log_filter_handler},
+    handler::metrics_handler::{get_metrics_handler, track_http_requests},
     handler::ping_handler::ping,
-    handler::slot_handler::check_slot_confirmation_handler,
+    handler::slot_handler::{
+        check_slot_confirmation_handler, get_slot_info_handler, get_slot_range_handler,
+    },
+    handler::trace_handler::trace_http_requests,
     state::AppState,
 };
-use axum::{Router, routing::get};
+use axum::{
+    Router, middleware,
+    routing::{delete, get, post, put},
+};
 
 pub fn create_router(app_state: AppState) -> Router {
+    // route_layer only wraps the routes added before it: the data endpoints need a client
+    // key, probes, metrics and the admin API (behind its own token) do not
     Router::new()
-        .route("/", get(ping))
         .route(
             "/isSlotConfirmed/{slot}",
             get(check_slot_confirmation_handler),
         )
+        .route("/slot/{slot}", get(get_slot_info_handler))
+        .route("/slots", get(get_slot_range_handler))
+        .route("/slots/confirmations", post(batch_confirmation_handler))
         .route("/cache/latest", get(get_latest_slots_handler))
         .route("/cache/lru", get(get_lru_slots_handler))
+        .route("/cache/poller", get(get_poller_status_handler))
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            require_client_key,
+        ))
+        .route("/", get(ping))
+        .route("/health/live", get(liveness_handler))
+        .route("/health/ready", get(readiness_handler))
+        .route("/metrics", get(get_metrics_handler))
+        .nest("/admin", admin_router(app_state.clone()))
+        .route_layer(middleware::from_fn_with_state(
+            app_state.clone(),
+            track_http_requests,
+        ))
+        .route_layer(middleware::from_fn(trace_http_requests))
         .with_state(app_state)
 }
+
+/// Operator endpoints, all behind the admin token
+fn admin_router(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/backfill",
+            get(list_backfills_handler).post(start_backfill_handler),
+        )
+        .route(
+            "/backfill/{id}",
+            get(get_backfill_handler).delete(cancel_backfill_handler),
+        )
+        .route(
+            "/log-level",
+            get(get_log_filter_handler).put(set_log_filter_handler),
+        )
+        .route("/cache/{tier}", delete(flush_cache_handler))
+        .route("/cache/{tier}/capacity", put(resize_cache_handler))
+        .route("/slots/{slot}", delete(evict_slot_handler))
+        .route("/slots/pins", get(list_pins_handler))
+        .route(
+            "/slots/{slot}/pin",
+            put(pin_slot_handler).delete(unpin_slot_handler),
+        )
+        .route(
+            "/circuit-breaker",
+            get(get_breaker_handler).put(force_breaker_handler),
+        )
+        .route("/poller/pause", post(pause_poller_handler))
+        .route("/poller/resume", post(resume_poller_handler))
+        .route("/config/reload", post(reload_config_handler))
+        .route("/clients", get(list_clients_handler))
+        .route("/clients/{name}", get(get_client_handler))
+        .route("/clients/{name}/usage", delete(reset_client_usage_handler))
+        .route_layer(middleware::from_fn_with_state(
+            app_state,
+            require_admin_token,
+        ))
+}
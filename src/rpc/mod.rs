@@ -1,3 +1,8 @@
+pub mod redacting_rpc;
 pub mod rpc_api;
+pub mod rpc_pool;
+pub mod upstream;
 
+pub use redacting_rpc::RedactingRpc;
 pub use rpc_api::RpcApi;
+pub use rpc_pool::RpcPool;
@@ -0,0 +1,64 @@
+use crate::commitment::Commitment;
+use crate::rpc::RpcApi;
+use crate::slot_info::BlockMetadata;
+use crate::utils::redact::Redactor;
+use solana_client::client_error::ClientError;
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::{future::Future, pin::Pin, sync::Arc};
+
+type RpcFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ClientError>> + Send + 'a>>;
+
+/// `RpcApi` wrapper scrubbing secrets from the errors of the wrapped client, so an API key
+/// in the endpoint URL never reaches a log line or a response through a `ClientError`.
+pub struct RedactingRpc {
+    inner: Arc<dyn RpcApi + Send + Sync>,
+    redactor: Arc<Redactor>,
+}
+
+impl RedactingRpc {
+    pub fn new(inner: Arc<dyn RpcApi + Send + Sync>, redactor: Arc<Redactor>) -> Self {
+        Self { inner, redactor }
+    }
+
+    fn redact<'a, T: Send + 'a>(&'a self, call: RpcFuture<'a, T>) -> RpcFuture<'a, T> {
+        Box::pin(async move { call.await.map_err(|e| self.redactor.redact_client_error(e)) })
+    }
+}
+
+impl RpcApi for RedactingRpc {
+    fn get_slot<'a>(&'a self) -> RpcFuture<'a, u64> {
+        self.redact(self.inner.get_slot())
+    }
+
+    fn get_blocks<'a>(&'a self, start_slot: u64, end_slot: Option<u64>) -> RpcFuture<'a, Vec<u64>> {
+        self.redact(self.inner.get_blocks(start_slot, end_slot))
+    }
+
+    fn get_slot_with_commitment<'a>(&'a self, commitment: Commitment) -> RpcFuture<'a, u64> {
+        self.redact(self.inner.get_slot_with_commitment(commitment))
+    }
+
+    fn get_blocks_with_commitment<'a>(
+        &'a self,
+        start_slot: u64,
+        end_slot: Option<u64>,
+        commitment: Commitment,
+    ) -> RpcFuture<'a, Vec<u64>> {
+        self.redact(
+            self.inner
+                .get_blocks_with_commitment(start_slot, end_slot, commitment),
+        )
+    }
+
+    fn get_block_metadata<'a>(
+        &'a self,
+        slot: u64,
+        commitment: Commitment,
+    ) -> RpcFuture<'a, Option<BlockMetadata>> {
+        self.redact(self.inner.get_block_metadata(slot, commitment))
+    }
+
+    fn get_epoch_schedule<'a>(&'a self) -> RpcFuture<'a, EpochSchedule> {
+        self.redact(self.inner.get_epoch_schedule())
+    }
+}
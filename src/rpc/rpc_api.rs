@@ -1,4 +1,17 @@
-use solana_client::{client_error::ClientError, nonblocking::rpc_client::RpcClient};
+use crate::commitment::Commitment;
+use crate::slot_info::BlockMetadata;
+use serde::Deserialize;
+use serde_json::json;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    nonblocking::rpc_client::RpcClient,
+    rpc_custom_error::{
+        JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+        JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+    },
+    rpc_request::{RpcError, RpcRequest},
+};
+use solana_sdk::epoch_schedule::EpochSchedule;
 use std::{future::Future, pin::Pin};
 
 pub trait RpcApi: Send + Sync {
@@ -11,6 +24,96 @@ pub trait RpcApi: Send + Sync {
         start_slot: u64,
         end_slot: Option<u64>,
     ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+
+    /// Latest slot that reached `commitment`.
+    /// Implementations without commitment support answer at their default commitment.
+    fn get_slot_with_commitment<'a>(
+        &'a self,
+        _commitment: Commitment,
+    ) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>> {
+        self.get_slot()
+    }
+
+    /// Blocks in the range that reached `commitment`.
+    /// Implementations without commitment support answer at their default commitment.
+    fn get_blocks_with_commitment<'a>(
+        &'a self,
+        start_slot: u64,
+        end_slot: Option<u64>,
+        _commitment: Commitment,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>> {
+        self.get_blocks(start_slot, end_slot)
+    }
+
+    /// Block fields of the slot at `commitment`, `None` if the slot has no block (yet).
+    /// Implementations without block support never report one.
+    fn get_block_metadata<'a>(
+        &'a self,
+        _slot: u64,
+        _commitment: Commitment,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<BlockMetadata>, ClientError>> + Send + 'a>> {
+        Box::pin(async { Ok(None) })
+    }
+
+    /// Epoch layout of the cluster, to translate epochs into slots.
+    /// Implementations without epoch support report the mainnet schedule.
+    fn get_epoch_schedule<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<EpochSchedule, ClientError>> + Send + 'a>> {
+        Box::pin(async { Ok(EpochSchedule::without_warmup()) })
+    }
+}
+
+// The subset of the getBlock response kept in the caches
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockResponse {
+    blockhash: String,
+    parent_slot: u64,
+    block_height: Option<u64>,
+    block_time: Option<i64>,
+}
+
+async fn fetch_block_metadata(
+    client: &RpcClient,
+    slot: u64,
+    commitment: Commitment,
+) -> Result<Option<BlockMetadata>, ClientError> {
+    // getBlock does not support `processed` either
+    let commitment = commitment.max(Commitment::Confirmed);
+    let config = json!({
+        "commitment": commitment.as_str(),
+        "transactionDetails": "none",
+        "rewards": false,
+        "maxSupportedTransactionVersion": 0,
+    });
+
+    match client
+        .send::<BlockResponse>(RpcRequest::GetBlock, json!([slot, config]))
+        .await
+    {
+        Ok(block) => Ok(Some(BlockMetadata {
+            blockhash: block.blockhash,
+            parent_slot: block.parent_slot,
+            block_height: block.block_height,
+            block_time: block.block_time,
+        })),
+        Err(e) if is_missing_block(&e) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+fn is_missing_block(error: &ClientError) -> bool {
+    matches!(
+        error.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if [
+                JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+                JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED,
+            ]
+            .contains(code)
+    )
 }
 
 impl RpcApi for RpcClient {
@@ -27,4 +130,36 @@ impl RpcApi for RpcClient {
     ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>> {
         Box::pin(self.get_blocks(start_slot, end_slot))
     }
+
+    fn get_slot_with_commitment<'a>(
+        &'a self,
+        commitment: Commitment,
+    ) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>> {
+        Box::pin(self.get_slot_with_commitment(commitment.into()))
+    }
+
+    fn get_blocks_with_commitment<'a>(
+        &'a self,
+        start_slot: u64,
+        end_slot: Option<u64>,
+        commitment: Commitment,
+    ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>> {
+        // getBlocks does not support `processed`, every confirmed block is also processed
+        let commitment = commitment.max(Commitment::Confirmed);
+        Box::pin(self.get_blocks_with_commitment(start_slot, end_slot, commitment.into()))
+    }
+
+    fn get_block_metadata<'a>(
+        &'a self,
+        slot: u64,
+        commitment: Commitment,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<BlockMetadata>, ClientError>> + Send + 'a>> {
+        Box::pin(fetch_block_metadata(self, slot, commitment))
+    }
+
+    fn get_epoch_schedule<'a>(
+        &'a self,
+    ) -> Pin<Box<dyn Future<Output = Result<EpochSchedule, ClientError>> + Send + 'a>> {
+        Box::pin(self.get_epoch_schedule())
+    }
 }
@@ -0,0 +1,247 @@
+use crate::commitment::Commitment;
+use crate::rpc::RpcApi;
+use crate::slot_info::BlockMetadata;
+use crate::utils::error_utils::IsTransient;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::{Instrument, info_span, warn};
+
+/// Weight of the newest sample in the latency and error EWMAs.
+const DEFAULT_EWMA_ALPHA: f64 = 0.2;
+
+/// Latency assumed for an endpoint that has not answered yet, so that a fresh
+/// fallback is neither starved nor preferred over a healthy primary.
+const INITIAL_LATENCY_MS: f64 = 100.0;
+
+/// Score penalty (in milliseconds) of an endpoint whose error rate is 100%.
+const ERROR_PENALTY_MS: f64 = 1_000.0;
+
+/// The error rate of an endpoint halves every `ERROR_HALF_LIFE` without new
+/// failures, so an endpoint that stopped receiving traffic gets retried again.
+const ERROR_HALF_LIFE: Duration = Duration::from_secs(30);
+
+type RpcFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, ClientError>> + Send + 'a>>;
+
+#[derive(Debug)]
+struct EndpointHealth {
+    latency_ms: f64,
+    error_rate: f64,
+    // Instant at which `error_rate` was last computed, decay starts from there
+    error_rate_at: Option<Instant>,
+}
+
+impl EndpointHealth {
+    fn new() -> Self {
+        Self {
+            latency_ms: INITIAL_LATENCY_MS,
+            error_rate: 0.0,
+            error_rate_at: None,
+        }
+    }
+
+    fn decayed_error_rate(&self, now: Instant) -> f64 {
+        match self.error_rate_at {
+            Some(at) => {
+                let half_lives =
+                    now.duration_since(at).as_secs_f64() / ERROR_HALF_LIFE.as_secs_f64();
+                self.error_rate * 0.5_f64.powf(half_lives)
+            }
+            None => self.error_rate,
+        }
+    }
+
+    fn score(&self, now: Instant) -> f64 {
+        self.latency_ms + self.decayed_error_rate(now) * ERROR_PENALTY_MS
+    }
+}
+
+struct RpcEndpoint {
+    name: String,
+    client: Arc<dyn RpcApi + Send + Sync>,
+    health: Mutex<EndpointHealth>,
+}
+
+/// Point-in-time view of an endpoint's health, lowest `score` is preferred.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndpointHealthSnapshot {
+    pub name: String,
+    pub latency_ewma_ms: f64,
+    pub error_rate: f64,
+    pub score: f64,
+}
+
+/// `RpcApi` implementation spreading calls over several upstream endpoints.
+///
+/// Each call goes to the endpoint with the best health score (EWMA latency plus
+/// a penalty for its EWMA error rate). Transient errors fail over to the next
+/// best endpoint; non-transient errors are returned as-is since every endpoint
+/// would answer the same.
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    alpha: f64,
+}
+
+impl RpcPool {
+    pub fn new(endpoints: Vec<(String, Arc<dyn RpcApi + Send + Sync>)>) -> Self {
+        Self {
+            endpoints: endpoints
+                .into_iter()
+                .map(|(name, client)| RpcEndpoint {
+                    name,
+                    client,
+                    health: Mutex::new(EndpointHealth::new()),
+                })
+                .collect(),
+            alpha: DEFAULT_EWMA_ALPHA,
+        }
+    }
+
+    pub fn with_ewma_alpha(mut self, alpha: f64) -> Self {
+        self.alpha = alpha.clamp(f64::EPSILON, 1.0);
+        self
+    }
+
+    pub fn health_snapshot(&self) -> Vec<EndpointHealthSnapshot> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let health = endpoint.health.lock().unwrap();
+                EndpointHealthSnapshot {
+                    name: endpoint.name.clone(),
+                    latency_ewma_ms: health.latency_ms,
+                    error_rate: health.decayed_error_rate(now),
+                    score: health.score(now),
+                }
+            })
+            .collect()
+    }
+
+    /// Endpoint indices ordered from healthiest to least healthy.
+    /// Ties keep configuration order, so the primary wins among equals.
+    fn ranked(&self) -> Vec<usize> {
+        let now = Instant::now();
+        let mut scored: Vec<(usize, f64)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(i, endpoint)| (i, endpoint.health.lock().unwrap().score(now)))
+            .collect();
+        scored.sort_by(|a, b| a.1.total_cmp(&b.1));
+        scored.into_iter().map(|(i, _)| i).collect()
+    }
+
+    fn record_success(&self, index: usize, elapsed: Duration) {
+        let mut health = self.endpoints[index].health.lock().unwrap();
+        let elapsed_ms = elapsed.as_secs_f64() * 1_000.0;
+        let now = Instant::now();
+        health.latency_ms = self.alpha * elapsed_ms + (1.0 - self.alpha) * health.latency_ms;
+        health.error_rate = (1.0 - self.alpha) * health.decayed_error_rate(now);
+        if health.error_rate_at.is_some() {
+            health.error_rate_at = Some(now);
+        }
+    }
+
+    fn record_failure(&self, index: usize) {
+        let mut health = self.endpoints[index].health.lock().unwrap();
+        let now = Instant::now();
+        health.error_rate = self.alpha + (1.0 - self.alpha) * health.decayed_error_rate(now);
+        health.error_rate_at = Some(now);
+    }
+
+    async fn route<'a, T, F>(&'a self, operation_name: &str, call: F) -> Result<T, ClientError>
+    where
+        F: Fn(&'a (dyn RpcApi + Send + Sync)) -> RpcFuture<'a, T>,
+    {
+        let ranked = self.ranked();
+        let mut last_error = None;
+
+        for (attempt, &index) in ranked.iter().enumerate() {
+            let endpoint = &self.endpoints[index];
+            let started = Instant::now();
+
+            let span = info_span!(
+                "rpc",
+                rpc.method = operation_name,
+                rpc.endpoint = %endpoint.name,
+                rpc.attempt = attempt + 1
+            );
+            match call(endpoint.client.as_ref()).instrument(span).await {
+                Ok(value) => {
+                    self.record_success(index, started.elapsed());
+                    return Ok(value);
+                }
+                Err(e) => {
+                    // A non-transient error is still a well-formed answer from a healthy endpoint
+                    if !e.is_transient() {
+                        self.record_success(index, started.elapsed());
+                        return Err(e);
+                    }
+                    self.record_failure(index);
+                    if attempt + 1 < ranked.len() {
+                        warn!(
+                            "Operation '{}' failed on endpoint '{}' with transient error: {}. Failing over.",
+                            operation_name, endpoint.name, e
+                        );
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ClientError::from(ClientErrorKind::Custom(
+                "No RPC endpoints configured".to_string(),
+            ))
+        }))
+    }
+}
+
+impl RpcApi for RpcPool {
+    fn get_slot<'a>(&'a self) -> RpcFuture<'a, u64> {
+        Box::pin(self.route("get_slot", |client| client.get_slot()))
+    }
+
+    fn get_blocks<'a>(&'a self, start_slot: u64, end_slot: Option<u64>) -> RpcFuture<'a, Vec<u64>> {
+        Box::pin(self.route("get_blocks", move |client| {
+            client.get_blocks(start_slot, end_slot)
+        }))
+    }
+
+    fn get_slot_with_commitment<'a>(&'a self, commitment: Commitment) -> RpcFuture<'a, u64> {
+        Box::pin(self.route("get_slot", move |client| {
+            client.get_slot_with_commitment(commitment)
+        }))
+    }
+
+    fn get_blocks_with_commitment<'a>(
+        &'a self,
+        start_slot: u64,
+        end_slot: Option<u64>,
+        commitment: Commitment,
+    ) -> RpcFuture<'a, Vec<u64>> {
+        Box::pin(self.route("get_blocks", move |client| {
+            client.get_blocks_with_commitment(start_slot, end_slot, commitment)
+        }))
+    }
+
+    fn get_block_metadata<'a>(
+        &'a self,
+        slot: u64,
+        commitment: Commitment,
+    ) -> RpcFuture<'a, Option<BlockMetadata>> {
+        Box::pin(self.route("get_block", move |client| {
+            client.get_block_metadata(slot, commitment)
+        }))
+    }
+
+    fn get_epoch_schedule<'a>(&'a self) -> RpcFuture<'a, EpochSchedule> {
+        Box::pin(self.route("get_epoch_schedule", |client| client.get_epoch_schedule()))
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::Config;
+use reqwest::header::{AUTHORIZATION, HeaderMap, HeaderName, HeaderValue, InvalidHeaderValue};
+use solana_client::{nonblocking::rpc_client::RpcClient, rpc_client::RpcClientConfig};
+use solana_rpc_client::http_sender::HttpSender;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Request timeout of the upstream client, the same as `RpcClient::new`
+const RPC_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How the upstream API key is sent to the provider
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApiKeyAuth {
+    /// Appended to SOLANA_RPC_URL and SOLANA_WS_URL
+    #[default]
+    Url,
+    /// As the value of the API_KEY_HEADER header
+    Header,
+    /// As `Authorization: Bearer <key>`
+    Bearer,
+}
+
+impl fmt::Display for ApiKeyAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeyAuth::Url => f.write_str("url"),
+            ApiKeyAuth::Header => f.write_str("header"),
+            ApiKeyAuth::Bearer => f.write_str("bearer"),
+        }
+    }
+}
+
+impl FromStr for ApiKeyAuth {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "url" => Ok(ApiKeyAuth::Url),
+            "header" => Ok(ApiKeyAuth::Header),
+            "bearer" => Ok(ApiKeyAuth::Bearer),
+            other => Err(format!("Unknown API key auth: {}", other)),
+        }
+    }
+}
+
+/// Credentials sent to the upstream provider, either in the endpoint URLs or as headers
+#[derive(Clone)]
+pub struct UpstreamAuth {
+    api_key: String,
+    auth: ApiKeyAuth,
+    headers: HeaderMap,
+}
+
+impl fmt::Debug for UpstreamAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UpstreamAuth")
+            .field("auth", &self.auth)
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+impl UpstreamAuth {
+    pub fn new(
+        api_key: &str,
+        auth: ApiKeyAuth,
+        header: HeaderName,
+    ) -> Result<Self, InvalidHeaderValue> {
+        let mut headers = HeaderMap::new();
+        let value = match auth {
+            ApiKeyAuth::Url => None,
+            ApiKeyAuth::Header => Some((header, HeaderValue::from_str(api_key)?)),
+            ApiKeyAuth::Bearer => Some((
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", api_key))?,
+            )),
+        };
+        if let Some((name, mut value)) = value {
+            // Keeps the key out of the HTTP client's own debug output
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+        Ok(Self {
+            api_key: api_key.to_string(),
+            auth,
+            headers,
+        })
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, InvalidHeaderValue> {
+        Self::new(
+            &config.api_key,
+            config.api_key_auth,
+            config.api_key_header.clone(),
+        )
+    }
+
+    /// The endpoint URL to connect to, with the key appended in `url` mode
+    pub fn url(&self, base_url: &str) -> String {
+        match self.auth {
+            ApiKeyAuth::Url => format!("{}{}", base_url, self.api_key),
+            ApiKeyAuth::Header | ApiKeyAuth::Bearer => base_url.to_string(),
+        }
+    }
+
+    /// Headers carrying the key, empty in `url` mode
+    pub fn headers(&self) -> HeaderMap {
+        self.headers.clone()
+    }
+
+    /// RPC client for the provider endpoint, sending the key with every request
+    pub fn rpc_client(&self, base_url: &str) -> Result<RpcClient, reqwest::Error> {
+        let mut headers = HttpSender::default_headers();
+        headers.extend(self.headers());
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .timeout(RPC_TIMEOUT)
+            .pool_idle_timeout(RPC_TIMEOUT)
+            .build()?;
+        Ok(RpcClient::new_sender(
+            HttpSender::new_with_client(self.url(base_url), client),
+            RpcClientConfig::with_commitment(CommitmentConfig::default()),
+        ))
+    }
+}
@@ -0,0 +1,161 @@
+use crate::{circuit_breaker::CircuitState, state::AppState};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// In-memory cache tier targeted by an admin operation, the subset of
+/// [`crate::metrics::CacheTier`] that can be flushed and resized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryTier {
+    Primary,
+    Lru,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlushResult {
+    pub tier: MemoryTier,
+    /// Number of slots dropped from the tier
+    pub flushed: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityUpdate {
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ResizeResult {
+    pub tier: MemoryTier,
+    pub capacity: usize,
+    /// Number of slots evicted to fit the new capacity
+    pub evicted: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SlotPin {
+    pub slot: u64,
+    pub pinned: bool,
+}
+
+/// State an operator can force the circuit breaker into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakerOverride {
+    Open,
+    Closed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakerUpdate {
+    pub state: BreakerOverride,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BreakerStatus {
+    pub state: CircuitState,
+    /// Whether the circuit stays open until an operator closes it
+    pub forced: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PollerPause {
+    pub paused: bool,
+}
+
+/// Drops every slot of the tier, the poller refills the primary cache on its next round
+pub async fn flush_tier(app_state: &AppState, tier: MemoryTier) -> FlushResult {
+    let flushed = match tier {
+        MemoryTier::Primary => app_state.cache.clear().await,
+        MemoryTier::Lru => app_state.lru_cache.clear().await,
+    };
+    info!("Flushed {} slots from the {:?} cache", flushed, tier);
+    FlushResult { tier, flushed }
+}
+
+/// Changes the capacity of the tier until the next restart, evicting the oldest slots beyond it
+pub async fn resize_tier(
+    app_state: &AppState,
+    tier: MemoryTier,
+    capacity: usize,
+) -> Result<ResizeResult, String> {
+    if capacity == 0 {
+        return Err("capacity must be greater than 0".to_string());
+    }
+    let evicted = match tier {
+        MemoryTier::Primary => app_state.cache.resize(capacity).await,
+        MemoryTier::Lru => app_state.lru_cache.resize(capacity).await,
+    };
+    info!(
+        "Resized the {:?} cache to {} slots, evicted {}",
+        tier, capacity, evicted
+    );
+    Ok(ResizeResult {
+        tier,
+        capacity,
+        evicted,
+    })
+}
+
+/// Forgets the slot in both in-memory tiers, returns false if neither had it.
+/// The slot is looked up again further down the chain on the next request.
+pub async fn evict_slot(app_state: &AppState, slot: u64) -> bool {
+    let from_primary = app_state.cache.remove(&slot).await;
+    let from_lru = app_state.lru_cache.remove(&slot).await;
+    if from_primary || from_lru {
+        info!("Evicted slot {} from the in-memory caches", slot);
+    }
+    from_primary || from_lru
+}
+
+/// Keeps the slot in the LRU cache until it is unpinned, copying it over from the primary
+/// cache if needed. Returns false if neither tier has the slot.
+pub async fn pin_slot(app_state: &AppState, slot: u64) -> bool {
+    if !app_state.lru_cache.get(&slot).await
+        && let Some(info) = app_state.cache.get_info(&slot).await
+    {
+        app_state.lru_cache.put_info(info).await;
+    }
+    let pinned = app_state.lru_cache.pin(slot).await;
+    if pinned {
+        info!("Pinned slot {} in the LRU cache", slot);
+    }
+    pinned
+}
+
+/// Makes the slot evictable again, returns false if it was not pinned
+pub async fn unpin_slot(app_state: &AppState, slot: u64) -> bool {
+    let unpinned = app_state.lru_cache.unpin(&slot).await;
+    if unpinned {
+        info!("Unpinned slot {} in the LRU cache", slot);
+    }
+    unpinned
+}
+
+/// Slots currently pinned in the LRU cache
+pub async fn pinned_slots(app_state: &AppState) -> Vec<u64> {
+    app_state.lru_cache.pinned().await
+}
+
+pub async fn get_breaker_status(app_state: &AppState) -> BreakerStatus {
+    BreakerStatus {
+        state: app_state.circuit_breaker.state().await,
+        forced: app_state.circuit_breaker.is_forced_open().await,
+    }
+}
+
+/// Forces the circuit breaker open, rejecting all RPC calls until closed again, or closed,
+/// resetting the failure count
+pub async fn force_breaker(app_state: &AppState, update: BreakerOverride) -> BreakerStatus {
+    match update {
+        BreakerOverride::Open => app_state.circuit_breaker.force_open().await,
+        BreakerOverride::Closed => app_state.circuit_breaker.force_closed().await,
+    }
+    get_breaker_status(app_state).await
+}
+
+/// Pauses or resumes the background poller, a paused poller skips its rounds
+pub fn set_poller_paused(app_state: &AppState, paused: bool) -> PollerPause {
+    app_state.poller.set_paused(paused);
+    info!("Slot poller {}", if paused { "paused" } else { "resumed" });
+    PollerPause { paused }
+}
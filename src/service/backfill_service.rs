@@ -0,0 +1,542 @@
+use crate::{
+    cache::StoredSlot, commitment::Commitment, config::Config, state::AppState,
+    utils::rate_budget::RateBudget,
+};
+use serde::{Deserialize, Serialize};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::{
+    collections::{BTreeMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    fs,
+    sync::{OnceCell, watch},
+    time::sleep,
+};
+use tracing::{info, warn};
+
+#[derive(Debug, Clone)]
+pub struct BackfillSettings {
+    /// Upper bound of the number of slots covered by one `get_blocks` call
+    pub chunk_size: u64,
+    /// RPC calls all backfill jobs together may issue per second
+    pub max_rpc_per_sec: u32,
+    /// How long a job waits after a failed call, or while live traffic sees an open circuit
+    pub retry_backoff: Duration,
+    /// File the job list is checkpointed to, so jobs resume after a restart
+    pub checkpoint_path: Option<PathBuf>,
+}
+
+impl Default for BackfillSettings {
+    fn default() -> Self {
+        Self {
+            chunk_size: 1000,
+            max_rpc_per_sec: 5,
+            retry_backoff: Duration::from_secs(5),
+            checkpoint_path: None,
+        }
+    }
+}
+
+impl BackfillSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            chunk_size: config.backfill_chunk_size.max(1),
+            max_rpc_per_sec: config.backfill_max_rpc_per_sec.max(1),
+            retry_backoff: config.backfill_retry_backoff,
+            checkpoint_path: config.backfill_checkpoint_path.clone(),
+        }
+    }
+}
+
+/// Range to pre-load, given either as slots or as epochs (both inclusive)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BackfillRequest {
+    pub start_slot: Option<u64>,
+    pub end_slot: Option<u64>,
+    pub start_epoch: Option<u64>,
+    pub end_epoch: Option<u64>,
+    pub commitment: Option<Commitment>,
+}
+
+impl BackfillRequest {
+    /// The inclusive slot range of the request, or why it is invalid.
+    /// Epoch ranges are translated with the cluster's epoch schedule.
+    pub fn slot_range(&self, schedule: Option<&EpochSchedule>) -> Result<(u64, u64), String> {
+        let (start, end) = match (
+            self.start_slot,
+            self.end_slot,
+            self.start_epoch,
+            self.end_epoch,
+        ) {
+            (Some(start), Some(end), None, None) => (start, end),
+            (None, None, Some(start_epoch), Some(end_epoch)) => {
+                let schedule = schedule.ok_or("The epoch schedule is unknown")?;
+                let end_slot = end_epoch
+                    .checked_add(1)
+                    .and_then(|next_epoch| first_slot_in_epoch(schedule, next_epoch))
+                    .ok_or("End is out of range")?;
+                (
+                    first_slot_in_epoch(schedule, start_epoch).unwrap_or(u64::MAX),
+                    end_slot - 1,
+                )
+            }
+            _ => {
+                return Err(
+                    "Provide either start_slot and end_slot, or start_epoch and end_epoch"
+                        .to_string(),
+                );
+            }
+        };
+        if start > end {
+            return Err(format!("Start {} is after end {}", start, end));
+        }
+        if end == u64::MAX {
+            return Err("End is out of range".to_string());
+        }
+        Ok((start, end))
+    }
+}
+
+// Like `EpochSchedule::get_first_slot_in_epoch`, but `None` instead of overflowing
+fn first_slot_in_epoch(schedule: &EpochSchedule, epoch: u64) -> Option<u64> {
+    if epoch <= schedule.first_normal_epoch {
+        return Some(schedule.get_first_slot_in_epoch(epoch));
+    }
+    (epoch - schedule.first_normal_epoch)
+        .checked_mul(schedule.slots_per_epoch)?
+        .checked_add(schedule.first_normal_slot)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackfillJob {
+    pub id: u64,
+    pub start_slot: u64,
+    pub end_slot: u64,
+    pub commitment: Commitment,
+    pub status: JobStatus,
+    /// First slot not loaded yet, a resumed job continues from here
+    pub next_slot: u64,
+    pub confirmed_slots: u64,
+    pub skipped_slots: u64,
+    /// Failed RPC calls, each retried after the backoff
+    pub failed_calls: u64,
+    /// Unix milliseconds
+    pub created_at: u64,
+    pub updated_at: u64,
+}
+
+impl BackfillJob {
+    pub fn total_slots(&self) -> u64 {
+        self.end_slot - self.start_slot + 1
+    }
+
+    pub fn processed_slots(&self) -> u64 {
+        self.next_slot - self.start_slot
+    }
+}
+
+struct JobEntry {
+    job: BackfillJob,
+    cancel: watch::Sender<bool>,
+}
+
+/// Runs backfill jobs that pre-load historical slot ranges into the LRU and persistent tiers.
+///
+/// Jobs share an RPC rate budget, bypass the circuit breaker of live traffic so their
+/// failures never open it, and pause while it is open. Progress is checkpointed after
+/// every chunk when a checkpoint path is configured.
+pub struct BackfillManager {
+    settings: BackfillSettings,
+    budget: RateBudget,
+    jobs: Mutex<BTreeMap<u64, JobEntry>>,
+    // Serializes checkpoint writes, so an older job list never overwrites a newer one
+    checkpoint_lock: tokio::sync::Mutex<()>,
+    epoch_schedule: OnceCell<EpochSchedule>,
+}
+
+impl BackfillManager {
+    pub fn new(settings: BackfillSettings) -> Self {
+        Self {
+            budget: RateBudget::new(settings.max_rpc_per_sec),
+            settings,
+            jobs: Mutex::new(BTreeMap::new()),
+            checkpoint_lock: tokio::sync::Mutex::new(()),
+            epoch_schedule: OnceCell::new(),
+        }
+    }
+
+    pub fn get(&self, id: u64) -> Option<BackfillJob> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|entry| entry.job.clone())
+    }
+
+    pub fn list(&self) -> Vec<BackfillJob> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.job.clone())
+            .collect()
+    }
+
+    fn insert(&self, job: BackfillJob) -> watch::Receiver<bool> {
+        let (cancel, cancel_rx) = watch::channel(false);
+        self.jobs
+            .lock()
+            .unwrap()
+            .insert(job.id, JobEntry { job, cancel });
+        cancel_rx
+    }
+
+    // Allocates the next id and registers the job under it in one step
+    fn create(
+        &self,
+        job_with_id: impl FnOnce(u64) -> BackfillJob,
+    ) -> (BackfillJob, watch::Receiver<bool>) {
+        let mut jobs = self.jobs.lock().unwrap();
+        let id = jobs.keys().next_back().map_or(1, |id| id + 1);
+        let job = job_with_id(id);
+        let (cancel, cancel_rx) = watch::channel(false);
+        jobs.insert(
+            id,
+            JobEntry {
+                job: job.clone(),
+                cancel,
+            },
+        );
+        (job, cancel_rx)
+    }
+
+    fn update(&self, id: u64, update: impl FnOnce(&mut BackfillJob)) -> Option<BackfillJob> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(&id)?;
+        update(&mut entry.job);
+        entry.job.updated_at = unix_millis();
+        Some(entry.job.clone())
+    }
+
+    async fn checkpoint(&self) {
+        let Some(path) = &self.settings.checkpoint_path else {
+            return;
+        };
+        let _guard = self.checkpoint_lock.lock().await;
+        let jobs = self.list();
+        if let Err(e) = write_checkpoint(path, &jobs).await {
+            warn!(
+                "Failed to write backfill checkpoint to {}: {}",
+                path.display(),
+                e
+            );
+        }
+    }
+}
+
+/// The epoch schedule of the upstream cluster, asked for once and kept, called at startup
+/// so that epoch ranges do not wait for it
+pub async fn epoch_schedule(app_state: &AppState) -> Result<EpochSchedule, String> {
+    app_state
+        .backfill
+        .epoch_schedule
+        .get_or_try_init(|| async {
+            app_state
+                .rpc_client
+                .get_epoch_schedule()
+                .await
+                .map_err(|e| format!("Failed to get the epoch schedule: {}", e))
+        })
+        .await
+        .cloned()
+}
+
+/// Starts a job loading the requested range
+pub async fn start_backfill(
+    app_state: &AppState,
+    request: &BackfillRequest,
+) -> Result<BackfillJob, String> {
+    // Only epoch ranges need the schedule, slot ranges never wait for it
+    let schedule = match request.start_epoch.or(request.end_epoch) {
+        Some(_) => Some(epoch_schedule(app_state).await?),
+        None => None,
+    };
+    let (start_slot, end_slot) = request.slot_range(schedule.as_ref())?;
+    // Historical slots are settled, and getBlocks has no processed view
+    let commitment = request
+        .commitment
+        .unwrap_or(Commitment::Finalized)
+        .max(Commitment::Confirmed);
+
+    let manager = &app_state.backfill;
+    let now = unix_millis();
+    let (job, cancel_rx) = manager.create(|id| BackfillJob {
+        id,
+        start_slot,
+        end_slot,
+        commitment,
+        status: JobStatus::Running,
+        next_slot: start_slot,
+        confirmed_slots: 0,
+        skipped_slots: 0,
+        failed_calls: 0,
+        created_at: now,
+        updated_at: now,
+    });
+    info!(
+        "Starting backfill job {} for slots {}..={} at {} commitment",
+        job.id, start_slot, end_slot, commitment
+    );
+
+    manager.checkpoint().await;
+    tokio::spawn(run_job(app_state.clone(), job.id, cancel_rx));
+    Ok(job)
+}
+
+pub async fn get_backfill(app_state: &AppState, id: u64) -> Option<BackfillJob> {
+    app_state.backfill.get(id)
+}
+
+pub async fn list_backfills(app_state: &AppState) -> Vec<BackfillJob> {
+    app_state.backfill.list()
+}
+
+/// Stops a running job, its checkpoint is kept. Returns `None` for unknown jobs.
+pub async fn cancel_backfill(app_state: &AppState, id: u64) -> Option<BackfillJob> {
+    let manager = &app_state.backfill;
+    let job = manager.update(id, |job| {
+        if job.status == JobStatus::Running {
+            job.status = JobStatus::Cancelled;
+        }
+    })?;
+    if let Some(entry) = manager.jobs.lock().unwrap().get(&id) {
+        let _ = entry.cancel.send(true);
+    }
+    info!("Backfill job {} cancelled", id);
+    manager.checkpoint().await;
+    Some(job)
+}
+
+/// Loads the checkpointed jobs and resumes the running ones. Returns how many were resumed.
+pub async fn resume_backfills(app_state: &AppState) -> usize {
+    let manager = &app_state.backfill;
+    let Some(path) = &manager.settings.checkpoint_path else {
+        return 0;
+    };
+    let jobs = match load_checkpoint(path).await {
+        Ok(jobs) => jobs,
+        Err(e) => {
+            warn!(
+                "Ignoring unreadable backfill checkpoint at {}: {}",
+                path.display(),
+                e
+            );
+            return 0;
+        }
+    };
+
+    let mut resumed = 0;
+    for job in jobs {
+        let running = job.status == JobStatus::Running;
+        let id = job.id;
+        let cancel_rx = manager.insert(job);
+        if running {
+            info!("Resuming backfill job {} from its checkpoint", id);
+            tokio::spawn(run_job(app_state.clone(), id, cancel_rx));
+            resumed += 1;
+        }
+    }
+    resumed
+}
+
+async fn run_job(app_state: AppState, id: u64, mut cancel_rx: watch::Receiver<bool>) {
+    let manager = &app_state.backfill;
+    let settings = &manager.settings;
+    let mut known_tip = None;
+
+    loop {
+        let Some(job) = manager.get(id) else {
+            return;
+        };
+        if job.status != JobStatus::Running || *cancel_rx.borrow() {
+            return;
+        }
+        if job.next_slot > job.end_slot {
+            manager.update(id, |job| job.status = JobStatus::Completed);
+            manager.checkpoint().await;
+            info!(
+                "Backfill job {} completed: {} confirmed and {} skipped slots",
+                id, job.confirmed_slots, job.skipped_slots
+            );
+            return;
+        }
+
+        // Live traffic has priority, stay away from an RPC endpoint it sees failing
+        if app_state.circuit_breaker.is_open().await {
+            if wait_or_cancel(settings.retry_backoff, &mut cancel_rx).await {
+                return;
+            }
+            continue;
+        }
+
+        let chunk_end = job
+            .end_slot
+            .min(job.next_slot.saturating_add(settings.chunk_size - 1));
+        // A skipped slot can only be told from a future one behind the tip
+        if known_tip.is_none_or(|tip| tip < chunk_end) {
+            manager.budget.acquire().await;
+            match app_state
+                .rpc_client
+                .get_slot_with_commitment(job.commitment)
+                .await
+            {
+                Ok(tip) => known_tip = Some(tip),
+                Err(e) => {
+                    warn!("Backfill job {} failed to get the chain tip: {}", id, e);
+                    manager.update(id, |job| job.failed_calls += 1);
+                    if wait_or_cancel(settings.retry_backoff, &mut cancel_rx).await {
+                        return;
+                    }
+                    continue;
+                }
+            }
+        }
+        let tip = known_tip.unwrap_or_default();
+        if tip < job.next_slot {
+            if wait_or_cancel(settings.retry_backoff, &mut cancel_rx).await {
+                return;
+            }
+            known_tip = None;
+            continue;
+        }
+        let chunk_end = chunk_end.min(tip);
+
+        manager.budget.acquire().await;
+        let blocks = match app_state
+            .rpc_client
+            .get_blocks_with_commitment(job.next_slot, Some(chunk_end), job.commitment)
+            .await
+        {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                warn!(
+                    "Backfill job {} failed to fetch slots {}..={}: {}",
+                    id, job.next_slot, chunk_end, e
+                );
+                manager.update(id, |job| job.failed_calls += 1);
+                if wait_or_cancel(settings.retry_backoff, &mut cancel_rx).await {
+                    return;
+                }
+                continue;
+            }
+        };
+
+        let confirmed: HashSet<u64> = blocks
+            .into_iter()
+            .filter(|slot| (job.next_slot..=chunk_end).contains(slot))
+            .collect();
+        let confirmed_slots = confirmed.len() as u64;
+        let skipped_slots = chunk_end + 1 - job.next_slot - confirmed_slots;
+        let observations: Vec<(u64, StoredSlot)> = (job.next_slot..=chunk_end)
+            .map(|slot| {
+                let stored = if confirmed.contains(&slot) {
+                    StoredSlot::Confirmed(job.commitment)
+                } else {
+                    StoredSlot::Skipped(job.commitment)
+                };
+                (slot, stored)
+            })
+            .collect();
+        store_observations(&app_state, observations).await;
+
+        manager.update(id, |job| {
+            job.next_slot = chunk_end + 1;
+            job.confirmed_slots += confirmed_slots;
+            job.skipped_slots += skipped_slots;
+        });
+        manager.checkpoint().await;
+    }
+}
+
+// Historical ranges can be far larger than the LRU cache, so they only go to the LRU cache
+// when no persistent tier kept them. The shared cache only keeps finalized entries for good,
+// weaker ones expire after its TTL.
+async fn store_observations(app_state: &AppState, observations: Vec<(u64, StoredSlot)>) {
+    let mut persisted = false;
+    if let Some(disk_store) = &app_state.disk_store {
+        persisted |= disk_store.record_all(observations.iter().copied()).await;
+    }
+    if let Some(shared_cache) = &app_state.shared_cache {
+        let finalized = observations
+            .iter()
+            .all(|(_, stored)| stored.commitment() >= Commitment::Finalized);
+        persisted |= shared_cache.put_many(&observations).await && finalized;
+    }
+    if persisted {
+        return;
+    }
+    for (slot, stored) in observations {
+        match stored {
+            StoredSlot::Confirmed(commitment) => {
+                app_state
+                    .lru_cache
+                    .put_with_commitment(slot, commitment)
+                    .await
+            }
+            StoredSlot::Skipped(commitment) => {
+                app_state.lru_cache.put_skipped(slot, commitment).await
+            }
+        }
+    }
+}
+
+// Returns `true` if the job was cancelled while waiting
+async fn wait_or_cancel(duration: Duration, cancel_rx: &mut watch::Receiver<bool>) -> bool {
+    let cancelled = tokio::select! {
+        _ = sleep(duration) => false,
+        result = cancel_rx.wait_for(|cancelled| *cancelled) => result.is_ok(),
+    };
+    cancelled || *cancel_rx.borrow()
+}
+
+async fn write_checkpoint(path: &Path, jobs: &[BackfillJob]) -> io::Result<()> {
+    let bytes = serde_json::to_vec(jobs).map_err(io::Error::other)?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).await?;
+    }
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, bytes).await?;
+    fs::rename(&tmp_path, path).await
+}
+
+async fn load_checkpoint(path: &Path) -> io::Result<Vec<BackfillJob>> {
+    match fs::read(path).await {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -1,4 +1,4 @@
-use crate::state::AppState;
+use crate::{service::slot_poller::LevelStatus, state::AppState};
 
 /// Retrieves all slots from the primary cache for recent slots
 pub async fn get_all_latest_slots(app_state: &AppState) -> Vec<u64> {
@@ -9,3 +9,8 @@ pub async fn get_all_latest_slots(app_state: &AppState) -> Vec<u64> {
 pub async fn get_all_lru_slots(app_state: &AppState) -> Vec<u64> {
     app_state.lru_cache.get_all_slots().await
 }
+
+/// Reports the poller cursor and catch-up progress of every tracked commitment level
+pub async fn get_poller_status(app_state: &AppState) -> Vec<LevelStatus> {
+    app_state.poller.status()
+}
@@ -0,0 +1,550 @@
+use crate::{config::Config, state::AppState};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, SystemTime},
+};
+use subtle::ConstantTimeEq;
+use tokio::{
+    sync::broadcast,
+    task::JoinHandle,
+    time::{Instant, MissedTickBehavior, interval},
+};
+use tracing::{info, warn};
+
+tokio::task_local! {
+    static CURRENT_CLIENT: RequestClient;
+}
+
+/// Limits a client key is held to, 0 disables a limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub struct ClientLimits {
+    /// Sustained requests per second, bursts of up to one second's worth go through
+    pub rate_limit_per_sec: u32,
+    /// Requests per quota window
+    pub request_quota: u64,
+    /// Slots resolved over RPC per quota window, cache hits do not count
+    pub rpc_fallback_quota: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientKey {
+    pub name: String,
+    pub key: String,
+    pub limits: ClientLimits,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientSettings {
+    /// `(name, key)` pairs given in the configuration, held to the default limits
+    pub keys: Vec<(String, String)>,
+    /// TOML file with `[[clients]]` entries, reloaded when it changes
+    pub keys_file: Option<PathBuf>,
+    pub reload_interval: Duration,
+    pub default_limits: ClientLimits,
+    pub quota_window: Duration,
+}
+
+impl ClientSettings {
+    /// Returns `None` when no client key is configured, which leaves the API open
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.client_api_keys.is_empty() && config.client_api_keys_file.is_none() {
+            return None;
+        }
+        Some(Self {
+            keys: config.client_api_keys.clone(),
+            keys_file: config.client_api_keys_file.clone(),
+            reload_interval: config.client_keys_reload_interval,
+            default_limits: ClientLimits {
+                rate_limit_per_sec: config.client_rate_limit_per_sec,
+                request_quota: config.client_request_quota,
+                rpc_fallback_quota: config.client_rpc_fallback_quota,
+            },
+            quota_window: config.client_quota_window,
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFile {
+    #[serde(default)]
+    clients: Vec<KeysFileEntry>,
+}
+
+// Limits left out fall back to the configured defaults
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct KeysFileEntry {
+    name: String,
+    key: String,
+    rate_limit_per_sec: Option<u32>,
+    request_quota: Option<u64>,
+    rpc_fallback_quota: Option<u64>,
+}
+
+/// Usage of a client key, `requests` and `rpc_fallbacks` are counted in the current window
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ClientUsage {
+    pub name: String,
+    pub limits: ClientLimits,
+    pub requests: u64,
+    pub rpc_fallbacks: u64,
+    /// Seconds until the quota window resets
+    pub window_resets_in_secs: u64,
+    pub total_requests: u64,
+    pub total_rpc_fallbacks: u64,
+    /// Requests rejected by the rate limit since startup
+    pub rate_limited: u64,
+    /// Requests rejected because a quota was used up since startup
+    pub quota_exceeded: u64,
+}
+
+#[derive(Debug)]
+struct Usage {
+    window_start: Instant,
+    requests: u64,
+    rpc_fallbacks: u64,
+    total_requests: u64,
+    total_rpc_fallbacks: u64,
+    rate_limited: u64,
+    quota_exceeded: u64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl Usage {
+    fn new(now: Instant, limits: &ClientLimits) -> Self {
+        Self {
+            window_start: now,
+            requests: 0,
+            rpc_fallbacks: 0,
+            total_requests: 0,
+            total_rpc_fallbacks: 0,
+            rate_limited: 0,
+            quota_exceeded: 0,
+            tokens: f64::from(limits.rate_limit_per_sec),
+            refilled_at: now,
+        }
+    }
+
+    // A window starts with the first request after the previous one ended
+    fn roll_window(&mut self, now: Instant, window: Duration) {
+        if now.duration_since(self.window_start) >= window {
+            self.window_start = now;
+            self.requests = 0;
+            self.rpc_fallbacks = 0;
+        }
+    }
+
+    fn window_remaining(&self, now: Instant, window: Duration) -> Duration {
+        window.saturating_sub(now.duration_since(self.window_start))
+    }
+
+    // Takes a token from the bucket, or returns how long until the next one is available
+    fn take_token(&mut self, now: Instant, rate: u32) -> Result<(), Duration> {
+        if rate == 0 {
+            return Ok(());
+        }
+        let rate = f64::from(rate);
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(rate);
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+
+    fn report(&self, client: &ClientKey, now: Instant, window: Duration) -> ClientUsage {
+        ClientUsage {
+            name: client.name.clone(),
+            limits: client.limits,
+            requests: self.requests,
+            rpc_fallbacks: self.rpc_fallbacks,
+            window_resets_in_secs: self.window_remaining(now, window).as_secs(),
+            total_requests: self.total_requests,
+            total_rpc_fallbacks: self.total_rpc_fallbacks,
+            rate_limited: self.rate_limited,
+            quota_exceeded: self.quota_exceeded,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Admission {
+    Admitted(RequestClient),
+    /// The key is missing or matches no client
+    Unknown,
+    /// Retry after the given delay
+    RateLimited(Duration),
+    QuotaExceeded(Duration),
+}
+
+/// Client keys with their limits and usage. Usage is kept per client name, so it survives
+/// a key rotation that keeps the name.
+#[derive(Debug)]
+pub struct ClientRegistry {
+    settings: RwLock<ClientSettings>,
+    clients: RwLock<Vec<ClientKey>>,
+    usage: Mutex<HashMap<String, Usage>>,
+    file_modified: Mutex<Option<SystemTime>>,
+}
+
+impl ClientRegistry {
+    pub fn load(settings: ClientSettings) -> io::Result<Self> {
+        let file_modified = file_modified(&settings)?;
+        let clients = read_clients(&settings)?;
+        Ok(Self {
+            settings: RwLock::new(settings),
+            clients: RwLock::new(clients),
+            usage: Mutex::new(HashMap::new()),
+            file_modified: Mutex::new(file_modified),
+        })
+    }
+
+    pub fn settings(&self) -> ClientSettings {
+        self.settings.read().unwrap().clone()
+    }
+
+    /// Names of the current clients, sorted
+    pub fn client_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .clients
+            .read()
+            .unwrap()
+            .iter()
+            .map(|client| client.name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    // Compares against every key so the time taken does not depend on which one matched
+    fn find(&self, presented: &str) -> Option<ClientKey> {
+        self.clients
+            .read()
+            .unwrap()
+            .iter()
+            .fold(None, |found, client| {
+                let matches = bool::from(client.key.as_bytes().ct_eq(presented.as_bytes()));
+                if matches { Some(client.clone()) } else { found }
+            })
+    }
+
+    /// Checks the key, its rate limit and request quota, and counts the request if admitted
+    pub fn admit(self: &Arc<Self>, presented: Option<&str>) -> Admission {
+        let Some(client) = presented.and_then(|key| self.find(key)) else {
+            return Admission::Unknown;
+        };
+        let window = self.settings.read().unwrap().quota_window;
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage
+            .entry(client.name.clone())
+            .or_insert_with(|| Usage::new(now, &client.limits));
+        usage.roll_window(now, window);
+
+        let quota = client.limits.request_quota;
+        if quota > 0 && usage.requests >= quota {
+            usage.quota_exceeded += 1;
+            return Admission::QuotaExceeded(usage.window_remaining(now, window));
+        }
+        if let Err(retry_after) = usage.take_token(now, client.limits.rate_limit_per_sec) {
+            usage.rate_limited += 1;
+            return Admission::RateLimited(retry_after);
+        }
+        usage.requests += 1;
+        usage.total_requests += 1;
+        Admission::Admitted(RequestClient {
+            name: Arc::from(client.name.as_str()),
+            registry: self.clone(),
+            rpc_denied: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Charges the client for slots about to be resolved over RPC, or returns how long
+    /// until its RPC fallback quota resets if they do not fit in what is left of it
+    pub fn charge_rpc_fallbacks(&self, name: &str, slots: u64) -> Result<(), Duration> {
+        let Some(client) = self
+            .clients
+            .read()
+            .unwrap()
+            .iter()
+            .find(|client| client.name == name)
+            .cloned()
+        else {
+            // Removed by a reload while the request was running
+            return Ok(());
+        };
+        let window = self.settings.read().unwrap().quota_window;
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage
+            .entry(client.name)
+            .or_insert_with(|| Usage::new(now, &client.limits));
+        usage.roll_window(now, window);
+
+        let quota = client.limits.rpc_fallback_quota;
+        if quota > 0 && usage.rpc_fallbacks + slots > quota {
+            usage.quota_exceeded += 1;
+            return Err(usage.window_remaining(now, window));
+        }
+        usage.rpc_fallbacks += slots;
+        usage.total_rpc_fallbacks += slots;
+        Ok(())
+    }
+
+    /// Usage of every client, sorted by name
+    pub fn usage(&self) -> Vec<ClientUsage> {
+        self.client_names()
+            .iter()
+            .filter_map(|name| self.usage_of(name))
+            .collect()
+    }
+
+    pub fn usage_of(&self, name: &str) -> Option<ClientUsage> {
+        let client = self
+            .clients
+            .read()
+            .unwrap()
+            .iter()
+            .find(|client| client.name == name)
+            .cloned()?;
+        let window = self.settings.read().unwrap().quota_window;
+        let now = Instant::now();
+        let mut usage = self.usage.lock().unwrap();
+        let usage = usage
+            .entry(client.name.clone())
+            .or_insert_with(|| Usage::new(now, &client.limits));
+        usage.roll_window(now, window);
+        Some(usage.report(&client, now, window))
+    }
+
+    /// Starts a new quota window for the client, totals are kept
+    pub fn reset_usage(&self, name: &str) -> Option<ClientUsage> {
+        if let Some(usage) = self.usage.lock().unwrap().get_mut(name) {
+            usage.window_start = Instant::now();
+            usage.requests = 0;
+            usage.rpc_fallbacks = 0;
+        }
+        self.usage_of(name)
+    }
+
+    /// Re-reads the keys file if it changed since the last load.
+    /// Returns whether new keys are in use, the current ones stay on error.
+    pub fn reload_if_changed(&self) -> io::Result<bool> {
+        let settings = self.settings.read().unwrap().clone();
+        let modified = file_modified(&settings)?;
+        if modified == *self.file_modified.lock().unwrap() {
+            return Ok(false);
+        }
+        self.apply(settings, modified)?;
+        Ok(true)
+    }
+
+    /// Switches to new settings, e.g. after a configuration reload
+    pub fn reconfigure(&self, settings: ClientSettings) -> io::Result<()> {
+        let modified = file_modified(&settings)?;
+        self.apply(settings, modified)
+    }
+
+    fn apply(&self, settings: ClientSettings, modified: Option<SystemTime>) -> io::Result<()> {
+        let clients = read_clients(&settings)?;
+        let names: HashSet<&str> = clients.iter().map(|client| client.name.as_str()).collect();
+        self.usage
+            .lock()
+            .unwrap()
+            .retain(|name, _| names.contains(name.as_str()));
+        *self.clients.write().unwrap() = clients;
+        *self.settings.write().unwrap() = settings;
+        *self.file_modified.lock().unwrap() = modified;
+        Ok(())
+    }
+}
+
+fn file_modified(settings: &ClientSettings) -> io::Result<Option<SystemTime>> {
+    settings
+        .keys_file
+        .as_deref()
+        .map(|path| fs::metadata(path)?.modified())
+        .transpose()
+}
+
+// Keys from the configuration followed by those of the file, names and keys must be unique
+fn read_clients(settings: &ClientSettings) -> io::Result<Vec<ClientKey>> {
+    let mut clients: Vec<ClientKey> = settings
+        .keys
+        .iter()
+        .map(|(name, key)| ClientKey {
+            name: name.clone(),
+            key: key.clone(),
+            limits: settings.default_limits,
+        })
+        .collect();
+    if let Some(path) = &settings.keys_file {
+        clients.extend(read_keys_file(path, &settings.default_limits)?);
+    }
+
+    let invalid = |message: String| io::Error::new(io::ErrorKind::InvalidData, message);
+    let mut names = HashSet::new();
+    let mut keys = HashSet::new();
+    for client in &clients {
+        if client.name.is_empty() || client.key.is_empty() {
+            return Err(invalid(
+                "client names and keys must not be empty".to_string(),
+            ));
+        }
+        if !names.insert(client.name.as_str()) {
+            return Err(invalid(format!("duplicate client name '{}'", client.name)));
+        }
+        if !keys.insert(client.key.as_str()) {
+            return Err(invalid(format!(
+                "client '{}' reuses the key of another client",
+                client.name
+            )));
+        }
+    }
+    Ok(clients)
+}
+
+fn read_keys_file(path: &Path, defaults: &ClientLimits) -> io::Result<Vec<ClientKey>> {
+    let content = fs::read_to_string(path)?;
+    let file: KeysFile = toml::from_str(&content).map_err(|e| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), e.message()),
+        )
+    })?;
+    Ok(file
+        .clients
+        .into_iter()
+        .map(|entry| ClientKey {
+            name: entry.name,
+            key: entry.key,
+            limits: ClientLimits {
+                rate_limit_per_sec: entry
+                    .rate_limit_per_sec
+                    .unwrap_or(defaults.rate_limit_per_sec),
+                request_quota: entry.request_quota.unwrap_or(defaults.request_quota),
+                rpc_fallback_quota: entry
+                    .rpc_fallback_quota
+                    .unwrap_or(defaults.rpc_fallback_quota),
+            },
+        })
+        .collect())
+}
+
+/// Checks the keys file every `reload_interval` until shutdown
+pub fn watch_client_keys(
+    registry: Arc<ClientRegistry>,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    let reload_interval = registry.settings.read().unwrap().reload_interval;
+    tokio::spawn(async move {
+        let mut ticker = interval(reload_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.recv() => break,
+                _ = ticker.tick() => match registry.reload_if_changed() {
+                    Ok(true) => info!("Reloaded client keys, {} clients", registry.client_names().len()),
+                    Ok(false) => {}
+                    Err(e) => warn!("Failed to reload client keys, keeping the current ones: {}", e),
+                },
+            }
+        }
+    })
+}
+
+/// The client a request is served for, set for the duration of the request
+#[derive(Debug, Clone)]
+pub struct RequestClient {
+    pub name: Arc<str>,
+    registry: Arc<ClientRegistry>,
+    rpc_denied: Arc<Mutex<Option<Duration>>>,
+}
+
+impl PartialEq for RequestClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl RequestClient {
+    /// Runs the future with this client as the one RPC fallbacks are charged to
+    pub async fn run<F: Future>(self, future: F) -> F::Output {
+        CURRENT_CLIENT.scope(self, future).await
+    }
+
+    /// Delay until the RPC fallback quota resets, if a fallback was refused for it
+    pub fn rpc_denied(&self) -> Option<Duration> {
+        *self.rpc_denied.lock().unwrap()
+    }
+
+    /// Charges this client for slots about to be resolved over RPC, returns `false` if
+    /// they exceed its RPC fallback quota
+    pub fn charge_rpc_fallbacks(&self, slots: usize) -> bool {
+        match self.registry.charge_rpc_fallbacks(&self.name, slots as u64) {
+            Ok(()) => true,
+            Err(retry_after) => {
+                *self.rpc_denied.lock().unwrap() = Some(retry_after);
+                false
+            }
+        }
+    }
+}
+
+/// The client of the current request, `None` outside a client request
+pub fn current_client() -> Option<RequestClient> {
+    CURRENT_CLIENT.try_with(Clone::clone).ok()
+}
+
+/// Charges the client of the current request for slots about to be resolved over RPC.
+/// Returns `false` if they exceed its RPC fallback quota, in which case they must not be
+/// resolved. Always `true` outside a client request, e.g. for the poller.
+pub fn acquire_rpc_fallbacks(slots: usize) -> bool {
+    if slots == 0 {
+        return true;
+    }
+    current_client().is_none_or(|client| client.charge_rpc_fallbacks(slots))
+}
+
+/// Keeps the client of the current request for a future spawned on its behalf
+pub fn with_current_client<F>(future: F) -> impl Future<Output = F::Output>
+where
+    F: Future,
+{
+    let client = current_client();
+    async move {
+        match client {
+            Some(client) => client.run(future).await,
+            None => future.await,
+        }
+    }
+}
+
+/// Usage of every client, `None` when client keys are not enabled
+pub fn client_usage(app_state: &AppState) -> Option<Vec<ClientUsage>> {
+    Some(app_state.client_registry.as_ref()?.usage())
+}
+
+pub fn get_client_usage(app_state: &AppState, name: &str) -> Option<ClientUsage> {
+    app_state.client_registry.as_ref()?.usage_of(name)
+}
+
+/// Starts a new quota window for the client
+pub fn reset_client_usage(app_state: &AppState, name: &str) -> Option<ClientUsage> {
+    let usage = app_state.client_registry.as_ref()?.reset_usage(name)?;
+    info!("Reset the quota window of client {}", name);
+    Some(usage)
+}
@@ -1,15 +1,33 @@
+use crate::cache::{DiskStore, LruCache, StoredSlot};
 use crate::circuit_breaker::CircuitBreakerError;
+use crate::commitment::Commitment;
+use crate::metrics::CacheTier;
+use crate::service::client_service::acquire_rpc_fallbacks;
+use crate::service::fallback_batcher::plan_ranges;
 use crate::state::AppState;
+use crate::utils::single_flight::SingleFlight;
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::time::Instant;
-use tracing::{error, warn};
+use tracing::{Span, debug, error, field, instrument, warn};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ConfirmationStatus {
     Confirmed,
     NotConfirmed,
     Error,
+    /// Resolving the slot needed an RPC call the client's RPC fallback quota did not cover
+    QuotaExceeded,
 }
 
+/// Largest range covered by a single getBlocks call when checking many slots at once
+const RANGE_CALL_MAX_SLOTS: u64 = 5000;
+
+/// RPC fallbacks currently in flight, keyed by slot and commitment
+pub type InFlightConfirmations = SingleFlight<(u64, Commitment), ConfirmationStatus>;
+
 pub async fn confirm(app_state: &AppState, slot: u64) -> ConfirmationStatus {
     let now = Instant::now();
 
@@ -69,35 +87,38 @@ pub async fn confirm_with_lru(app_state: &AppState, slot: u64) -> ConfirmationSt
 }
 
 pub async fn confirm_with_lru_and_breaker(app_state: &AppState, slot: u64) -> ConfirmationStatus {
+    confirm_with_commitment(app_state, slot, Commitment::Confirmed).await
+}
+
+/// Checks whether the slot reached the requested commitment, consulting both cache tiers
+/// before falling back to RPC behind the circuit breaker
+#[instrument(skip(app_state))]
+pub async fn confirm_with_commitment(
+    app_state: &AppState,
+    slot: u64,
+    commitment: Commitment,
+) -> ConfirmationStatus {
     let now = Instant::now();
 
-    let status = {
-        if app_state.cache.contains(&slot).await || app_state.lru_cache.get(&slot).await {
-            ConfirmationStatus::Confirmed
-        } else {
-            let rpc_call = || app_state.rpc_client.get_blocks(slot, Some(slot));
-            match app_state.circuit_breaker.execute(rpc_call).await {
-                Ok(blocks) => {
-                    if blocks.contains(&slot) {
-                        app_state.lru_cache.put(slot).await;
-                        ConfirmationStatus::Confirmed
-                    } else {
-                        ConfirmationStatus::NotConfirmed
-                    }
+    let status = match cached_status(app_state, slot, commitment).await {
+        Some(status) => status,
+        None => {
+            // getBlocks has no processed view, a confirmed block satisfies a processed query
+            let rpc_commitment = commitment.max(Commitment::Confirmed);
+            loop {
+                let mut led = false;
+                let status = app_state
+                    .in_flight
+                    .run((slot, rpc_commitment), || {
+                        led = true;
+                        confirm_via_rpc(app_state, slot, rpc_commitment)
+                    })
+                    .await;
+                // Only the request making the call is charged for it. A follower of one its
+                // quota refused tries again on its own account.
+                if led || status != ConfirmationStatus::QuotaExceeded {
+                    break status;
                 }
-                Err(e) => match e {
-                    CircuitBreakerError::Inner(rpc_err) => {
-                        error!(
-                            "RPC error during fallback check for slot {}: {}",
-                            slot, rpc_err
-                        );
-                        ConfirmationStatus::Error
-                    }
-                    CircuitBreakerError::CircuitOpen => {
-                        warn!("Circuit is open. Rejecting request for slot {}.", slot);
-                        ConfirmationStatus::Error
-                    }
-                },
             }
         }
     };
@@ -107,3 +128,385 @@ pub async fn confirm_with_lru_and_breaker(app_state: &AppState, slot: u64) -> Co
         .record_is_slot_confirmed_elapsed(now.elapsed());
     status
 }
+
+/// Checks many slots at the requested commitment, answering in input order.
+/// Cache hits are answered directly, misses are grouped into as few range calls as possible.
+#[instrument(skip_all, fields(slots = slots.len(), ?commitment))]
+pub async fn confirm_many(
+    app_state: &AppState,
+    slots: &[u64],
+    commitment: Commitment,
+) -> Vec<ConfirmationStatus> {
+    let mut cached = Vec::with_capacity(slots.len());
+    let mut misses = Vec::new();
+    for &slot in slots {
+        let status = cached_status(app_state, slot, commitment).await;
+        if status.is_none() {
+            misses.push(slot);
+        }
+        cached.push(status);
+    }
+    misses.sort_unstable();
+    misses.dedup();
+
+    let rpc_commitment = commitment.max(Commitment::Confirmed);
+    let shared = shared_statuses(app_state, &misses, rpc_commitment).await;
+    let mut resolved: HashMap<u64, ConfirmationStatus> = misses
+        .iter()
+        .zip(shared)
+        .filter_map(|(&slot, status)| Some((slot, status?)))
+        .collect();
+    misses.retain(|slot| !resolved.contains_key(slot));
+
+    // Only the slots left for RPC are charged, each once
+    if acquire_rpc_fallbacks(misses.len()) {
+        resolved.extend(
+            resolve_in_ranges(
+                app_state,
+                &misses,
+                rpc_commitment,
+                RANGE_CALL_MAX_SLOTS,
+                RANGE_CALL_MAX_SLOTS,
+            )
+            .await,
+        );
+    } else {
+        resolved.extend(
+            misses
+                .into_iter()
+                .map(|slot| (slot, ConfirmationStatus::QuotaExceeded)),
+        );
+    }
+
+    slots
+        .iter()
+        .zip(cached)
+        .map(|(slot, status)| {
+            status
+                .or_else(|| resolved.get(slot).copied())
+                .unwrap_or(ConfirmationStatus::Error)
+        })
+        .collect()
+}
+
+// Answers from the cache tiers, `None` means the slot has to be resolved through RPC.
+// The span records the tier that answered, `miss` when none did.
+#[instrument(skip(app_state), fields(cache.tier = field::Empty))]
+async fn cached_status(
+    app_state: &AppState,
+    slot: u64,
+    commitment: Commitment,
+) -> Option<ConfirmationStatus> {
+    let metrics = &app_state.metrics;
+
+    let primary = if app_state.cache.contains_at(&slot, commitment).await {
+        Some(ConfirmationStatus::Confirmed)
+    } else if app_state.cache.is_skipped_at(&slot, commitment).await {
+        Some(ConfirmationStatus::NotConfirmed)
+    } else {
+        None
+    };
+    metrics.record_cache_lookup(CacheTier::Primary, primary.is_some());
+    if primary.is_some() {
+        record_tier_hit(Some(CacheTier::Primary));
+        return primary;
+    }
+
+    let lru = if app_state
+        .lru_cache
+        .get_commitment(&slot)
+        .await
+        .is_some_and(|cached| cached >= commitment)
+    {
+        Some(ConfirmationStatus::Confirmed)
+    } else if app_state.lru_cache.is_skipped_at(&slot, commitment).await {
+        Some(ConfirmationStatus::NotConfirmed)
+    } else {
+        None
+    };
+    metrics.record_cache_lookup(CacheTier::Lru, lru.is_some());
+    if lru.is_some() {
+        record_tier_hit(Some(CacheTier::Lru));
+        return lru;
+    }
+
+    let Some(disk_store) = &app_state.disk_store else {
+        record_tier_hit(None);
+        return None;
+    };
+    let stored = stored_status(disk_store, &app_state.lru_cache, slot, commitment).await;
+    metrics.record_cache_lookup(CacheTier::Disk, stored.is_some());
+    record_tier_hit(stored.is_some().then_some(CacheTier::Disk));
+    stored
+}
+
+fn record_tier_hit(tier: Option<CacheTier>) {
+    Span::current().record("cache.tier", tier.map_or("miss", |tier| tier.as_str()));
+}
+
+// Answers from the historical tier and brings the hit back into the LRU cache
+async fn stored_status(
+    disk_store: &DiskStore,
+    lru_cache: &LruCache,
+    slot: u64,
+    commitment: Commitment,
+) -> Option<ConfirmationStatus> {
+    match disk_store.get(&slot).await? {
+        StoredSlot::Confirmed(stored) if stored >= commitment => {
+            lru_cache.put_with_commitment(slot, stored).await;
+            Some(ConfirmationStatus::Confirmed)
+        }
+        StoredSlot::Skipped(stored) if stored >= commitment => {
+            lru_cache.put_skipped(slot, stored).await;
+            Some(ConfirmationStatus::NotConfirmed)
+        }
+        _ => None,
+    }
+}
+
+// Resolves a cache miss through the circuit breaker and records confirmed slots.
+// Concurrent misses for the same slot share a single call via `AppState::in_flight`,
+// misses for nearby slots share a range call when the batcher is enabled.
+#[instrument(skip(app_state))]
+async fn confirm_via_rpc(
+    app_state: &AppState,
+    slot: u64,
+    commitment: Commitment,
+) -> ConfirmationStatus {
+    // The batcher consults the shared cache and charges the client itself
+    if let Some(batcher) = &app_state.batcher {
+        return batcher.confirm(slot, commitment).await;
+    }
+    if let Some(status) = shared_statuses(app_state, &[slot], commitment)
+        .await
+        .pop()
+        .flatten()
+    {
+        return status;
+    }
+    if !acquire_rpc_fallbacks(1) {
+        return ConfirmationStatus::QuotaExceeded;
+    }
+
+    let rpc_call = || {
+        app_state
+            .rpc_client
+            .get_blocks_with_commitment(slot, Some(slot), commitment)
+    };
+    match app_state.circuit_breaker.execute(rpc_call).await {
+        Ok(blocks) => {
+            let (status, learned) = record_rpc_answer(
+                app_state,
+                slot,
+                commitment,
+                blocks.contains(&slot),
+                blocks.iter().max().copied(),
+            )
+            .await;
+            publish_to_shared_cache(app_state, learned.into_iter().collect());
+            status
+        }
+        Err(e) => match e {
+            CircuitBreakerError::Inner(rpc_err) => {
+                error!(
+                    "RPC error during fallback check for slot {} at {} commitment: {}",
+                    slot, commitment, rpc_err
+                );
+                ConfirmationStatus::Error
+            }
+            CircuitBreakerError::CircuitOpen => {
+                warn!("Circuit is open. Rejecting request for slot {}.", slot);
+                ConfirmationStatus::Error
+            }
+        },
+    }
+}
+
+/// Resolves the slots with `get_blocks` range calls, merging slots at most `max_slot_gap`
+/// apart into ranges of at most `max_range` slots. Ranges are fetched concurrently.
+/// Callers consult the shared cache and charge the clients beforehand.
+pub(crate) async fn resolve_in_ranges(
+    app_state: &AppState,
+    slots: &[u64],
+    commitment: Commitment,
+    max_slot_gap: u64,
+    max_range: u64,
+) -> HashMap<u64, ConfirmationStatus> {
+    let mut statuses = HashMap::with_capacity(slots.len());
+    let ranges = plan_ranges(slots, max_slot_gap, max_range);
+    debug!(
+        "Resolving {} slots at {} commitment with {} range calls",
+        slots.len(),
+        commitment,
+        ranges.len()
+    );
+
+    let results = join_all(
+        ranges
+            .iter()
+            .map(|&(start, end)| fetch_range(app_state, start, end, commitment)),
+    )
+    .await;
+
+    let mut learned = Vec::new();
+    for &slot in slots {
+        // Ranges are sorted and every slot belongs to exactly one of them
+        let index = ranges.partition_point(|&(start, _)| start <= slot) - 1;
+        let status = match &results[index] {
+            Some(blocks) => {
+                let (status, observation) = record_rpc_answer(
+                    app_state,
+                    slot,
+                    commitment,
+                    blocks.contains(&slot),
+                    blocks.iter().max().copied(),
+                )
+                .await;
+                learned.extend(observation);
+                status
+            }
+            None => ConfirmationStatus::Error,
+        };
+        statuses.insert(slot, status);
+    }
+    publish_to_shared_cache(app_state, learned);
+    statuses
+}
+
+// Answers from the tier shared with other replicas in one round trip, in input order.
+// Hits are brought into the local tiers.
+#[instrument(skip_all, fields(slots = slots.len(), cache.hits = field::Empty))]
+pub(crate) async fn shared_statuses(
+    app_state: &AppState,
+    slots: &[u64],
+    commitment: Commitment,
+) -> Vec<Option<ConfirmationStatus>> {
+    let Some(shared_cache) = &app_state.shared_cache else {
+        return vec![None; slots.len()];
+    };
+
+    let mut hits = Vec::new();
+    let statuses = shared_cache
+        .get_many(slots)
+        .await
+        .into_iter()
+        .zip(slots)
+        .map(|(stored, &slot)| {
+            let stored = stored.filter(|stored| stored.commitment() >= commitment);
+            app_state
+                .metrics
+                .record_cache_lookup(CacheTier::Shared, stored.is_some());
+            let stored = stored?;
+            hits.push((slot, stored));
+            Some(match stored {
+                StoredSlot::Confirmed(_) => ConfirmationStatus::Confirmed,
+                StoredSlot::Skipped(_) => ConfirmationStatus::NotConfirmed,
+            })
+        })
+        .collect();
+
+    Span::current().record("cache.hits", hits.len());
+    for &(slot, stored) in &hits {
+        match stored {
+            StoredSlot::Confirmed(stored) => {
+                if !app_state.cache.upgrade(slot, stored).await {
+                    app_state.lru_cache.put_with_commitment(slot, stored).await;
+                }
+            }
+            StoredSlot::Skipped(stored) => app_state.lru_cache.put_skipped(slot, stored).await,
+        }
+    }
+    if let Some(disk_store) = &app_state.disk_store {
+        disk_store.record_all(hits).await;
+    }
+    statuses
+}
+
+// Shares what was learned over RPC with the other replicas, without delaying the answer
+fn publish_to_shared_cache(app_state: &AppState, learned: Vec<(u64, StoredSlot)>) {
+    if learned.is_empty() {
+        return;
+    }
+    if let Some(shared_cache) = app_state.shared_cache.clone() {
+        tokio::spawn(async move {
+            shared_cache.put_many(&learned).await;
+        });
+    }
+}
+
+#[instrument(skip(app_state))]
+async fn fetch_range(
+    app_state: &AppState,
+    start: u64,
+    end: u64,
+    commitment: Commitment,
+) -> Option<HashSet<u64>> {
+    let now = Instant::now();
+    let rpc_call = || {
+        app_state
+            .rpc_client
+            .get_blocks_with_commitment(start, Some(end), commitment)
+    };
+    let result = app_state.circuit_breaker.execute(rpc_call).await;
+    app_state.metrics.record_get_blocks_elapsed(now.elapsed());
+
+    match result {
+        Ok(blocks) => Some(blocks.into_iter().collect()),
+        Err(CircuitBreakerError::Inner(rpc_err)) => {
+            error!(
+                "RPC error during range check for slots {}..={} at {} commitment: {}",
+                start, end, commitment, rpc_err
+            );
+            None
+        }
+        Err(CircuitBreakerError::CircuitOpen) => {
+            warn!(
+                "Circuit is open. Rejecting range check for slots {}..={}.",
+                start, end
+            );
+            None
+        }
+    }
+}
+
+/// Records an RPC answer for the slot in the caches and converts it into a status,
+/// together with what was learned about the slot, if anything.
+/// A missing slot is only remembered as skipped once the chain is known to have moved
+/// past it at that commitment, either from the poller or from a later block in the answer.
+async fn record_rpc_answer(
+    app_state: &AppState,
+    slot: u64,
+    commitment: Commitment,
+    confirmed: bool,
+    highest_block: Option<u64>,
+) -> (ConfirmationStatus, Option<(u64, StoredSlot)>) {
+    let cache = &app_state.cache;
+    if confirmed {
+        if !cache.upgrade(slot, commitment).await {
+            app_state
+                .lru_cache
+                .put_with_commitment(slot, commitment)
+                .await;
+        }
+        if let Some(disk_store) = &app_state.disk_store {
+            disk_store.record_confirmed(slot, commitment).await;
+        }
+        return (
+            ConfirmationStatus::Confirmed,
+            Some((slot, StoredSlot::Confirmed(commitment))),
+        );
+    }
+
+    let known_tip = highest_block.max(cache.get_latest_slot_at(commitment).await);
+    if known_tip.is_some_and(|tip| tip > slot) {
+        app_state.lru_cache.put_skipped(slot, commitment).await;
+        if let Some(disk_store) = &app_state.disk_store {
+            disk_store.record_skipped(slot, commitment).await;
+        }
+        return (
+            ConfirmationStatus::NotConfirmed,
+            Some((slot, StoredSlot::Skipped(commitment))),
+        );
+    }
+    (ConfirmationStatus::NotConfirmed, None)
+}
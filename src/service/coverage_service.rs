@@ -0,0 +1,138 @@
+use crate::circuit_breaker::CircuitBreakerError;
+use crate::commitment::Commitment;
+use crate::service::client_service::acquire_rpc_fallbacks;
+use crate::state::AppState;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Instant;
+use tracing::{error, warn};
+
+/// Largest range answered by a single coverage query
+pub const MAX_COVERAGE_RANGE: u64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CoverageSource {
+    Cache,
+    Rpc,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CoverageCounts {
+    pub confirmed: usize,
+    pub skipped: usize,
+    pub unknown: usize,
+}
+
+/// What is known about every slot of an inclusive range at a given commitment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SlotCoverage {
+    pub start: u64,
+    pub end: u64,
+    pub commitment: Commitment,
+    pub source: CoverageSource,
+    pub confirmed: Vec<u64>,
+    pub skipped: Vec<u64>,
+    pub unknown: Vec<u64>,
+    pub counts: CoverageCounts,
+}
+
+impl SlotCoverage {
+    fn new(start: u64, end: u64, commitment: Commitment, source: CoverageSource) -> Self {
+        Self {
+            start,
+            end,
+            commitment,
+            source,
+            confirmed: Vec::new(),
+            skipped: Vec::new(),
+            unknown: Vec::new(),
+            counts: CoverageCounts::default(),
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        self.counts = CoverageCounts {
+            confirmed: self.confirmed.len(),
+            skipped: self.skipped.len(),
+            unknown: self.unknown.len(),
+        };
+        self
+    }
+}
+
+/// Classifies every slot of `start..=end` as confirmed, skipped or unknown.
+///
+/// The answer comes from `SlotCache` when it knows every slot of the range, otherwise
+/// from a single `get_blocks(start, end)` call. Returns `None` if that call fails.
+pub async fn get_range_coverage(
+    app_state: &AppState,
+    start: u64,
+    end: u64,
+    commitment: Commitment,
+) -> Option<SlotCoverage> {
+    let mut coverage = SlotCoverage::new(start, end, commitment, CoverageSource::Cache);
+    for slot in start..=end {
+        if app_state.cache.contains_at(&slot, commitment).await {
+            coverage.confirmed.push(slot);
+        } else if app_state.cache.is_skipped_at(&slot, commitment).await {
+            coverage.skipped.push(slot);
+        } else {
+            coverage.unknown.push(slot);
+        }
+    }
+    if coverage.unknown.is_empty() {
+        return Some(coverage.finish());
+    }
+    if !acquire_rpc_fallbacks(coverage.unknown.len()) {
+        return None;
+    }
+
+    // getBlocks has no processed view, a confirmed block satisfies a processed query
+    let rpc_commitment = commitment.max(Commitment::Confirmed);
+    let now = Instant::now();
+    let rpc_call = || {
+        app_state
+            .rpc_client
+            .get_blocks_with_commitment(start, Some(end), rpc_commitment)
+    };
+    let result = app_state.circuit_breaker.execute(rpc_call).await;
+    app_state.metrics.record_get_blocks_elapsed(now.elapsed());
+
+    let blocks: HashSet<u64> = match result {
+        Ok(blocks) => blocks.into_iter().collect(),
+        Err(CircuitBreakerError::Inner(rpc_err)) => {
+            error!(
+                "RPC error during coverage query for slots {}..={} at {} commitment: {}",
+                start, end, commitment, rpc_err
+            );
+            return None;
+        }
+        Err(CircuitBreakerError::CircuitOpen) => {
+            warn!(
+                "Circuit is open. Rejecting coverage query for slots {}..={}.",
+                start, end
+            );
+            return None;
+        }
+    };
+
+    // Slots past the chain tip may simply not be produced yet
+    let known_tip = blocks
+        .iter()
+        .max()
+        .copied()
+        .max(app_state.cache.get_latest_slot_at(rpc_commitment).await);
+
+    let mut coverage = SlotCoverage::new(start, end, commitment, CoverageSource::Rpc);
+    for slot in start..=end {
+        if blocks.contains(&slot) {
+            coverage.confirmed.push(slot);
+        } else if known_tip.is_some_and(|tip| tip > slot) {
+            coverage.skipped.push(slot);
+        } else {
+            coverage.unknown.push(slot);
+        }
+    }
+    Some(coverage.finish())
+}
@@ -0,0 +1,191 @@
+use crate::{
+    commitment::Commitment,
+    config::Config,
+    service::{
+        client_service::{RequestClient, current_client},
+        confirmation_service::{ConfirmationStatus, resolve_in_ranges, shared_statuses},
+    },
+    state::AppState,
+};
+use futures_util::future::join_all;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{Instant, timeout_at};
+use tracing::error;
+
+const LOOKUP_CHANNEL_CAPACITY: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct BatcherSettings {
+    /// How long the first miss of a batch waits for others to join it
+    pub window: Duration,
+    /// Upper bound of lookups resolved together
+    pub max_batch_size: usize,
+    /// Slots closer than this are merged into the same range call
+    pub max_slot_gap: u64,
+    /// Upper bound of the number of slots covered by one range call
+    pub max_range: u64,
+}
+
+impl BatcherSettings {
+    /// Returns `None` when batching is disabled by a zero window
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.batch_window.is_zero() {
+            return None;
+        }
+        Some(Self {
+            window: config.batch_window,
+            max_batch_size: config.batch_max_size.max(1),
+            max_slot_gap: config.batch_max_slot_gap,
+            max_range: config.batch_max_range.max(1),
+        })
+    }
+}
+
+struct PendingLookup {
+    slot: u64,
+    commitment: Commitment,
+    // Charged for the lookup if it needs RPC, `None` outside a client request
+    client: Option<RequestClient>,
+    respond_to: oneshot::Sender<ConfirmationStatus>,
+}
+
+/// Collects RPC fallback lookups arriving within a short window and resolves them
+/// with as few `get_blocks` range calls as possible.
+pub struct FallbackBatcher {
+    lookups: mpsc::Sender<PendingLookup>,
+}
+
+impl FallbackBatcher {
+    /// Starts the batching task. Batches are resolved with the clients and caches of
+    /// `app_state`, which should not route its own fallbacks through a batcher.
+    pub fn new(app_state: AppState, settings: BatcherSettings) -> Self {
+        let (lookups, receiver) = mpsc::channel(LOOKUP_CHANNEL_CAPACITY);
+        tokio::spawn(collect_batches(receiver, Arc::new(app_state), settings));
+        Self { lookups }
+    }
+
+    /// Resolves the slot as part of the next batch. `commitment` must be
+    /// confirmed or finalized, since getBlocks has no processed view.
+    pub async fn confirm(&self, slot: u64, commitment: Commitment) -> ConfirmationStatus {
+        let (respond_to, response) = oneshot::channel();
+        let lookup = PendingLookup {
+            slot,
+            commitment,
+            client: current_client(),
+            respond_to,
+        };
+
+        if self.lookups.send(lookup).await.is_err() {
+            error!(
+                "Fallback batcher is not running, cannot resolve slot {}.",
+                slot
+            );
+            return ConfirmationStatus::Error;
+        }
+        response.await.unwrap_or(ConfirmationStatus::Error)
+    }
+}
+
+/// Splits slots into inclusive `(start, end)` ranges, merging slots at most `max_slot_gap`
+/// apart while keeping every range within `max_range` slots.
+pub fn plan_ranges(slots: &[u64], max_slot_gap: u64, max_range: u64) -> Vec<(u64, u64)> {
+    let mut sorted = slots.to_vec();
+    sorted.sort_unstable();
+    sorted.dedup();
+
+    let mut ranges: Vec<(u64, u64)> = Vec::new();
+    for slot in sorted {
+        match ranges.last_mut() {
+            Some((start, end)) if slot - *end <= max_slot_gap && slot - *start < max_range => {
+                *end = slot;
+            }
+            _ => ranges.push((slot, slot)),
+        }
+    }
+    ranges
+}
+
+async fn collect_batches(
+    mut receiver: mpsc::Receiver<PendingLookup>,
+    app_state: Arc<AppState>,
+    settings: BatcherSettings,
+) {
+    while let Some(first) = receiver.recv().await {
+        let mut batch = vec![first];
+        let deadline = Instant::now() + settings.window;
+
+        while batch.len() < settings.max_batch_size {
+            match timeout_at(deadline, receiver.recv()).await {
+                Ok(Some(lookup)) => batch.push(lookup),
+                Ok(None) | Err(_) => break,
+            }
+        }
+
+        // Resolving in the background lets the next batch start collecting right away
+        tokio::spawn(resolve_batch(app_state.clone(), batch, settings.clone()));
+    }
+}
+
+async fn resolve_batch(
+    app_state: Arc<AppState>,
+    batch: Vec<PendingLookup>,
+    settings: BatcherSettings,
+) {
+    let mut by_commitment: BTreeMap<Commitment, Vec<PendingLookup>> = BTreeMap::new();
+    for lookup in batch {
+        by_commitment
+            .entry(lookup.commitment)
+            .or_default()
+            .push(lookup);
+    }
+
+    let groups = by_commitment
+        .into_iter()
+        .map(|(commitment, lookups)| resolve_group(&app_state, commitment, lookups, &settings));
+    join_all(groups).await;
+}
+
+async fn resolve_group(
+    app_state: &AppState,
+    commitment: Commitment,
+    lookups: Vec<PendingLookup>,
+    settings: &BatcherSettings,
+) {
+    let slots: Vec<u64> = lookups.iter().map(|lookup| lookup.slot).collect();
+    let shared = shared_statuses(app_state, &slots, commitment).await;
+
+    // Only lookups left for RPC are charged, each to the client it was made for
+    let mut answered = Vec::with_capacity(lookups.len());
+    let mut misses = Vec::new();
+    for (lookup, status) in lookups.into_iter().zip(shared) {
+        let status = status.or_else(|| {
+            let admitted = lookup
+                .client
+                .as_ref()
+                .is_none_or(|client| client.charge_rpc_fallbacks(1));
+            (!admitted).then_some(ConfirmationStatus::QuotaExceeded)
+        });
+        if status.is_none() {
+            misses.push(lookup.slot);
+        }
+        answered.push((lookup, status));
+    }
+
+    let statuses = resolve_in_ranges(
+        app_state,
+        &misses,
+        commitment,
+        settings.max_slot_gap,
+        settings.max_range,
+    )
+    .await;
+
+    for (lookup, status) in answered {
+        let status = status
+            .or_else(|| statuses.get(&lookup.slot).copied())
+            .unwrap_or(ConfirmationStatus::Error);
+        // The waiter may have gone away, its answer is cached anyway
+        let _ = lookup.respond_to.send(status);
+    }
+}
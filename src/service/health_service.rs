@@ -0,0 +1,154 @@
+use crate::{
+    circuit_breaker::CircuitState, commitment::Commitment, config::Config, state::AppState,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, time::Duration};
+
+/// Thresholds beyond which the instance stops reporting ready
+#[derive(Debug, Clone)]
+pub struct HealthSettings {
+    /// Largest tolerated gap between the chain tip and the latest cached slot, per level
+    pub max_poller_lag: u64,
+    /// Longest tolerated time without a successful poll round
+    pub max_poll_age: Duration,
+    /// Whether an open circuit breaker makes the instance unready. Off by default: every
+    /// replica sharing the upstream would trip together and leave no instance in rotation.
+    pub fail_on_open_circuit: bool,
+}
+
+impl Default for HealthSettings {
+    fn default() -> Self {
+        Self {
+            max_poller_lag: 150,
+            max_poll_age: Duration::from_secs(60),
+            fail_on_open_circuit: false,
+        }
+    }
+}
+
+impl HealthSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_poller_lag: config.health_max_poller_lag,
+            max_poll_age: config.health_max_poll_age,
+            fail_on_open_circuit: config.health_fail_on_open_circuit,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Alive,
+    Ready,
+    NotReady,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheFill {
+    pub len: usize,
+    pub capacity: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CacheFills {
+    pub primary: CacheFill,
+    pub lru: CacheFill,
+}
+
+/// Signals behind the liveness and readiness probes
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    /// Why the instance is not ready, empty when it is
+    pub failures: Vec<String>,
+    pub warmed_up: bool,
+    /// Milliseconds since the last successful poll round, `None` before the first one
+    pub last_poll_age_ms: Option<u64>,
+    /// Paused through the admin API, the poll age grows until it is resumed
+    pub poller_paused: bool,
+    pub poller_lag_slots: BTreeMap<Commitment, u64>,
+    pub circuit_breaker: CircuitState,
+    pub upstream_reachable: bool,
+    pub caches: CacheFills,
+}
+
+/// Whether the instance should receive traffic, i.e. the startup warm-up has filled the
+/// primary cache and no readiness threshold is exceeded
+pub async fn is_ready(app_state: &AppState) -> bool {
+    readiness(app_state).await.failures.is_empty()
+}
+
+/// Health signals for the liveness probe, which only fails when the process does not answer
+pub async fn liveness(app_state: &AppState) -> HealthReport {
+    let mut report = collect(app_state).await;
+    report.status = HealthStatus::Alive;
+    report
+}
+
+/// Health signals checked against the configured thresholds
+pub async fn readiness(app_state: &AppState) -> HealthReport {
+    let settings = app_state.health.read().unwrap().clone();
+    let mut report = collect(app_state).await;
+
+    if !report.warmed_up {
+        report
+            .failures
+            .push("primary cache is warming up".to_string());
+    }
+    match report.last_poll_age_ms {
+        Some(age_ms) if Duration::from_millis(age_ms) > settings.max_poll_age => {
+            report.failures.push(format!(
+                "last successful poll {} ms ago exceeds {} ms",
+                age_ms,
+                settings.max_poll_age.as_millis()
+            ))
+        }
+        Some(_) => {}
+        None => report.failures.push("no successful poll yet".to_string()),
+    }
+    for (commitment, &lag) in &report.poller_lag_slots {
+        if lag > settings.max_poller_lag {
+            report.failures.push(format!(
+                "{} poller lag of {} slots exceeds {}",
+                commitment, lag, settings.max_poller_lag
+            ));
+        }
+    }
+    if settings.fail_on_open_circuit && report.circuit_breaker == CircuitState::Open {
+        report.failures.push("circuit breaker is open".to_string());
+    }
+
+    report.status = if report.failures.is_empty() {
+        HealthStatus::Ready
+    } else {
+        HealthStatus::NotReady
+    };
+    report
+}
+
+async fn collect(app_state: &AppState) -> HealthReport {
+    let poller = &app_state.poller;
+    HealthReport {
+        status: HealthStatus::Alive,
+        failures: Vec::new(),
+        warmed_up: poller.is_warmed_up(),
+        last_poll_age_ms: poller
+            .since_last_success()
+            .map(|age| age.as_millis() as u64),
+        poller_paused: poller.is_paused(),
+        poller_lag_slots: poller.lags(),
+        circuit_breaker: app_state.circuit_breaker.state().await,
+        upstream_reachable: poller.is_upstream_reachable(),
+        caches: CacheFills {
+            primary: CacheFill {
+                len: app_state.cache.len().await,
+                capacity: app_state.cache.capacity(),
+            },
+            lru: CacheFill {
+                len: app_state.lru_cache.len().await,
+                capacity: app_state.lru_cache.capacity().await,
+            },
+        },
+    }
+}
@@ -0,0 +1,43 @@
+use crate::state::AppState;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+/// Log filter directives, e.g. `info,solana_caching_service::service::slot_poller=debug`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogFilter {
+    pub filter: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogFilterUpdate {
+    Applied(LogFilter),
+    Rejected(String),
+    /// The service runs without a reloadable log filter
+    Unavailable,
+}
+
+/// Log filter currently in effect, `None` when it cannot be changed at runtime
+pub fn get_log_filter(app_state: &AppState) -> Option<LogFilter> {
+    let log_levels = app_state.log_levels.as_ref()?;
+    Some(LogFilter {
+        filter: log_levels.current(),
+    })
+}
+
+/// Replaces the log filter until the next restart
+pub fn set_log_filter(app_state: &AppState, update: &LogFilter) -> LogFilterUpdate {
+    let Some(log_levels) = &app_state.log_levels else {
+        return LogFilterUpdate::Unavailable;
+    };
+    let previous = log_levels.current();
+    match log_levels.set(&update.filter) {
+        Ok(()) => {
+            info!(
+                "Log filter changed from '{}' to '{}'",
+                previous, update.filter
+            );
+            LogFilterUpdate::Applied(update.clone())
+        }
+        Err(message) => LogFilterUpdate::Rejected(message),
+    }
+}
@@ -0,0 +1,9 @@
+use crate::state::AppState;
+
+/// Renders the recorded metrics for scraping, `None` if the metrics backend is not scraped.
+/// Gauges of state that changes without an event, like the circuit breaker, are sampled here.
+pub async fn render_metrics(app_state: &AppState) -> Option<String> {
+    let metrics = &app_state.metrics;
+    metrics.record_circuit_state(app_state.circuit_breaker.state().await);
+    metrics.render()
+}
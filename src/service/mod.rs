@@ -1,3 +1,15 @@
+pub mod admin_service;
+pub mod backfill_service;
 pub mod cache_service;
+pub mod client_service;
 pub mod confirmation_service;
+pub mod coverage_service;
+pub mod fallback_batcher;
+pub mod health_service;
+pub mod logging_service;
+pub mod metrics_service;
+pub mod reload_service;
+pub mod slot_info_service;
 pub mod slot_poller;
+pub mod slot_source;
+pub mod snapshot_service;
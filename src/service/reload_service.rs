@@ -0,0 +1,241 @@
+use crate::{
+    config::{Config, sources::ConfigSources},
+    service::client_service::ClientSettings,
+    service::health_service::HealthSettings,
+    service::slot_poller::PollerSettings,
+    state::AppState,
+};
+use serde::Serialize;
+use tokio::sync::{Mutex, broadcast};
+use tracing::{info, warn};
+
+/// Sources the configuration is re-read from, next to the configuration the service runs
+/// with. A reload only writes back the settings it applied, the others keep their startup
+/// value, so the next reload still compares against what is actually in effect.
+#[derive(Debug)]
+pub struct ConfigReloader {
+    sources: ConfigSources,
+    current: Mutex<Config>,
+}
+
+impl ConfigReloader {
+    pub fn new(sources: ConfigSources, current: Config) -> Self {
+        Self {
+            sources,
+            current: Mutex::new(current),
+        }
+    }
+
+    /// Configuration currently in effect
+    pub async fn current(&self) -> Config {
+        self.current.lock().await.clone()
+    }
+}
+
+/// Outcome of a reload that passed validation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReloadReport {
+    /// Keys whose new value was applied to the running components
+    pub applied: Vec<String>,
+    /// Whether other settings changed, which only take effect after a restart
+    pub restart_required: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigReload {
+    Applied(ReloadReport),
+    /// The new configuration does not load or validate, the running one stays in effect
+    Rejected(String),
+    /// The service runs without a configuration source to reload from
+    Unavailable,
+}
+
+/// Re-reads the configuration and applies the changed poll interval, retry policy, breaker
+/// thresholds, cache capacities, readiness thresholds and client keys without a restart.
+/// Cached slots are kept, shrinking a cache only evicts its oldest slots.
+pub async fn reload_config(app_state: &AppState) -> ConfigReload {
+    let Some(reloader) = &app_state.config_reloader else {
+        return ConfigReload::Unavailable;
+    };
+    let new = match Config::load(&reloader.sources) {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Configuration reload rejected: {}", e);
+            return ConfigReload::Rejected(e.to_string());
+        }
+    };
+
+    let mut current = reloader.current.lock().await;
+    let old = current.clone();
+
+    // Client keys are applied first, as reading the keys file can still fail. Turning them
+    // on or off takes a restart.
+    let client_settings = ClientSettings::from_config(&new);
+    let clients_applied = app_state.client_registry.is_some() && client_settings.is_some();
+    if let Some(registry) = &app_state.client_registry
+        && let Some(settings) = client_settings
+        && settings != registry.settings()
+        && let Err(e) = registry.reconfigure(settings)
+    {
+        warn!("Configuration reload rejected: {}", e);
+        return ConfigReload::Rejected(e.to_string());
+    }
+
+    let mut applied = Vec::new();
+    let mut changed = |key: &str, is_changed: bool| {
+        if is_changed {
+            applied.push(key.to_string());
+        }
+        is_changed
+    };
+
+    let poller_changed = [
+        changed(
+            "POLL_INTERVAL_SECONDS",
+            new.poll_interval != old.poll_interval,
+        ),
+        changed("MAX_RETRIES", new.max_retries != old.max_retries),
+        changed(
+            "INITIAL_BACKOFF_MS",
+            new.initial_backoff != old.initial_backoff,
+        ),
+    ]
+    .contains(&true);
+    let breaker_changed = [
+        changed(
+            "CIRCUIT_FAILURE_THRESHOLD",
+            new.circuit_failure_threshold != old.circuit_failure_threshold,
+        ),
+        changed(
+            "CIRCUIT_OPEN_DURATION_SECS",
+            new.circuit_open_duration != old.circuit_open_duration,
+        ),
+    ]
+    .contains(&true);
+    // Applied together with the poll interval, which the poll age threshold must exceed
+    let health_changed = [
+        changed(
+            "HEALTH_MAX_POLLER_LAG_SLOTS",
+            new.health_max_poller_lag != old.health_max_poller_lag,
+        ),
+        changed(
+            "HEALTH_MAX_POLL_AGE_SECS",
+            new.health_max_poll_age != old.health_max_poll_age,
+        ),
+        changed(
+            "HEALTH_FAIL_ON_OPEN_CIRCUIT",
+            new.health_fail_on_open_circuit != old.health_fail_on_open_circuit,
+        ),
+    ]
+    .contains(&true);
+    let cache_changed = changed("CACHE_CAPACITY", new.cache_capacity != old.cache_capacity);
+    let lru_changed = changed(
+        "LRU_CACHE_CAPACITY",
+        new.lru_cache_capacity != old.lru_cache_capacity,
+    );
+    if clients_applied {
+        changed(
+            "CLIENT_API_KEYS",
+            new.client_api_keys != old.client_api_keys,
+        );
+        changed(
+            "CLIENT_API_KEYS_FILE",
+            new.client_api_keys_file != old.client_api_keys_file,
+        );
+        changed(
+            "CLIENT_RATE_LIMIT_PER_SEC",
+            new.client_rate_limit_per_sec != old.client_rate_limit_per_sec,
+        );
+        changed(
+            "CLIENT_REQUEST_QUOTA",
+            new.client_request_quota != old.client_request_quota,
+        );
+        changed(
+            "CLIENT_RPC_FALLBACK_QUOTA",
+            new.client_rpc_fallback_quota != old.client_rpc_fallback_quota,
+        );
+        changed(
+            "CLIENT_QUOTA_WINDOW_SECS",
+            new.client_quota_window != old.client_quota_window,
+        );
+    }
+
+    if poller_changed {
+        app_state.poller.update_settings(PollerSettings {
+            poll_interval: new.poll_interval,
+            max_retries: new.max_retries,
+            initial_backoff: new.initial_backoff,
+            ..PollerSettings::from_config(&old)
+        });
+    }
+    if health_changed {
+        *app_state.health.write().unwrap() = HealthSettings::from_config(&new);
+    }
+    if breaker_changed {
+        app_state
+            .circuit_breaker
+            .reconfigure(new.circuit_failure_threshold, new.circuit_open_duration);
+    }
+    if cache_changed {
+        app_state.cache.resize(new.cache_capacity).await;
+    }
+    if lru_changed {
+        app_state.lru_cache.resize(new.lru_cache_capacity).await;
+    }
+
+    // Only the applied settings are written back, whatever else differs needs a restart
+    current.poll_interval = new.poll_interval;
+    current.max_retries = new.max_retries;
+    current.initial_backoff = new.initial_backoff;
+    current.health_max_poller_lag = new.health_max_poller_lag;
+    current.health_max_poll_age = new.health_max_poll_age;
+    current.health_fail_on_open_circuit = new.health_fail_on_open_circuit;
+    current.circuit_failure_threshold = new.circuit_failure_threshold;
+    current.circuit_open_duration = new.circuit_open_duration;
+    current.cache_capacity = new.cache_capacity;
+    current.lru_cache_capacity = new.lru_cache_capacity;
+    if clients_applied {
+        current.client_api_keys = new.client_api_keys.clone();
+        current.client_api_keys_file = new.client_api_keys_file.clone();
+        current.client_rate_limit_per_sec = new.client_rate_limit_per_sec;
+        current.client_request_quota = new.client_request_quota;
+        current.client_rpc_fallback_quota = new.client_rpc_fallback_quota;
+        current.client_quota_window = new.client_quota_window;
+    }
+    let restart_required = *current != new;
+
+    info!("Configuration reloaded, applied {:?}", applied);
+    if restart_required {
+        warn!("Some changed settings only take effect after a restart.");
+    }
+    ConfigReload::Applied(ReloadReport {
+        applied,
+        restart_required,
+    })
+}
+
+/// Reloads the configuration every time the process receives SIGHUP, until shutdown
+#[cfg(unix)]
+pub fn reload_on_sighup(app_state: AppState, mut shutdown_rx: broadcast::Receiver<()>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangup = signal(SignalKind::hangup()).expect("failed to install SIGHUP handler");
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.recv() => break,
+                received = hangup.recv() => {
+                    if received.is_none() {
+                        break;
+                    }
+                    info!("Received SIGHUP, reloading configuration.");
+                    reload_config(&app_state).await;
+                }
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn reload_on_sighup(_app_state: AppState, _shutdown_rx: broadcast::Receiver<()>) {}
@@ -0,0 +1,117 @@
+use crate::circuit_breaker::CircuitBreakerError;
+use crate::commitment::Commitment;
+use crate::service::client_service::acquire_rpc_fallbacks;
+use crate::slot_info::SlotInfo;
+use crate::state::AppState;
+use futures_util::{StreamExt, stream};
+use tracing::{error, warn};
+
+// getBlock calls the poller keeps in flight while filling in block fields
+const METADATA_FETCH_CONCURRENCY: usize = 8;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotInfoLookup {
+    Found(SlotInfo),
+    NotConfirmed,
+    Error,
+}
+
+/// Returns the cached record of the slot if it reached the requested commitment and its
+/// block fields are known, otherwise fetches the block and caches it.
+pub async fn get_slot_info(
+    app_state: &AppState,
+    slot: u64,
+    commitment: Commitment,
+) -> SlotInfoLookup {
+    let cached = match app_state.cache.get_info(&slot).await {
+        Some(info) => Some(info),
+        None => app_state.lru_cache.get_info(&slot).await,
+    };
+    if let Some(info) = &cached
+        && info.commitment >= commitment
+        && info.block.is_some()
+    {
+        return SlotInfoLookup::Found(info.clone());
+    }
+    let stored_skipped = match &app_state.disk_store {
+        Some(disk_store) => disk_store.is_skipped_at(&slot, commitment).await,
+        None => false,
+    };
+    if stored_skipped
+        || app_state.cache.is_skipped_at(&slot, commitment).await
+        || app_state.lru_cache.is_skipped_at(&slot, commitment).await
+    {
+        return SlotInfoLookup::NotConfirmed;
+    }
+    if !acquire_rpc_fallbacks(1) {
+        return SlotInfoLookup::Error;
+    }
+
+    // getBlock has no processed view, a confirmed block satisfies a processed query
+    let rpc_commitment = commitment.max(Commitment::Confirmed);
+    let rpc_call = || {
+        app_state
+            .rpc_client
+            .get_block_metadata(slot, rpc_commitment)
+    };
+    match app_state.circuit_breaker.execute(rpc_call).await {
+        Ok(Some(block)) => {
+            let info = SlotInfo::new(slot, rpc_commitment).with_block(block);
+            match app_state.cache.merge_info(info.clone()).await {
+                Some(merged) => SlotInfoLookup::Found(merged),
+                None => {
+                    app_state.lru_cache.put_info(info.clone()).await;
+                    SlotInfoLookup::Found(info)
+                }
+            }
+        }
+        Ok(None) => SlotInfoLookup::NotConfirmed,
+        Err(CircuitBreakerError::Inner(rpc_err)) => {
+            error!(
+                "RPC error while fetching block of slot {} at {} commitment: {}",
+                slot, commitment, rpc_err
+            );
+            SlotInfoLookup::Error
+        }
+        Err(CircuitBreakerError::CircuitOpen) => {
+            warn!(
+                "Circuit is open. Rejecting block request for slot {}.",
+                slot
+            );
+            SlotInfoLookup::Error
+        }
+    }
+}
+
+/// Fetches block fields for the cached slots that have none yet
+pub(crate) async fn fill_block_metadata(
+    app_state: &AppState,
+    slots: &[u64],
+    commitment: Commitment,
+) {
+    let mut missing = Vec::new();
+    for &slot in slots {
+        if app_state
+            .cache
+            .get_info(&slot)
+            .await
+            .is_some_and(|info| info.block.is_none())
+        {
+            missing.push(slot);
+        }
+    }
+
+    stream::iter(missing)
+        .for_each_concurrent(METADATA_FETCH_CONCURRENCY, |slot| async move {
+            let rpc_call = || app_state.rpc_client.get_block_metadata(slot, commitment);
+            match app_state.circuit_breaker.execute(rpc_call).await {
+                Ok(Some(block)) => {
+                    let info = SlotInfo::new(slot, commitment).with_block(block);
+                    app_state.cache.merge_info(info).await;
+                }
+                Ok(None) => {}
+                Err(e) => warn!("Failed to fetch block of slot {}: {}", slot, e),
+            }
+        })
+        .await;
+}
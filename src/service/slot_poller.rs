@@ -1,10 +1,28 @@
 use crate::circuit_breaker::CircuitBreaker;
+use crate::commitment::Commitment;
+use crate::config::Config;
+use crate::logging::LogSampler;
+use crate::service::slot_info_service::fill_block_metadata;
+use crate::state::AppState;
 use crate::utils::retry::{with_retry, with_transient_retry};
-use crate::{cache::SlotCache, metrics::Metrics, rpc::RpcApi};
-use std::{sync::Arc, time::Duration};
+use crate::{
+    cache::{SlotCache, StoredSlot},
+    metrics::Metrics,
+    rpc::RpcApi,
+};
+use futures_util::{StreamExt, stream};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 use tokio::sync::broadcast;
 use tokio::time::{Instant, sleep};
-use tracing::{info, warn};
+use tracing::{Span, field, info, instrument, warn};
 
 pub fn poll<T: RpcApi + 'static + ?Sized>(
     rpc_client: Arc<T>,
@@ -21,8 +39,6 @@ pub fn poll<T: RpcApi + 'static + ?Sized>(
         loop {
             sleep(poll_interval).await;
 
-            info!("cache: {:?}", cache);
-
             let latest_on_chain = match rpc_client.get_slot().await {
                 Ok(slot) => slot,
                 Err(e) => {
@@ -89,8 +105,6 @@ pub fn poll_with_retry<T: RpcApi + 'static + ?Sized>(
         loop {
             sleep(poll_interval).await;
 
-            info!("cache: {:?}", cache);
-
             let latest_on_chain = match rpc_client.get_slot().await {
                 Ok(slot) => slot,
                 Err(e) => {
@@ -152,8 +166,6 @@ pub fn poll_with_transient_retry<T: RpcApi + 'static + ?Sized>(
         loop {
             sleep(poll_interval).await;
 
-            info!("cache: {:?}", cache);
-
             let latest_on_chain = match rpc_client.get_slot().await {
                 Ok(slot) => slot,
                 Err(e) => {
@@ -224,8 +236,6 @@ pub fn poll_with_transient_retry_and_signals<T: RpcApi + 'static + ?Sized>(
                 }
             }
 
-            info!("cache: {:?}", cache);
-
             let latest_on_chain = match rpc_client.get_slot().await {
                 Ok(slot) => slot,
                 Err(e) => {
@@ -270,22 +280,31 @@ pub fn poll_with_transient_retry_and_signals<T: RpcApi + 'static + ?Sized>(
     });
 }
 
+/// How often the poller runs and how its failed calls are retried
+#[derive(Debug, Clone, Copy)]
+pub struct PollSchedule {
+    pub poll_interval: Duration,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+}
+
 pub fn poll_with_transient_retry_and_signals_and_breaker<T: RpcApi + 'static + ?Sized>(
     rpc_client: Arc<T>,
     cache: Arc<SlotCache>,
     metrics: Arc<dyn Metrics + Send + Sync>,
     circuit_breaker: Arc<CircuitBreaker>,
-    poll_interval: Duration,
-    max_retries: u32,
-    initial_backoff: Duration,
+    schedule: PollSchedule,
     mut shutdown_rx: broadcast::Receiver<()>,
 ) {
+    let PollSchedule {
+        poll_interval,
+        max_retries,
+        initial_backoff,
+    } = schedule;
     info!("Starting background slot poller with ALL features enabled.");
 
     tokio::spawn(async move {
         loop {
-            info!("cache: {:?}", cache);
-
             tokio::select! {
                 biased;
                 _ = shutdown_rx.recv() => {
@@ -343,3 +362,645 @@ pub fn poll_with_transient_retry_and_signals_and_breaker<T: RpcApi + 'static + ?
         }
     });
 }
+
+#[derive(Debug, Clone)]
+pub struct PollerSettings {
+    pub poll_interval: Duration,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    /// Commitment levels tracked by the poller, from least to most final
+    pub commitments: Vec<Commitment>,
+    /// Whether to fetch blockhash, parent, height and time of every newly polled slot
+    pub fetch_block_metadata: bool,
+    /// Upper bound of the number of slots covered by one `get_blocks` call
+    pub backfill_chunk_size: u64,
+    /// Number of chunks fetched in parallel while catching up
+    pub backfill_concurrency: usize,
+    /// Only one in every `log_sample_every` per-chunk messages is logged
+    pub log_sample_every: u64,
+}
+
+impl PollerSettings {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            poll_interval: config.poll_interval,
+            max_retries: config.max_retries,
+            initial_backoff: config.initial_backoff,
+            commitments: config.poll_commitments.clone(),
+            fetch_block_metadata: config.fetch_block_metadata,
+            backfill_chunk_size: config.poll_backfill_chunk_size.max(1),
+            backfill_concurrency: config.poll_backfill_concurrency.max(1),
+            log_sample_every: config.log_sample_every,
+        }
+    }
+}
+
+/// Progress of a catch-up spanning more than one chunk
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CatchUpProgress {
+    /// Chain tip the level is catching up to
+    pub target_slot: u64,
+    pub total_slots: u64,
+    pub fetched_slots: u64,
+    pub total_chunks: usize,
+    pub fetched_chunks: usize,
+    pub failed_chunks: usize,
+}
+
+/// What the poller reports for one commitment level
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LevelStatus {
+    pub commitment: Commitment,
+    pub cursor: Option<u64>,
+    pub catch_up: Option<CatchUpProgress>,
+}
+
+#[derive(Debug, Default)]
+struct LevelState {
+    cursor: Option<u64>,
+    // Lowest slot the poller has fetched at this level, holes are looked for above it
+    covered_from: Option<u64>,
+    catch_up: Option<CatchUpProgress>,
+    // Chain tip minus latest cached slot at the end of the last poll
+    lag: Option<u64>,
+}
+
+/// Reconciliation cursor per commitment level, i.e. the slot the poller has fetched all
+/// blocks up to. Kept apart from the cache so that slots pushed by a slot source ahead of
+/// the poller do not hide the range in between, and shared so it can be snapshotted and
+/// its catch-up progress reported.
+#[derive(Debug, Default)]
+pub struct PollerState {
+    levels: Mutex<BTreeMap<Commitment, LevelState>>,
+    warmed_up: AtomicBool,
+    // Highest chain tip seen at any level, all levels backfill from the same floor below it
+    highest_tip: AtomicU64,
+    last_success: Mutex<Option<Instant>>,
+    upstream_reachable: AtomicBool,
+    paused: AtomicBool,
+    pending_settings: Mutex<Option<PollerSettings>>,
+    chunk_log: LogSampler,
+}
+
+impl PollerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the startup warm-up has filled the primary cache of every tracked level
+    pub fn is_warmed_up(&self) -> bool {
+        self.warmed_up.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_warmed_up(&self) {
+        self.warmed_up.store(true, Ordering::SeqCst);
+    }
+
+    fn observe_tip(&self, slot: u64) {
+        self.highest_tip.fetch_max(slot, Ordering::SeqCst);
+    }
+
+    /// Lowest slot worth fetching at any level, so that the `capacity` most recent slots of
+    /// the freshest level fit in the primary cache together. Every level shares the cache,
+    /// per level floors would have the levels evict each other's slots and refetch them.
+    fn backfill_floor(&self, capacity: usize) -> u64 {
+        self.highest_tip
+            .load(Ordering::SeqCst)
+            .saturating_sub(capacity.saturating_sub(1) as u64)
+    }
+
+    /// Time since a poll round last reconciled at least one level, `None` before the first one
+    pub fn since_last_success(&self) -> Option<Duration> {
+        self.last_success.lock().unwrap().map(|at| at.elapsed())
+    }
+
+    pub fn mark_polled(&self) {
+        *self.last_success.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// Whether the last attempt to read the chain tip got an answer from upstream
+    pub fn is_upstream_reachable(&self) -> bool {
+        self.upstream_reachable.load(Ordering::SeqCst)
+    }
+
+    pub fn set_upstream_reachable(&self, reachable: bool) {
+        self.upstream_reachable.store(reachable, Ordering::SeqCst);
+    }
+
+    /// Whether poll rounds are skipped until the poller is resumed
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::SeqCst);
+    }
+
+    /// Hands new settings to the running poller, which switches to them before its next round.
+    /// The tracked commitment levels cannot change while running and are kept.
+    pub fn update_settings(&self, settings: PollerSettings) {
+        *self.pending_settings.lock().unwrap() = Some(settings);
+    }
+
+    fn apply_pending_settings(&self, settings: &mut PollerSettings) {
+        if let Some(update) = self.pending_settings.lock().unwrap().take() {
+            *settings = PollerSettings {
+                commitments: std::mem::take(&mut settings.commitments),
+                ..update
+            };
+        }
+    }
+
+    /// Lag in slots per level, as measured at the end of the last poll
+    pub fn lags(&self) -> BTreeMap<Commitment, u64> {
+        self.levels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(&commitment, level)| Some((commitment, level.lag?)))
+            .collect()
+    }
+
+    pub fn set_lag(&self, commitment: Commitment, lag: u64) {
+        self.levels
+            .lock()
+            .unwrap()
+            .entry(commitment)
+            .or_default()
+            .lag = Some(lag);
+    }
+
+    pub fn cursor(&self, commitment: Commitment) -> Option<u64> {
+        self.levels
+            .lock()
+            .unwrap()
+            .get(&commitment)
+            .and_then(|level| level.cursor)
+    }
+
+    pub fn set_cursor(&self, commitment: Commitment, slot: u64) {
+        self.levels
+            .lock()
+            .unwrap()
+            .entry(commitment)
+            .or_default()
+            .cursor = Some(slot);
+    }
+
+    pub fn cursors(&self) -> BTreeMap<Commitment, u64> {
+        self.levels
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(&commitment, level)| Some((commitment, level.cursor?)))
+            .collect()
+    }
+
+    pub fn status(&self) -> Vec<LevelStatus> {
+        self.levels
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(&commitment, level)| LevelStatus {
+                commitment,
+                cursor: level.cursor,
+                catch_up: level.catch_up.clone(),
+            })
+            .collect()
+    }
+
+    fn covered_from(&self, commitment: Commitment) -> Option<u64> {
+        self.levels
+            .lock()
+            .unwrap()
+            .get(&commitment)
+            .and_then(|level| level.covered_from)
+    }
+
+    fn set_covered_from(&self, commitment: Commitment, slot: u64) {
+        self.levels
+            .lock()
+            .unwrap()
+            .entry(commitment)
+            .or_default()
+            .covered_from = Some(slot);
+    }
+
+    fn update_catch_up(
+        &self,
+        commitment: Commitment,
+        update: impl FnOnce(&mut Option<CatchUpProgress>),
+    ) {
+        update(
+            &mut self
+                .levels
+                .lock()
+                .unwrap()
+                .entry(commitment)
+                .or_default()
+                .catch_up,
+        );
+    }
+}
+
+pub fn poll_with_commitments(
+    app_state: AppState,
+    mut settings: PollerSettings,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    info!(
+        "Starting background slot poller tracking commitments {:?} with interval: {:?}",
+        settings.commitments, settings.poll_interval
+    );
+
+    tokio::spawn(async move {
+        if !warm_up(&app_state, &mut settings, &mut shutdown_rx).await {
+            info!("Shutdown signal received during warm-up, stopping commitment poller task.");
+            return;
+        }
+
+        loop {
+            app_state.poller.apply_pending_settings(&mut settings);
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.recv() => {
+                    info!("Shutdown signal received, stopping commitment poller task.");
+                    break;
+                }
+                _ = sleep(settings.poll_interval) => {}
+            }
+
+            poll_round(&app_state, &settings).await;
+        }
+    });
+}
+
+/// Fills the primary cache with the `CACHE_CAPACITY` most recent slots of every tracked level
+/// right away, repeating failed rounds every poll interval until no slot is missing, and then
+/// marks the poller as warmed up. Returns `false` if shutdown was requested first.
+async fn warm_up(
+    app_state: &AppState,
+    settings: &mut PollerSettings,
+    shutdown_rx: &mut broadcast::Receiver<()>,
+) -> bool {
+    info!(
+        "Warming up the primary cache with the {} most recent slots.",
+        app_state.cache.capacity()
+    );
+    let started = Instant::now();
+    loop {
+        app_state.poller.apply_pending_settings(settings);
+        poll_round(app_state, settings).await;
+
+        let mut cold = Vec::new();
+        for &commitment in &settings.commitments {
+            if !is_warm(app_state, commitment).await {
+                cold.push(commitment);
+            }
+        }
+        if cold.is_empty() {
+            break;
+        }
+        if !app_state.poller.is_paused() {
+            warn!(
+                "Warm-up incomplete for {:?}, retrying in {:?}.",
+                cold, settings.poll_interval
+            );
+        }
+
+        tokio::select! {
+            biased;
+            _ = shutdown_rx.recv() => return false,
+            _ = sleep(settings.poll_interval) => {}
+        }
+    }
+
+    app_state.poller.mark_warmed_up();
+    info!("Warm-up complete after {:?}.", started.elapsed());
+    true
+}
+
+// A level is warm once the poller has a cursor and no slot between the shared backfill floor
+// and the cursor is missing. Slots below the floor may already be evicted, which is expected.
+async fn is_warm(app_state: &AppState, commitment: Commitment) -> bool {
+    let Some(cursor) = app_state.poller.cursor(commitment) else {
+        return false;
+    };
+    let covered_from = app_state
+        .poller
+        .covered_from(commitment)
+        .unwrap_or(cursor)
+        .max(app_state.poller.backfill_floor(app_state.cache.capacity()));
+    covered_from > cursor
+        || app_state
+            .cache
+            .missing_ranges(covered_from, cursor, commitment)
+            .await
+            .is_empty()
+}
+
+#[instrument(skip_all)]
+async fn poll_round(app_state: &AppState, settings: &PollerSettings) {
+    if app_state.poller.is_paused() {
+        return;
+    }
+    // The least final level is the freshest one, it drives the latest slot metric
+    let freshest = settings.commitments.first().copied();
+    for &commitment in &settings.commitments {
+        let cursor = app_state.poller.cursor(commitment);
+        let record_latest = freshest == Some(commitment);
+        if let Some(polled_up_to) =
+            poll_commitment(app_state, settings, commitment, cursor, record_latest).await
+        {
+            app_state.poller.set_cursor(commitment, polled_up_to);
+            app_state.poller.mark_polled();
+        }
+    }
+}
+
+/// Fetches the blocks between the cursor and the chain tip at the given commitment, along
+/// with holes found behind the cursor. Large ranges are split into chunks fetched in parallel.
+/// Returns the slot the level is reconciled up to, or `None` if nothing could be fetched.
+#[instrument(skip(app_state, settings, record_latest), fields(latest_slot = field::Empty))]
+async fn poll_commitment(
+    app_state: &AppState,
+    settings: &PollerSettings,
+    commitment: Commitment,
+    cursor: Option<u64>,
+    record_latest: bool,
+) -> Option<u64> {
+    let rpc_client = &app_state.rpc_client;
+
+    let get_slot_call = || rpc_client.get_slot_with_commitment(commitment);
+    let latest_on_chain = match app_state.circuit_breaker.execute(get_slot_call).await {
+        Ok(slot) => slot,
+        Err(e) => {
+            warn!(
+                "Failed to get latest {} slot (circuit breaker): {}",
+                commitment, e
+            );
+            app_state.poller.set_upstream_reachable(false);
+            return None;
+        }
+    };
+    app_state.poller.set_upstream_reachable(true);
+    app_state.poller.observe_tip(latest_on_chain);
+    Span::current().record("latest_slot", latest_on_chain);
+
+    let polled_up_to = match cursor {
+        Some(cursor) => Some(cursor),
+        None => app_state.cache.get_latest_slot_at(commitment).await,
+    };
+    // Without a cursor or cached slot the level starts with the most recent slots the cache holds
+    let start_slot = match polled_up_to {
+        Some(latest_cached) => latest_cached + 1,
+        None => latest_on_chain.saturating_sub(app_state.cache.capacity().saturating_sub(1) as u64),
+    };
+    // Slots older than the cache holds would be evicted right away
+    let backfill_floor = app_state.poller.backfill_floor(app_state.cache.capacity());
+    if start_slot < backfill_floor {
+        info!(
+            "{} poller is {} slots behind, backfilling only the last {} slots.",
+            commitment,
+            latest_on_chain.saturating_sub(start_slot),
+            app_state.cache.capacity()
+        );
+    }
+    let start_slot = start_slot.max(backfill_floor);
+
+    let covered_from = app_state
+        .poller
+        .covered_from(commitment)
+        .unwrap_or(start_slot)
+        .max(backfill_floor);
+    app_state.poller.set_covered_from(commitment, covered_from);
+
+    let mut holes = Vec::new();
+    if start_slot > covered_from {
+        holes = app_state
+            .cache
+            .missing_ranges(covered_from, start_slot - 1, commitment)
+            .await;
+    }
+    if !holes.is_empty() {
+        info!(
+            "Found {} holes in the {} cache behind slot {}, refetching them.",
+            holes.len(),
+            commitment,
+            start_slot
+        );
+    }
+
+    let mut ranges = holes;
+    if start_slot <= latest_on_chain {
+        ranges.push((start_slot, latest_on_chain));
+    }
+    if ranges.is_empty() {
+        record_lag(app_state, commitment, latest_on_chain).await;
+        // A level whose tip is still below the shared floor has nothing worth caching yet
+        return polled_up_to.or(Some(latest_on_chain));
+    }
+    let chunks = split_into_chunks(&ranges, settings.backfill_chunk_size);
+
+    let catching_up = chunks.len() > 1;
+    if catching_up {
+        let total_slots = chunks.iter().map(|&(start, end)| end - start + 1).sum();
+        info!(
+            "{} poller catching up on {} slots in {} chunks.",
+            commitment,
+            total_slots,
+            chunks.len()
+        );
+        app_state.poller.update_catch_up(commitment, |catch_up| {
+            *catch_up = Some(CatchUpProgress {
+                target_slot: latest_on_chain,
+                total_slots,
+                fetched_slots: 0,
+                total_chunks: chunks.len(),
+                fetched_chunks: 0,
+                failed_chunks: 0,
+            })
+        });
+    }
+
+    // Chunks complete in order, so the cursor can follow the fetched prefix of the tip range
+    let mut results = stream::iter(chunks.iter().copied())
+        .map(|(start, end)| fetch_chunk(app_state, settings, start, end, commitment))
+        .buffered(settings.backfill_concurrency);
+    let mut reconciled_up_to = polled_up_to;
+    let mut tip_range_intact = true;
+    let mut highest_block = None;
+    let mut index = 0;
+    while let Some(result) = results.next().await {
+        let (start, end) = chunks[index];
+        index += 1;
+        let fetched = result.is_some();
+        match result {
+            Some(slots) => {
+                highest_block = highest_block.max(slots.iter().max().copied());
+                record_chunk(app_state, settings, start, end, &slots, commitment).await;
+                if start >= start_slot && tip_range_intact {
+                    reconciled_up_to = Some(end);
+                }
+            }
+            None if start >= start_slot => tip_range_intact = false,
+            None => {}
+        }
+        if catching_up {
+            app_state.poller.update_catch_up(commitment, |catch_up| {
+                if let Some(catch_up) = catch_up {
+                    catch_up.fetched_chunks += 1;
+                    if fetched {
+                        catch_up.fetched_slots += end - start + 1;
+                    } else {
+                        catch_up.failed_chunks += 1;
+                    }
+                    if catch_up.fetched_chunks % 10 == 0 {
+                        info!(
+                            "{} catch-up progress: {}/{} slots, {} chunks failed.",
+                            commitment,
+                            catch_up.fetched_slots,
+                            catch_up.total_slots,
+                            catch_up.failed_chunks
+                        );
+                    }
+                }
+            });
+        }
+    }
+
+    if record_latest && let Some(latest_slot) = highest_block {
+        app_state.metrics.record_latest_slot(latest_slot);
+    }
+    record_lag(app_state, commitment, latest_on_chain).await;
+    if catching_up {
+        app_state.poller.update_catch_up(commitment, |catch_up| {
+            if let Some(catch_up) = catch_up.take() {
+                info!(
+                    "{} catch-up finished: {}/{} slots fetched, {} chunks failed.",
+                    commitment,
+                    catch_up.fetched_slots,
+                    catch_up.total_slots,
+                    catch_up.failed_chunks
+                );
+            }
+        });
+    }
+    if reconciled_up_to == polled_up_to && !tip_range_intact {
+        return None;
+    }
+    reconciled_up_to
+}
+
+async fn record_lag(app_state: &AppState, commitment: Commitment, latest_on_chain: u64) {
+    if let Some(latest_cached) = app_state.cache.get_latest_slot_at(commitment).await {
+        let lag = latest_on_chain.saturating_sub(latest_cached);
+        app_state.poller.set_lag(commitment, lag);
+        app_state.metrics.record_poller_lag(commitment, lag);
+    }
+}
+
+/// Splits inclusive ranges into inclusive chunks of at most `chunk_size` slots
+pub fn split_into_chunks(ranges: &[(u64, u64)], chunk_size: u64) -> Vec<(u64, u64)> {
+    let chunk_size = chunk_size.max(1);
+    let mut chunks = Vec::new();
+    for &(start, end) in ranges {
+        let mut chunk_start = start;
+        while chunk_start <= end {
+            let chunk_end = end.min(chunk_start.saturating_add(chunk_size - 1));
+            chunks.push((chunk_start, chunk_end));
+            if chunk_end == u64::MAX {
+                break;
+            }
+            chunk_start = chunk_end + 1;
+        }
+    }
+    chunks
+}
+
+#[instrument(skip(app_state, settings), fields(retries = field::Empty))]
+async fn fetch_chunk(
+    app_state: &AppState,
+    settings: &PollerSettings,
+    start: u64,
+    end: u64,
+    commitment: Commitment,
+) -> Option<Vec<u64>> {
+    let rpc_client = &app_state.rpc_client;
+    let now = Instant::now();
+    let attempts = AtomicU32::new(0);
+    let get_blocks_with_retry_call = || {
+        with_transient_retry(
+            "get_blocks",
+            || {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                rpc_client.get_blocks_with_commitment(start, Some(end), commitment)
+            },
+            settings.max_retries,
+            settings.initial_backoff,
+        )
+    };
+
+    let blocks_result = app_state
+        .circuit_breaker
+        .execute(get_blocks_with_retry_call)
+        .await;
+    app_state.metrics.record_get_blocks_elapsed(now.elapsed());
+    let retries = attempts.load(Ordering::SeqCst).saturating_sub(1);
+    Span::current().record("retries", retries);
+    if retries > 0 {
+        app_state.metrics.record_retries("get_blocks", retries);
+    }
+
+    match blocks_result {
+        Ok(slots) => Some(slots),
+        Err(e) => {
+            warn!(
+                "get_blocks operation for slots {}..={} at {} commitment failed (circuit breaker): {}",
+                start, end, commitment, e
+            );
+            None
+        }
+    }
+}
+
+// Every slot of a fetched chunk is decided, the ones without a block were skipped
+async fn record_chunk(
+    app_state: &AppState,
+    settings: &PollerSettings,
+    start: u64,
+    end: u64,
+    slots: &[u64],
+    commitment: Commitment,
+) {
+    if let Some(suppressed) = app_state.poller.chunk_log.sample(settings.log_sample_every) {
+        info!(
+            suppressed,
+            "Found {} new {} slots to cache.",
+            slots.len(),
+            commitment
+        );
+    }
+    let confirmed: HashSet<u64> = slots.iter().copied().collect();
+    for slot in (start..=end).filter(|slot| !confirmed.contains(slot)) {
+        app_state.cache.insert_skipped(slot, commitment).await;
+    }
+    for &slot in slots {
+        app_state
+            .cache
+            .insert_with_commitment(slot, commitment)
+            .await;
+    }
+    if let Some(disk_store) = &app_state.disk_store {
+        let skipped = (start..=end)
+            .filter(|slot| !confirmed.contains(slot))
+            .map(|slot| (slot, StoredSlot::Skipped(commitment)));
+        let blocks = slots
+            .iter()
+            .map(|&slot| (slot, StoredSlot::Confirmed(commitment)));
+        disk_store.record_all(skipped.chain(blocks)).await;
+    }
+    if settings.fetch_block_metadata {
+        fill_block_metadata(app_state, slots, commitment).await;
+    }
+}
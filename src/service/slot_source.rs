@@ -0,0 +1,261 @@
+use crate::{cache::SlotCache, commitment::Commitment};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::{error::Error, fmt, future::Future, pin::Pin, sync::Arc, time::Duration};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{sleep, timeout};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Message, client::IntoClientRequest, http::HeaderMap},
+};
+use tracing::{debug, info, warn};
+
+const SLOT_SUBSCRIBE_ID: u64 = 1;
+const ROOT_SUBSCRIBE_ID: u64 = 2;
+
+const UPDATE_CHANNEL_CAPACITY: usize = 1024;
+const MAX_RECONNECT_BACKOFF: Duration = Duration::from_secs(30);
+// Slots arrive roughly every 400ms, a socket silent for this long is treated as dead
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A slot observed by a push source at a given commitment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotUpdate {
+    pub slot: u64,
+    pub commitment: Commitment,
+}
+
+#[derive(Debug)]
+pub enum SlotSourceError {
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+    Subscription(String),
+    Idle(Duration),
+}
+
+impl fmt::Display for SlotSourceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotSourceError::WebSocket(e) => write!(f, "WebSocket error: {}", e),
+            SlotSourceError::Subscription(msg) => write!(f, "Subscription failed: {}", msg),
+            SlotSourceError::Idle(after) => write!(f, "No message received for {:?}", after),
+        }
+    }
+}
+
+impl Error for SlotSourceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SlotSourceError::WebSocket(e) => Some(e),
+            SlotSourceError::Subscription(_) | SlotSourceError::Idle(_) => None,
+        }
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for SlotSourceError {
+    fn from(e: tokio_tungstenite::tungstenite::Error) -> Self {
+        SlotSourceError::WebSocket(e)
+    }
+}
+
+pub trait SlotSource: Send + Sync {
+    /// Streams slot updates into `updates` until the subscription ends.
+    /// Returns `Ok(())` when the remote side closes the subscription cleanly.
+    fn run<'a>(
+        &'a self,
+        updates: mpsc::Sender<SlotUpdate>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), SlotSourceError>> + Send + 'a>>;
+}
+
+/// Slot source backed by the Solana pubsub WebSocket API.
+///
+/// `slotSubscribe` notifications are reported as processed slots,
+/// `rootSubscribe` notifications as finalized ones.
+pub struct PubsubSlotSource {
+    ws_url: String,
+    headers: HeaderMap,
+    read_timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct PubsubMessage {
+    id: Option<u64>,
+    method: Option<String>,
+    params: Option<NotificationParams>,
+    error: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NotificationParams {
+    result: Value,
+}
+
+impl PubsubSlotSource {
+    pub fn new(ws_url: impl Into<String>) -> Self {
+        Self {
+            ws_url: ws_url.into(),
+            headers: HeaderMap::new(),
+            read_timeout: DEFAULT_READ_TIMEOUT,
+        }
+    }
+
+    /// Headers sent with the WebSocket handshake, e.g. the provider API key
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// How long the socket may stay silent before the subscription is dropped and reconnected
+    pub fn with_read_timeout(mut self, read_timeout: Duration) -> Self {
+        self.read_timeout = read_timeout;
+        self
+    }
+
+    async fn subscribe_and_forward(
+        &self,
+        updates: mpsc::Sender<SlotUpdate>,
+    ) -> Result<(), SlotSourceError> {
+        let mut request = self.ws_url.as_str().into_client_request()?;
+        request.headers_mut().extend(self.headers.clone());
+        let (mut ws, _) = connect_async(request).await?;
+
+        for (id, method) in [
+            (SLOT_SUBSCRIBE_ID, "slotSubscribe"),
+            (ROOT_SUBSCRIBE_ID, "rootSubscribe"),
+        ] {
+            let request = json!({"jsonrpc": "2.0", "id": id, "method": method});
+            ws.send(Message::Text(request.to_string().into())).await?;
+        }
+
+        loop {
+            let Some(message) = timeout(self.read_timeout, ws.next())
+                .await
+                .map_err(|_| SlotSourceError::Idle(self.read_timeout))?
+            else {
+                break;
+            };
+            let text = match message? {
+                Message::Text(text) => text,
+                Message::Ping(payload) => {
+                    ws.send(Message::Pong(payload)).await?;
+                    continue;
+                }
+                Message::Close(_) => break,
+                _ => continue,
+            };
+
+            let message: PubsubMessage = match serde_json::from_str(&text) {
+                Ok(message) => message,
+                Err(e) => {
+                    warn!("Ignoring malformed pubsub message: {}", e);
+                    continue;
+                }
+            };
+
+            if let Some(error) = message.error {
+                return Err(SlotSourceError::Subscription(format!(
+                    "request {:?} rejected: {}",
+                    message.id, error
+                )));
+            }
+
+            let Some(update) = parse_notification(&message) else {
+                continue;
+            };
+            if updates.send(update).await.is_err() {
+                // Nobody is listening anymore, there is no point in keeping the socket open
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_notification(message: &PubsubMessage) -> Option<SlotUpdate> {
+    let result = &message.params.as_ref()?.result;
+    match message.method.as_deref()? {
+        "slotNotification" => Some(SlotUpdate {
+            slot: result.get("slot")?.as_u64()?,
+            commitment: Commitment::Processed,
+        }),
+        "rootNotification" => Some(SlotUpdate {
+            slot: result.as_u64()?,
+            commitment: Commitment::Finalized,
+        }),
+        _ => None,
+    }
+}
+
+impl SlotSource for PubsubSlotSource {
+    fn run<'a>(
+        &'a self,
+        updates: mpsc::Sender<SlotUpdate>,
+    ) -> Pin<Box<dyn Future<Output = Result<(), SlotSourceError>> + Send + 'a>> {
+        Box::pin(self.subscribe_and_forward(updates))
+    }
+}
+
+/// Feeds the primary cache from a push source, reconnecting with exponential backoff
+/// whenever the subscription drops. The poller keeps reconciling in the meantime.
+pub fn ingest_slot_updates<S: SlotSource + 'static + ?Sized>(
+    source: Arc<S>,
+    cache: Arc<SlotCache>,
+    reconnect_backoff: Duration,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) {
+    info!("Starting push-based slot ingestion.");
+
+    tokio::spawn(async move {
+        let mut backoff = reconnect_backoff;
+
+        loop {
+            let (tx, mut rx) = mpsc::channel(UPDATE_CHANNEL_CAPACITY);
+            let run = source.run(tx);
+            tokio::pin!(run);
+
+            let mut received = 0_u64;
+            let result = loop {
+                tokio::select! {
+                    biased;
+                    _ = shutdown_rx.recv() => {
+                        info!("Shutdown signal received, stopping slot ingestion task.");
+                        return;
+                    }
+                    Some(update) = rx.recv() => {
+                        received += 1;
+                        cache.insert_with_commitment(update.slot, update.commitment).await;
+                    }
+                    result = &mut run => break result,
+                }
+            };
+
+            while let Ok(update) = rx.try_recv() {
+                cache
+                    .insert_with_commitment(update.slot, update.commitment)
+                    .await;
+            }
+
+            match result {
+                Ok(()) => warn!("Slot subscription closed after {} updates.", received),
+                Err(e) => warn!("Slot subscription failed after {} updates: {}", received, e),
+            }
+
+            // A connection that delivered updates was healthy, start the backoff over
+            if received > 0 {
+                backoff = reconnect_backoff;
+            }
+            debug!("Reconnecting slot subscription in {:?}", backoff);
+
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.recv() => {
+                    info!("Shutdown signal received, stopping slot ingestion task.");
+                    return;
+                }
+                _ = sleep(backoff) => {}
+            }
+            backoff = (backoff * 2).min(MAX_RECONNECT_BACKOFF);
+        }
+    });
+}
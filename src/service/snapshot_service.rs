@@ -0,0 +1,167 @@
+use crate::{cache::LruEntry, commitment::Commitment, slot_info::SlotInfo, state::AppState};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{fs, io::AsyncWriteExt, sync::broadcast, task::JoinHandle, time::sleep};
+use tracing::{info, warn};
+
+/// Bumped whenever the snapshot layout changes, older snapshots are ignored
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Contents of both cache tiers and the poller cursors at a point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    /// When the snapshot was taken, in Unix milliseconds
+    pub taken_at: u64,
+    pub cursors: BTreeMap<Commitment, u64>,
+    /// Primary cache records, oldest first
+    pub primary: Vec<SlotInfo>,
+    /// Known skipped slots of the primary cache, oldest first
+    pub primary_skipped: Vec<(u64, Commitment)>,
+    /// LRU cache entries, most recently used first
+    pub lru: Vec<LruEntry>,
+}
+
+pub async fn take_snapshot(app_state: &AppState) -> Snapshot {
+    Snapshot {
+        version: SNAPSHOT_VERSION,
+        taken_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default(),
+        cursors: app_state.poller.cursors(),
+        primary: app_state.cache.entries().await,
+        primary_skipped: app_state.cache.skipped_entries().await,
+        lru: app_state.lru_cache.entries().await,
+    }
+}
+
+/// Writes a snapshot of the caches to `path`. The file is replaced atomically,
+/// so a crash mid-write leaves the previous snapshot intact.
+pub async fn write_snapshot(app_state: &AppState, path: &Path) -> Result<(), Error> {
+    let snapshot = take_snapshot(app_state).await;
+    let bytes = serde_json::to_vec(&snapshot).map_err(Error::other)?;
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).await?;
+    }
+    let tmp_path = tmp_path(path);
+    let mut file = fs::File::create(&tmp_path).await?;
+    file.write_all(&bytes).await?;
+    file.sync_all().await?;
+    fs::rename(&tmp_path, path).await?;
+
+    info!(
+        "Wrote snapshot with {} primary and {} LRU entries to {}",
+        snapshot.primary.len(),
+        snapshot.lru.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Reads a snapshot, `Ok(None)` if there is none yet
+pub async fn load_snapshot(path: &Path) -> Result<Option<Snapshot>, Error> {
+    let bytes = match fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let snapshot: Snapshot =
+        serde_json::from_slice(&bytes).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    if snapshot.version != SNAPSHOT_VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "snapshot version {} is not supported, expected {}",
+                snapshot.version, SNAPSHOT_VERSION
+            ),
+        ));
+    }
+    Ok(Some(snapshot))
+}
+
+/// Seeds the caches and poller cursors from a snapshot. The poller then backfills
+/// the gap between the restored cursors and the chain tip.
+pub async fn restore_snapshot(app_state: &AppState, snapshot: Snapshot) {
+    info!(
+        "Restoring snapshot taken at {} with {} primary and {} LRU entries",
+        snapshot.taken_at,
+        snapshot.primary.len(),
+        snapshot.lru.len()
+    );
+
+    for (slot, commitment) in snapshot.primary_skipped {
+        app_state.cache.insert_skipped(slot, commitment).await;
+    }
+    for info in snapshot.primary {
+        app_state.cache.insert_info(info).await;
+    }
+    app_state.lru_cache.restore(snapshot.lru).await;
+    for (commitment, slot) in snapshot.cursors {
+        app_state.poller.set_cursor(commitment, slot);
+    }
+}
+
+/// Loads and restores the snapshot at `path` if there is a usable one
+pub async fn warm_start(app_state: &AppState, path: &Path) {
+    match load_snapshot(path).await {
+        Ok(Some(snapshot)) => restore_snapshot(app_state, snapshot).await,
+        Ok(None) => info!("No snapshot at {}, starting cold.", path.display()),
+        Err(e) => warn!(
+            "Ignoring unreadable snapshot at {}, starting cold: {}",
+            path.display(),
+            e
+        ),
+    }
+}
+
+/// Writes a snapshot every `interval`, and a final one once the shutdown signal arrives.
+/// Await the returned handle during shutdown so the final snapshot is complete.
+pub fn spawn_snapshot_task(
+    app_state: AppState,
+    path: PathBuf,
+    interval: Duration,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> JoinHandle<()> {
+    info!(
+        "Writing cache snapshots to {} every {:?}",
+        path.display(),
+        interval
+    );
+
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                biased;
+                _ = shutdown_rx.recv() => {
+                    info!("Shutdown signal received, writing final snapshot.");
+                    if let Err(e) = write_snapshot(&app_state, &path).await {
+                        warn!("Failed to write final snapshot to {}: {}", path.display(), e);
+                    }
+                    break;
+                }
+                _ = sleep(interval) => {
+                    if let Err(e) = write_snapshot(&app_state, &path).await {
+                        warn!("Failed to write snapshot to {}: {}", path.display(), e);
+                    }
+                }
+            }
+        }
+    })
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    PathBuf::from(tmp)
+}
@@ -0,0 +1,65 @@
+use crate::commitment::Commitment;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Block fields of a slot, as reported by `getBlock`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockMetadata {
+    pub blockhash: String,
+    pub parent_slot: u64,
+    pub block_height: Option<u64>,
+    /// Estimated production time, as a Unix timestamp in seconds
+    pub block_time: Option<i64>,
+}
+
+/// Everything the caches know about a slot that has a block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotInfo {
+    pub slot: u64,
+    pub commitment: Commitment,
+    /// When the slot was first seen at its current commitment, in Unix milliseconds
+    pub observed_at: u64,
+    #[serde(flatten)]
+    pub block: Option<BlockMetadata>,
+}
+
+impl SlotInfo {
+    pub fn new(slot: u64, commitment: Commitment) -> Self {
+        Self {
+            slot,
+            commitment,
+            observed_at: unix_millis(),
+            block: None,
+        }
+    }
+
+    pub fn with_block(mut self, block: BlockMetadata) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    /// Raises the commitment, returns false if it was already at least that high
+    pub fn upgrade(&mut self, commitment: Commitment) -> bool {
+        if commitment <= self.commitment {
+            return false;
+        }
+        self.commitment = commitment;
+        self.observed_at = unix_millis();
+        true
+    }
+
+    /// Merges a newer observation of the same slot into this one
+    pub fn merge(&mut self, other: SlotInfo) {
+        self.upgrade(other.commitment);
+        if other.block.is_some() {
+            self.block = other.block;
+        }
+    }
+}
+
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or_default()
+}
@@ -1,10 +1,20 @@
 use crate::{
-    cache::{LruCache, SlotCache},
+    cache::{DiskStore, LruCache, SharedCache, SlotCache},
     circuit_breaker::CircuitBreaker,
+    logging::LogLevels,
     metrics::Metrics,
     rpc::RpcApi,
+    service::{
+        backfill_service::{BackfillManager, BackfillSettings},
+        client_service::ClientRegistry,
+        confirmation_service::InFlightConfirmations,
+        fallback_batcher::FallbackBatcher,
+        health_service::HealthSettings,
+        reload_service::ConfigReloader,
+        slot_poller::PollerState,
+    },
 };
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 #[derive(Clone)]
 pub struct AppState {
@@ -13,4 +23,99 @@ pub struct AppState {
     pub lru_cache: Arc<LruCache>,
     pub metrics: Arc<dyn Metrics + Send + Sync>,
     pub circuit_breaker: Arc<CircuitBreaker>,
+    pub in_flight: Arc<InFlightConfirmations>,
+    pub batcher: Option<Arc<FallbackBatcher>>,
+    pub disk_store: Option<Arc<DiskStore>>,
+    pub shared_cache: Option<Arc<SharedCache>>,
+    pub poller: Arc<PollerState>,
+    pub backfill: Arc<BackfillManager>,
+    pub log_levels: Option<Arc<LogLevels>>,
+    // Readiness thresholds, replaced on configuration reload
+    pub health: Arc<RwLock<HealthSettings>>,
+    pub admin_token: Option<Arc<str>>,
+    pub config_reloader: Option<Arc<ConfigReloader>>,
+    pub client_registry: Option<Arc<ClientRegistry>>,
+}
+
+impl AppState {
+    pub fn new(
+        rpc_client: Arc<dyn RpcApi + Send + Sync>,
+        cache: Arc<SlotCache>,
+        lru_cache: Arc<LruCache>,
+        metrics: Arc<dyn Metrics + Send + Sync>,
+        circuit_breaker: Arc<CircuitBreaker>,
+    ) -> Self {
+        Self {
+            rpc_client,
+            cache,
+            lru_cache,
+            metrics,
+            circuit_breaker,
+            in_flight: Arc::new(InFlightConfirmations::new()),
+            batcher: None,
+            disk_store: None,
+            shared_cache: None,
+            poller: Arc::new(PollerState::new()),
+            backfill: Arc::new(BackfillManager::new(BackfillSettings::default())),
+            log_levels: None,
+            health: Arc::new(RwLock::new(HealthSettings::default())),
+            admin_token: None,
+            config_reloader: None,
+            client_registry: None,
+        }
+    }
+
+    /// Routes RPC fallbacks through the batcher instead of issuing one call per slot
+    pub fn with_batcher(mut self, batcher: Arc<FallbackBatcher>) -> Self {
+        self.batcher = Some(batcher);
+        self
+    }
+
+    /// Consults and feeds the on-disk historical tier between the LRU cache and RPC
+    pub fn with_disk_store(mut self, disk_store: Arc<DiskStore>) -> Self {
+        self.disk_store = Some(disk_store);
+        self
+    }
+
+    /// Shares slots resolved over RPC with the other replicas through a Redis-protocol server
+    pub fn with_shared_cache(mut self, shared_cache: Arc<SharedCache>) -> Self {
+        self.shared_cache = Some(shared_cache);
+        self
+    }
+
+    /// Replaces the default backfill job manager, e.g. to apply configured settings
+    pub fn with_backfill(mut self, backfill: Arc<BackfillManager>) -> Self {
+        self.backfill = backfill;
+        self
+    }
+
+    /// Replaces the default readiness thresholds
+    pub fn with_health_settings(mut self, health: HealthSettings) -> Self {
+        self.health = Arc::new(RwLock::new(health));
+        self
+    }
+
+    /// Enables the `/admin` API for requests carrying the token as a bearer token
+    pub fn with_admin_token(mut self, admin_token: Arc<str>) -> Self {
+        self.admin_token = Some(admin_token);
+        self
+    }
+
+    /// Requires a client key on the data endpoints and holds each key to its limits
+    pub fn with_client_registry(mut self, client_registry: Arc<ClientRegistry>) -> Self {
+        self.client_registry = Some(client_registry);
+        self
+    }
+
+    /// Allows re-reading the configuration and applying it to the running components
+    pub fn with_config_reloader(mut self, config_reloader: Arc<ConfigReloader>) -> Self {
+        self.config_reloader = Some(config_reloader);
+        self
+    }
+
+    /// Allows changing the log levels at runtime through the admin endpoint
+    pub fn with_log_levels(mut self, log_levels: Arc<LogLevels>) -> Self {
+        self.log_levels = Some(log_levels);
+        self
+    }
 }
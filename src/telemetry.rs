@@ -0,0 +1,94 @@
+use crate::config::Config;
+use axum::http::HeaderMap;
+use opentelemetry::{
+    Context,
+    propagation::{Extractor, TextMapPropagator},
+    trace::TracerProvider,
+};
+use opentelemetry_otlp::{ExporterBuildError, SpanExporter, WithExportConfig};
+use opentelemetry_sdk::{
+    Resource,
+    propagation::TraceContextPropagator,
+    trace::{Sampler, SdkTracer, SdkTracerProvider},
+};
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Path of the trace endpoint below the collector's base URL
+const OTLP_TRACES_PATH: &str = "/v1/traces";
+
+#[derive(Debug, Clone)]
+pub struct TracingSettings {
+    /// Base URL of the OTLP/HTTP collector, e.g. `http://localhost:4318`
+    pub endpoint: String,
+    pub service_name: String,
+    /// Share of new traces that are recorded, incoming `traceparent` decisions are kept
+    pub sample_ratio: f64,
+}
+
+impl TracingSettings {
+    /// Returns `None` when no `OTEL_EXPORTER_OTLP_ENDPOINT` is configured
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let endpoint = config.otlp_endpoint.clone()?;
+        Some(Self {
+            endpoint,
+            service_name: config.otel_service_name.clone(),
+            sample_ratio: config.trace_sample_ratio,
+        })
+    }
+}
+
+/// Builds a provider exporting spans in batches to the collector over OTLP/HTTP.
+/// Spans are exported from a background thread, `shutdown` flushes the last batch.
+pub fn init_tracer_provider(
+    settings: &TracingSettings,
+) -> Result<SdkTracerProvider, ExporterBuildError> {
+    let endpoint = format!(
+        "{}{}",
+        settings.endpoint.trim_end_matches('/'),
+        OTLP_TRACES_PATH
+    );
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+        settings.sample_ratio.clamp(0.0, 1.0),
+    )));
+    let resource = Resource::builder()
+        .with_service_name(settings.service_name.clone())
+        .build();
+
+    Ok(SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_sampler(sampler)
+        .with_resource(resource)
+        .build())
+}
+
+/// Layer turning `tracing` spans into OpenTelemetry spans of the provider
+pub fn otel_layer<S>(provider: &SdkTracerProvider) -> OpenTelemetryLayer<S, SdkTracer>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    tracing_opentelemetry::layer().with_tracer(provider.tracer(env!("CARGO_PKG_NAME")))
+}
+
+/// Remote parent carried by the W3C `traceparent` and `tracestate` headers.
+/// The context holds no valid span when the headers are missing or malformed.
+pub fn parent_context(headers: &HeaderMap) -> Context {
+    TraceContextPropagator::new().extract(&HeaderExtractor(headers))
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(|name| name.as_str()).collect()
+    }
+}
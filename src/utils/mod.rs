@@ -1,2 +1,5 @@
 pub mod error_utils;
+pub mod rate_budget;
+pub mod redact;
 pub mod retry;
+pub mod single_flight;
@@ -0,0 +1,34 @@
+use std::time::Duration;
+use tokio::{
+    sync::Mutex,
+    time::{Instant, sleep_until},
+};
+
+/// Spaces operations evenly so that at most `per_second` of them start every second.
+///
+/// Callers share one budget and wait their turn in [`RateBudget::acquire`], so background
+/// work holding the budget never issues more calls than it was granted.
+pub struct RateBudget {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateBudget {
+    pub fn new(per_second: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / per_second.max(1),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Waits until the caller may start one operation
+    pub async fn acquire(&self) {
+        let start_at = {
+            let mut next_slot = self.next_slot.lock().await;
+            let start_at = (*next_slot).max(Instant::now());
+            *next_slot = start_at + self.interval;
+            start_at
+        };
+        sleep_until(start_at).await;
+    }
+}
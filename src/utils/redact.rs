@@ -0,0 +1,103 @@
+use crate::config::Config;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_request::RpcError,
+};
+use std::{borrow::Cow, io};
+
+/// Replacement for every secret found in a log line or an error
+pub const REDACTED: &str = "<redacted>";
+
+/// Scrubs known secrets from text, such as the upstream API key in an endpoint URL
+#[derive(Clone, Default)]
+pub struct Redactor {
+    // Longest first, so a secret containing another one is replaced whole
+    secrets: Vec<String>,
+}
+
+impl std::fmt::Debug for Redactor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Redactor({} secrets)", self.secrets.len())
+    }
+}
+
+impl Redactor {
+    pub fn new(secrets: impl IntoIterator<Item = String>) -> Self {
+        let mut secrets: Vec<String> = secrets.into_iter().filter(|s| !s.is_empty()).collect();
+        secrets.sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        secrets.dedup();
+        Self { secrets }
+    }
+
+    /// The upstream API key, the fallback URLs, the admin token, the Redis URL and the client keys
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            [config.api_key.clone()]
+                .into_iter()
+                .chain(config.rpc_fallback_urls.iter().cloned())
+                .chain(config.admin_token.clone())
+                .chain(config.redis_url.clone())
+                .chain(config.client_api_keys.iter().map(|(_, key)| key.clone())),
+        )
+    }
+
+    pub fn redact<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for secret in &self.secrets {
+            if text.contains(secret.as_str()) {
+                text = Cow::Owned(text.replace(secret.as_str(), REDACTED));
+            }
+        }
+        text
+    }
+
+    fn contains_secret(&self, text: &str) -> bool {
+        self.secrets
+            .iter()
+            .any(|secret| text.contains(secret.as_str()))
+    }
+
+    /// Drops the URL from HTTP errors and scrubs the messages of the others,
+    /// keeping the error kind so retries still tell transient errors apart
+    pub fn redact_client_error(&self, error: ClientError) -> ClientError {
+        let kind = match error.kind {
+            ClientErrorKind::Reqwest(e) => ClientErrorKind::Reqwest(e.without_url()),
+            ClientErrorKind::Io(e) if self.contains_secret(&e.to_string()) => {
+                ClientErrorKind::Io(io::Error::new(e.kind(), self.redact(&e.to_string())))
+            }
+            ClientErrorKind::Middleware(e) if self.contains_secret(&format!("{:#}", e)) => {
+                ClientErrorKind::Custom(format!("Middleware: {}", self.redact(&format!("{:#}", e))))
+            }
+            ClientErrorKind::RpcError(e) => ClientErrorKind::RpcError(self.redact_rpc_error(e)),
+            ClientErrorKind::Custom(message) => {
+                ClientErrorKind::Custom(self.redact(&message).into_owned())
+            }
+            kind => kind,
+        };
+        ClientError {
+            request: error.request,
+            kind,
+        }
+    }
+
+    fn redact_rpc_error(&self, error: RpcError) -> RpcError {
+        match error {
+            RpcError::RpcRequestError(message) => {
+                RpcError::RpcRequestError(self.redact(&message).into_owned())
+            }
+            RpcError::RpcResponseError {
+                code,
+                message,
+                data,
+            } => RpcError::RpcResponseError {
+                code,
+                message: self.redact(&message).into_owned(),
+                data,
+            },
+            RpcError::ParseError(message) => {
+                RpcError::ParseError(self.redact(&message).into_owned())
+            }
+            RpcError::ForUser(message) => RpcError::ForUser(self.redact(&message).into_owned()),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::utils::error_utils::IsTransient;
 use std::{future::Future, time::Duration};
 use tokio::time::sleep;
-use tracing::{error, warn};
+use tracing::{Instrument, error, info_span, warn};
 
 pub async fn with_retry<F, Fut, T, E>(
     operation_name: &str,
@@ -15,7 +15,12 @@ where
 {
     let mut attempts = 0;
     loop {
-        match operation().await {
+        let span = info_span!(
+            "attempt",
+            operation = operation_name,
+            retry.attempt = attempts + 1
+        );
+        match operation().instrument(span).await {
             Ok(value) => return Ok(value),
             Err(e) => {
                 attempts += 1;
@@ -47,7 +52,12 @@ where
 {
     let mut attempts = 0;
     loop {
-        match operation().await {
+        let span = info_span!(
+            "attempt",
+            operation = operation_name,
+            retry.attempt = attempts + 1
+        );
+        match operation().instrument(span).await {
             Ok(value) => return Ok(value),
             Err(e) => {
                 if !e.is_transient() {
@@ -0,0 +1,90 @@
+use std::{collections::HashMap, future::Future, hash::Hash, sync::Mutex};
+use tokio::sync::watch;
+
+/// Deduplicates concurrent operations sharing the same key.
+///
+/// The first caller for a key runs the operation, every caller arriving while it is
+/// in flight waits for and receives a clone of its result, errors included.
+/// If the running caller is cancelled, one of the waiters takes over.
+pub struct SingleFlight<K, V> {
+    in_flight: Mutex<HashMap<K, watch::Receiver<Option<V>>>>,
+}
+
+enum Role<V> {
+    Leader(watch::Sender<Option<V>>),
+    Follower(watch::Receiver<Option<V>>),
+}
+
+// Removes the in-flight entry when the leader completes or is dropped mid-flight
+struct FlightGuard<'a, K: Eq + Hash, V> {
+    in_flight: &'a Mutex<HashMap<K, watch::Receiver<Option<V>>>>,
+    key: Option<K>,
+}
+
+impl<K: Eq + Hash, V> Drop for FlightGuard<'_, K, V> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key.take() {
+            self.in_flight.lock().unwrap().remove(&key);
+        }
+    }
+}
+
+impl<K, V> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<K, V> SingleFlight<K, V>
+where
+    K: Eq + Hash + Clone,
+    V: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn run<F, Fut>(&self, key: K, operation: F) -> V
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = V>,
+    {
+        let mut operation = Some(operation);
+
+        loop {
+            let role = {
+                let mut in_flight = self.in_flight.lock().unwrap();
+                match in_flight.get(&key) {
+                    Some(receiver) => Role::Follower(receiver.clone()),
+                    None => {
+                        let (sender, receiver) = watch::channel(None);
+                        in_flight.insert(key.clone(), receiver);
+                        Role::Leader(sender)
+                    }
+                }
+            };
+
+            match role {
+                Role::Leader(sender) => {
+                    let guard = FlightGuard {
+                        in_flight: &self.in_flight,
+                        key: Some(key),
+                    };
+                    // A caller only ever leads once, since leading always returns
+                    let value = operation.take().expect("operation already consumed")().await;
+                    drop(guard);
+                    let _ = sender.send(Some(value.clone()));
+                    return value;
+                }
+                Role::Follower(mut receiver) => {
+                    if let Ok(value) = receiver.wait_for(Option::is_some).await {
+                        return value.clone().expect("value checked by wait_for");
+                    }
+                    // The leader was cancelled before producing a value, compete again
+                }
+            }
+        }
+    }
+}
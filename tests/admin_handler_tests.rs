@@ -0,0 +1,296 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+};
+use mockall::mock;
+use serde_json::{Value, json};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::{CircuitBreaker, CircuitState},
+    metrics::LoggingMetrics,
+    routes::create_router,
+    rpc::RpcApi,
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use tower::ServiceExt;
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+const TOKEN: &str = "test-admin-token";
+
+fn create_test_app_state() -> AppState {
+    AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(LoggingMetrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+    .with_admin_token(Arc::from(TOKEN))
+}
+
+async fn send(
+    app: &Router,
+    method: Method,
+    uri: &str,
+    token: Option<&str>,
+    body: Option<Value>,
+) -> (StatusCode, Value) {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (
+        status,
+        serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+    )
+}
+
+#[tokio::test]
+async fn test_admin_api_requires_the_token() {
+    let app = create_router(create_test_app_state());
+
+    let (status, _) = send(&app, Method::GET, "/admin/backfill", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send(&app, Method::GET, "/admin/backfill", Some("wrong"), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, body) = send(&app, Method::GET, "/admin/backfill", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!([]));
+
+    // Public routes stay open
+    let (status, _) = send(&app, Method::GET, "/cache/lru", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_backfill_routes_require_the_token() {
+    let app = create_router(create_test_app_state());
+    let body = json!({"start_slot": 10, "end_slot": 20});
+
+    let (status, _) = send(&app, Method::POST, "/admin/backfill", None, Some(body)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, Method::GET, "/admin/backfill/1", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = send(&app, Method::DELETE, "/admin/backfill/1", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = send(&app, Method::GET, "/admin/backfill/1", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_log_level_route_requires_the_token() {
+    let app = create_router(create_test_app_state());
+
+    let (status, _) = send(&app, Method::GET, "/admin/log-level", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let body = json!({"filter": "debug"});
+    let (status, _) = send(&app, Method::PUT, "/admin/log-level", None, Some(body)).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // Past the token check, the test app has no reloadable logging
+    let (status, _) = send(&app, Method::GET, "/admin/log-level", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_admin_api_is_disabled_without_token() {
+    let app_state = AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(LoggingMetrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let app = create_router(app_state);
+
+    let (status, _) = send(&app, Method::POST, "/admin/poller/pause", Some(""), None).await;
+
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_flush_and_resize_cache_tiers() {
+    let app_state = create_test_app_state();
+    for slot in 1..=5 {
+        app_state.cache.insert(slot).await;
+        app_state.lru_cache.put(slot).await;
+    }
+    let app = create_router(app_state.clone());
+
+    let (status, body) = send(
+        &app,
+        Method::DELETE,
+        "/admin/cache/primary",
+        Some(TOKEN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"tier": "primary", "flushed": 5}));
+    assert!(app_state.cache.is_empty().await);
+
+    let (status, body) = send(
+        &app,
+        Method::PUT,
+        "/admin/cache/lru/capacity",
+        Some(TOKEN),
+        Some(json!({"capacity": 3})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"tier": "lru", "capacity": 3, "evicted": 2}));
+    assert_eq!(app_state.lru_cache.len().await, 3);
+
+    let (status, _) = send(
+        &app,
+        Method::PUT,
+        "/admin/cache/primary/capacity",
+        Some(TOKEN),
+        Some(json!({"capacity": 0})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send(&app, Method::DELETE, "/admin/cache/disk", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_evict_and_pin_slots() {
+    let app_state = create_test_app_state();
+    app_state.cache.insert(42).await;
+    app_state.cache.insert(43).await;
+    let app = create_router(app_state.clone());
+
+    let (status, body) = send(&app, Method::PUT, "/admin/slots/42/pin", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"slot": 42, "pinned": true}));
+    assert!(
+        app_state.lru_cache.get(&42).await,
+        "Pinning copies the slot"
+    );
+
+    let (_, body) = send(&app, Method::GET, "/admin/slots/pins", Some(TOKEN), None).await;
+    assert_eq!(body, json!([42]));
+
+    let (status, _) = send(&app, Method::PUT, "/admin/slots/7/pin", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        "/admin/slots/42/pin",
+        Some(TOKEN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send(
+        &app,
+        Method::DELETE,
+        "/admin/slots/42/pin",
+        Some(TOKEN),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, Method::DELETE, "/admin/slots/43", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(!app_state.cache.contains(&43).await);
+    let (status, _) = send(&app, Method::DELETE, "/admin/slots/43", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_force_circuit_breaker() {
+    let app_state = create_test_app_state();
+    let app = create_router(app_state.clone());
+
+    let (status, body) = send(
+        &app,
+        Method::PUT,
+        "/admin/circuit-breaker",
+        Some(TOKEN),
+        Some(json!({"state": "open"})),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"state": "open", "forced": true}));
+    assert_eq!(app_state.circuit_breaker.state().await, CircuitState::Open);
+
+    let (_, body) = send(
+        &app,
+        Method::PUT,
+        "/admin/circuit-breaker",
+        Some(TOKEN),
+        Some(json!({"state": "closed"})),
+    )
+    .await;
+    assert_eq!(body, json!({"state": "closed", "forced": false}));
+
+    let (_, body) = send(
+        &app,
+        Method::GET,
+        "/admin/circuit-breaker",
+        Some(TOKEN),
+        None,
+    )
+    .await;
+    assert_eq!(body, json!({"state": "closed", "forced": false}));
+}
+
+#[tokio::test]
+async fn test_pause_and_resume_poller() {
+    let app_state = create_test_app_state();
+    let app = create_router(app_state.clone());
+
+    let (status, body) = send(&app, Method::POST, "/admin/poller/pause", Some(TOKEN), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"paused": true}));
+    assert!(app_state.poller.is_paused());
+
+    let (_, health) = send(&app, Method::GET, "/health/live", None, None).await;
+    assert_eq!(health["poller_paused"], json!(true));
+
+    let (_, body) = send(
+        &app,
+        Method::POST,
+        "/admin/poller/resume",
+        Some(TOKEN),
+        None,
+    )
+    .await;
+    assert_eq!(body, json!({"paused": false}));
+    assert!(!app_state.poller.is_paused());
+}
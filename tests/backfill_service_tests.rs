@@ -0,0 +1,430 @@
+use mockall::{mock, predicate::*};
+use solana_caching_service::{
+    cache::{DiskStore, LruCache, SharedCache, SharedCacheSettings, SlotCache, StoredSlot},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    metrics::Metrics,
+    rpc::RpcApi,
+    service::backfill_service::{
+        BackfillJob, BackfillManager, BackfillRequest, BackfillSettings, JobStatus,
+        cancel_backfill, get_backfill, resume_backfills, start_backfill,
+    },
+    state::AppState,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_sdk::epoch_schedule::EpochSchedule;
+use std::{future::Future, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_slot_with_commitment<'a>(
+            &'a self,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_epoch_schedule<'a>(
+            &'a self,
+        ) -> Pin<Box<dyn Future<Output = Result<EpochSchedule, ClientError>> + Send + 'a>>;
+    }
+}
+
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn test_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("backfill-tests-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn settings() -> BackfillSettings {
+    BackfillSettings {
+        chunk_size: 10,
+        max_rpc_per_sec: 1000,
+        retry_backoff: Duration::from_millis(10),
+        checkpoint_path: None,
+    }
+}
+
+fn create_app_state(
+    mock_rpc: MockRpcApi,
+    circuit_breaker: CircuitBreaker,
+    settings: BackfillSettings,
+) -> AppState {
+    AppState::new(
+        Arc::new(mock_rpc),
+        Arc::new(SlotCache::new(50)),
+        Arc::new(LruCache::new(100)),
+        Arc::new(MockMetrics::new()),
+        Arc::new(circuit_breaker),
+    )
+    .with_backfill(Arc::new(BackfillManager::new(settings)))
+}
+
+fn slot_request(start_slot: u64, end_slot: u64) -> BackfillRequest {
+    BackfillRequest {
+        start_slot: Some(start_slot),
+        end_slot: Some(end_slot),
+        ..Default::default()
+    }
+}
+
+fn epoch_request(start_epoch: u64, end_epoch: u64) -> BackfillRequest {
+    BackfillRequest {
+        start_epoch: Some(start_epoch),
+        end_epoch: Some(end_epoch),
+        ..Default::default()
+    }
+}
+
+async fn wait_for_status(app_state: &AppState, id: u64, status: JobStatus) -> BackfillJob {
+    for _ in 0..200 {
+        let job = get_backfill(app_state, id).await.unwrap();
+        if job.status == status {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("job {} never reached {:?}", id, status);
+}
+
+#[test]
+fn test_slot_range_from_slots_and_epochs() {
+    let mainnet = Some(&EpochSchedule::without_warmup());
+    assert_eq!(slot_request(10, 20).slot_range(mainnet), Ok((10, 20)));
+    assert_eq!(
+        epoch_request(600, 610).slot_range(mainnet),
+        Ok((600 * 432_000, 611 * 432_000 - 1))
+    );
+    assert!(slot_request(20, 10).slot_range(mainnet).is_err());
+    assert!(
+        BackfillRequest {
+            start_slot: Some(1),
+            end_epoch: Some(2),
+            ..Default::default()
+        }
+        .slot_range(mainnet)
+        .is_err()
+    );
+    assert!(epoch_request(0, u64::MAX).slot_range(mainnet).is_err());
+    assert!(epoch_request(600, 610).slot_range(None).is_err());
+}
+
+#[tokio::test]
+async fn test_epoch_ranges_follow_the_cluster_epoch_schedule() {
+    let mut mock_rpc = MockRpcApi::new();
+    // A test cluster with short epochs that start with warm-up epochs
+    mock_rpc
+        .expect_get_epoch_schedule()
+        .times(1)
+        .returning(|| Box::pin(async { Ok(EpochSchedule::custom(8192, 8192, true)) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    let app_state = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(3, Duration::from_secs(10)),
+        settings(),
+    );
+
+    // Warm-up epochs 0 to 7 hold 32, 64, ... 4096 slots, the following ones 8192 slots each
+    let job = start_backfill(&app_state, &epoch_request(3, 3))
+        .await
+        .unwrap();
+    assert_eq!((job.start_slot, job.end_slot), (224, 479));
+
+    let job = start_backfill(&app_state, &epoch_request(8, 9))
+        .await
+        .unwrap();
+    assert_eq!((job.start_slot, job.end_slot), (8160, 24543));
+}
+
+#[tokio::test]
+async fn test_job_loads_range_into_lru_in_chunks() {
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(100), eq(Some(109)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![100, 101, 105]) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(110), eq(Some(114)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![110]) }));
+    let app_state = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(3, Duration::from_secs(10)),
+        settings(),
+    );
+
+    let job = start_backfill(
+        &app_state,
+        &BackfillRequest {
+            commitment: Some(Commitment::Finalized),
+            ..slot_request(100, 114)
+        },
+    )
+    .await
+    .unwrap();
+    let job = wait_for_status(&app_state, job.id, JobStatus::Completed).await;
+
+    assert_eq!(job.next_slot, 115);
+    assert_eq!(job.confirmed_slots, 4);
+    assert_eq!(job.skipped_slots, 11);
+    assert_eq!(
+        app_state.lru_cache.get_commitment(&105).await,
+        Some(Commitment::Finalized)
+    );
+    assert!(
+        app_state
+            .lru_cache
+            .is_skipped_at(&102, Commitment::Finalized)
+            .await
+    );
+}
+
+#[tokio::test]
+async fn test_job_stores_old_slots_on_disk_next_to_tip_slots() {
+    let disk_path = test_path("old_next_to_tip.log");
+    let disk_store = Arc::new(DiskStore::open(&disk_path, 1_000_000).unwrap());
+    // Written by the poller before the job starts
+    disk_store
+        .record_confirmed(350_000_000, Commitment::Confirmed)
+        .await;
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(350_000_000) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(200), eq(Some(201)), eq(Commitment::Finalized))
+        .returning(|_, _, _| Box::pin(async { Ok(vec![200]) }));
+    let app_state = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(3, Duration::from_secs(10)),
+        settings(),
+    )
+    .with_disk_store(disk_store.clone());
+
+    let job = start_backfill(&app_state, &slot_request(200, 201))
+        .await
+        .unwrap();
+    assert_eq!(job.commitment, Commitment::Finalized);
+    wait_for_status(&app_state, job.id, JobStatus::Completed).await;
+
+    assert_eq!(
+        disk_store.get(&200).await,
+        Some(StoredSlot::Confirmed(Commitment::Finalized))
+    );
+    assert_eq!(
+        disk_store.get(&201).await,
+        Some(StoredSlot::Skipped(Commitment::Finalized))
+    );
+    assert!(
+        disk_store
+            .is_confirmed_at(&350_000_000, Commitment::Confirmed)
+            .await
+    );
+    assert!(app_state.lru_cache.get_all_slots().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_job_falls_back_to_lru_when_shared_cache_drops_the_writes() {
+    // Bind and drop to get a port nothing listens on
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap().to_string();
+    drop(listener);
+    let shared_cache = Arc::new(SharedCache::new(SharedCacheSettings {
+        address,
+        password: None,
+        database: None,
+        key_prefix: "test".to_string(),
+        ttl: Duration::from_secs(30),
+        timeout: Duration::from_millis(200),
+        retry_interval: Duration::from_secs(60),
+    }));
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![200]) }));
+    let app_state = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(3, Duration::from_secs(10)),
+        settings(),
+    )
+    .with_shared_cache(shared_cache);
+
+    let job = start_backfill(&app_state, &slot_request(200, 201))
+        .await
+        .unwrap();
+    wait_for_status(&app_state, job.id, JobStatus::Completed).await;
+
+    assert_eq!(
+        app_state.lru_cache.get_commitment(&200).await,
+        Some(Commitment::Finalized)
+    );
+    assert!(
+        app_state
+            .lru_cache
+            .is_skipped_at(&201, Commitment::Finalized)
+            .await
+    );
+}
+
+#[tokio::test]
+async fn test_cancelled_job_stops_and_resumes_from_checkpoint() {
+    let checkpoint_path = test_path("resume.json");
+    let backfill_settings = BackfillSettings {
+        chunk_size: 10,
+        // One call per 50ms leaves time to cancel after the first chunk
+        max_rpc_per_sec: 20,
+        checkpoint_path: Some(checkpoint_path.clone()),
+        ..settings()
+    };
+
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    let app_state = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(3, Duration::from_secs(10)),
+        backfill_settings.clone(),
+    );
+    let job = start_backfill(&app_state, &slot_request(0, 99))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(120)).await;
+    let cancelled = cancel_backfill(&app_state, job.id).await.unwrap();
+    assert_eq!(cancelled.status, JobStatus::Cancelled);
+    assert!(cancelled.next_slot > 0 && cancelled.next_slot < 100);
+    assert!(cancel_backfill(&app_state, 42).await.is_none());
+
+    // Mark the checkpointed job as running again, as if the process stopped mid-job
+    let checkpoint = std::fs::read_to_string(&checkpoint_path).unwrap();
+    std::fs::write(
+        &checkpoint_path,
+        checkpoint.replace("\"cancelled\"", "\"running\""),
+    )
+    .unwrap();
+
+    let resumed_from = cancelled.next_slot;
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    // No chunk before the checkpoint is fetched again
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .withf(move |start, _, _| *start >= resumed_from)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    let restarted = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(3, Duration::from_secs(10)),
+        BackfillSettings {
+            max_rpc_per_sec: 1000,
+            ..backfill_settings
+        },
+    );
+
+    assert_eq!(resume_backfills(&restarted).await, 1);
+    let job = wait_for_status(&restarted, job.id, JobStatus::Completed).await;
+    assert_eq!(job.next_slot, 100);
+    assert_eq!(job.skipped_slots, 100);
+}
+
+#[tokio::test]
+async fn test_job_waits_while_circuit_is_open() {
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    let circuit_breaker = CircuitBreaker::new(1, Duration::from_millis(100));
+    let _: Result<(), _> = circuit_breaker
+        .execute(|| async {
+            Err(ClientError::from(ClientErrorKind::Custom(
+                "live traffic failed".to_string(),
+            )))
+        })
+        .await;
+    let app_state = create_app_state(mock_rpc, circuit_breaker, settings());
+
+    let job = start_backfill(&app_state, &slot_request(0, 9))
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_eq!(get_backfill(&app_state, job.id).await.unwrap().next_slot, 0);
+
+    let job = wait_for_status(&app_state, job.id, JobStatus::Completed).await;
+    assert_eq!(job.next_slot, 10);
+}
+
+#[tokio::test]
+async fn test_failed_calls_are_retried_without_opening_the_circuit() {
+    let mut mock_rpc = MockRpcApi::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    let mut failures = 3;
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(move |_, _, _| {
+            if failures > 0 {
+                failures -= 1;
+                Box::pin(async {
+                    Err(ClientError::from(ClientErrorKind::Custom(
+                        "unavailable".to_string(),
+                    )))
+                })
+            } else {
+                Box::pin(async { Ok(vec![5]) })
+            }
+        });
+    let app_state = create_app_state(
+        mock_rpc,
+        CircuitBreaker::new(1, Duration::from_secs(10)),
+        settings(),
+    );
+
+    let job = start_backfill(&app_state, &slot_request(0, 9))
+        .await
+        .unwrap();
+    let job = wait_for_status(&app_state, job.id, JobStatus::Completed).await;
+
+    assert_eq!(job.failed_calls, 3);
+    assert_eq!(job.confirmed_slots, 1);
+    assert!(!app_state.circuit_breaker.is_open().await);
+}
@@ -3,7 +3,10 @@ use mockall::mock;
 use solana_caching_service::{
     cache::{LruCache, SlotCache},
     circuit_breaker::CircuitBreaker,
-    handler::cache_handler::{get_latest_slots_handler, get_lru_slots_handler},
+    commitment::Commitment,
+    handler::cache_handler::{
+        get_latest_slots_handler, get_lru_slots_handler, get_poller_status_handler,
+    },
     metrics::Metrics,
     rpc::RpcApi,
     state::AppState,
@@ -32,13 +35,13 @@ mock! {
 }
 
 fn create_test_app_state() -> AppState {
-    AppState {
-        cache: Arc::new(SlotCache::new(10)),
-        lru_cache: Arc::new(LruCache::new(10)),
-        rpc_client: Arc::new(MockRpcApi::new()),
-        metrics: Arc::new(MockMetrics::new()),
-        circuit_breaker: Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
-    }
+    AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(MockMetrics::new()),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
 }
 
 #[tokio::test]
@@ -78,3 +81,24 @@ async fn test_get_lru_slots_handler() {
     assert_eq!(status, StatusCode::OK);
     assert_eq!(body_str, "[201,200]");
 }
+
+#[tokio::test]
+async fn test_get_poller_status_handler() {
+    let app_state = create_test_app_state();
+    app_state.poller.set_cursor(Commitment::Confirmed, 300);
+
+    let response = get_poller_status_handler(State(app_state))
+        .await
+        .into_response();
+    let status = response.status();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body_str = String::from_utf8(body_bytes.to_vec()).unwrap();
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        body_str,
+        r#"[{"commitment":"confirmed","cursor":300,"catch_up":null}]"#
+    );
+}
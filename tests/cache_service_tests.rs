@@ -36,13 +36,13 @@ async fn test_get_all_latest_slots_service() {
     slot_cache.insert(100).await;
     slot_cache.insert(101).await;
 
-    let app_state = AppState {
-        cache: slot_cache,
-        lru_cache: Arc::new(LruCache::new(10)),
-        rpc_client: Arc::new(MockRpcApi::new()),
-        metrics: Arc::new(MockMetrics::new()),
-        circuit_breaker: Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
-    };
+    let app_state = AppState::new(
+        Arc::new(MockRpcApi::new()),
+        slot_cache,
+        Arc::new(LruCache::new(10)),
+        Arc::new(MockMetrics::new()),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
 
     let result = get_all_latest_slots(&app_state).await;
 
@@ -55,13 +55,13 @@ async fn test_get_all_lru_slots_service() {
     lru_cache.put(200).await;
     lru_cache.put(201).await;
 
-    let app_state = AppState {
+    let app_state = AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
         lru_cache,
-        cache: Arc::new(SlotCache::new(10)),
-        rpc_client: Arc::new(MockRpcApi::new()),
-        metrics: Arc::new(MockMetrics::new()),
-        circuit_breaker: Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
-    };
+        Arc::new(MockMetrics::new()),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
 
     let result = get_all_lru_slots(&app_state).await;
 
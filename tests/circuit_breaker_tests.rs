@@ -103,3 +103,52 @@ async fn test_circuit_transitions_to_half_open_and_back_to_open() {
         Err(CircuitBreakerError::CircuitOpen)
     ));
 }
+
+#[tokio::test]
+async fn test_is_open_reflects_open_circuit() {
+    let circuit_breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+    assert!(!circuit_breaker.is_open().await);
+
+    let _: Result<(), _> = circuit_breaker
+        .execute(|| async { Err(TestError("Failed".into())) })
+        .await;
+
+    assert!(circuit_breaker.is_open().await);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert!(!circuit_breaker.is_open().await);
+}
+
+#[tokio::test]
+async fn test_forced_open_circuit_stays_open_until_closed() {
+    let circuit_breaker = CircuitBreaker::new(3, Duration::from_millis(1));
+    let operation = || async { Ok::<_, TestError>("Success") };
+
+    circuit_breaker.force_open().await;
+    tokio::time::sleep(Duration::from_millis(5)).await;
+
+    assert!(circuit_breaker.is_forced_open().await);
+    assert!(matches!(
+        circuit_breaker.execute(operation).await,
+        Err(CircuitBreakerError::CircuitOpen)
+    ));
+
+    circuit_breaker.force_closed().await;
+    assert!(!circuit_breaker.is_forced_open().await);
+    assert!(circuit_breaker.execute(operation).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_reconfigured_threshold_applies_to_counted_failures() {
+    let circuit_breaker = CircuitBreaker::new(5, Duration::from_secs(10));
+    let failing_operation = || async { Err::<(), _>(TestError("Failed".into())) };
+
+    let _ = circuit_breaker.execute(failing_operation).await;
+    let _ = circuit_breaker.execute(failing_operation).await;
+    assert!(!circuit_breaker.is_open().await);
+
+    circuit_breaker.reconfigure(3, Duration::from_secs(20));
+    let _ = circuit_breaker.execute(failing_operation).await;
+
+    assert!(circuit_breaker.is_open().await);
+    assert_eq!(circuit_breaker.open_duration(), Duration::from_secs(20));
+}
@@ -0,0 +1,305 @@
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode, header},
+};
+use mockall::mock;
+use serde_json::Value;
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    metrics::LoggingMetrics,
+    routes::create_router,
+    rpc::RpcApi,
+    service::client_service::{ClientLimits, ClientRegistry, ClientSettings},
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use tower::ServiceExt;
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+const KEY: &str = "partner-key";
+const ADMIN_TOKEN: &str = "test-admin-token";
+
+fn create_test_app(rpc_client: MockRpcApi, limits: ClientLimits) -> Router {
+    create_test_app_with_keys(rpc_client, limits, &[("partner", KEY)])
+}
+
+fn create_test_app_with_keys(
+    rpc_client: MockRpcApi,
+    limits: ClientLimits,
+    keys: &[(&str, &str)],
+) -> Router {
+    let registry = ClientRegistry::load(ClientSettings {
+        keys: keys
+            .iter()
+            .map(|(name, key)| (name.to_string(), key.to_string()))
+            .collect(),
+        keys_file: None,
+        reload_interval: Duration::from_secs(10),
+        default_limits: limits,
+        quota_window: Duration::from_secs(60),
+    })
+    .unwrap();
+    let app_state = AppState::new(
+        Arc::new(rpc_client),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(LoggingMetrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+    .with_admin_token(Arc::from(ADMIN_TOKEN))
+    .with_client_registry(Arc::new(registry));
+    create_router(app_state)
+}
+
+async fn get(app: &Router, uri: &str, headers: &[(&str, &str)]) -> (StatusCode, Option<String>) {
+    let mut request = Request::builder().uri(uri);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let retry_after = response
+        .headers()
+        .get(header::RETRY_AFTER)
+        .map(|value| value.to_str().unwrap().to_string());
+    (response.status(), retry_after)
+}
+
+async fn post_slots(app: &Router, content_type: &str, body: &str) -> (StatusCode, String) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/slots/confirmations")
+        .header("x-api-key", KEY)
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body.to_string()))
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(bytes.to_vec()).unwrap())
+}
+
+async fn admin(app: &Router, method: &str, uri: &str) -> Value {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", ADMIN_TOKEN))
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_data_endpoints_require_a_client_key() {
+    let app = create_test_app(MockRpcApi::new(), ClientLimits::default());
+
+    assert_eq!(
+        get(&app, "/cache/lru", &[]).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        get(&app, "/cache/lru", &[("x-api-key", "wrong")]).await.0,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        get(&app, "/cache/lru", &[("x-api-key", KEY)]).await.0,
+        StatusCode::OK
+    );
+    let bearer = format!("Bearer {}", KEY);
+    assert_eq!(
+        get(&app, "/cache/lru", &[("authorization", &bearer)])
+            .await
+            .0,
+        StatusCode::OK
+    );
+
+    // Probes stay open
+    assert_eq!(get(&app, "/", &[]).await.0, StatusCode::OK);
+    assert_eq!(get(&app, "/health/live", &[]).await.0, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_answers_429_with_retry_after() {
+    let limits = ClientLimits {
+        rate_limit_per_sec: 1,
+        ..ClientLimits::default()
+    };
+    let app = create_test_app(MockRpcApi::new(), limits);
+
+    assert_eq!(
+        get(&app, "/cache/lru", &[("x-api-key", KEY)]).await,
+        (StatusCode::OK, None)
+    );
+    assert_eq!(
+        get(&app, "/cache/lru", &[("x-api-key", KEY)]).await,
+        (StatusCode::TOO_MANY_REQUESTS, Some("1".to_string()))
+    );
+
+    let usage = admin(&app, "GET", "/admin/clients/partner").await;
+    assert_eq!(usage["requests"], 1);
+    assert_eq!(usage["rate_limited"], 1);
+}
+
+#[tokio::test]
+async fn test_request_quota_answers_429_until_reset() {
+    let limits = ClientLimits {
+        request_quota: 2,
+        ..ClientLimits::default()
+    };
+    let app = create_test_app(MockRpcApi::new(), limits);
+
+    for _ in 0..2 {
+        assert_eq!(
+            get(&app, "/cache/lru", &[("x-api-key", KEY)]).await.0,
+            StatusCode::OK
+        );
+    }
+    let (status, retry_after) = get(&app, "/cache/lru", &[("x-api-key", KEY)]).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = retry_after.unwrap().parse().unwrap();
+    assert!((1..=60).contains(&retry_after));
+
+    let usage = admin(&app, "DELETE", "/admin/clients/partner/usage").await;
+    assert_eq!(usage["requests"], 0);
+    assert_eq!(usage["total_requests"], 2);
+    assert_eq!(usage["quota_exceeded"], 1);
+    assert_eq!(
+        get(&app, "/cache/lru", &[("x-api-key", KEY)]).await.0,
+        StatusCode::OK
+    );
+}
+
+#[tokio::test]
+async fn test_rpc_fallback_quota_only_counts_cache_misses() {
+    let mut rpc_client = MockRpcApi::new();
+    rpc_client
+        .expect_get_blocks()
+        .times(1)
+        .returning(|start, _| Box::pin(async move { Ok(vec![start]) }));
+    let limits = ClientLimits {
+        rpc_fallback_quota: 1,
+        ..ClientLimits::default()
+    };
+    let app = create_test_app(rpc_client, limits);
+
+    // Resolved over RPC, then answered from the cache
+    for _ in 0..2 {
+        assert_eq!(
+            get(&app, "/isSlotConfirmed/100", &[("x-api-key", KEY)])
+                .await
+                .0,
+            StatusCode::OK
+        );
+    }
+    let (status, retry_after) = get(&app, "/isSlotConfirmed/200", &[("x-api-key", KEY)]).await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+    assert!(retry_after.is_some());
+
+    let usage = admin(&app, "GET", "/admin/clients").await;
+    assert_eq!(usage[0]["name"], "partner");
+    assert_eq!(usage[0]["requests"], 3);
+    assert_eq!(usage[0]["rpc_fallbacks"], 1);
+    assert_eq!(usage[0]["limits"]["rpc_fallback_quota"], 1);
+}
+
+#[tokio::test]
+async fn test_batches_keep_cached_answers_once_rpc_fallback_quota_is_used_up() {
+    let mut rpc_client = MockRpcApi::new();
+    rpc_client
+        .expect_get_blocks()
+        .times(1)
+        .returning(|start, _| Box::pin(async move { Ok(vec![start]) }));
+    let limits = ClientLimits {
+        rpc_fallback_quota: 1,
+        ..ClientLimits::default()
+    };
+    let app = create_test_app(rpc_client, limits);
+    assert_eq!(
+        get(&app, "/isSlotConfirmed/100", &[("x-api-key", KEY)])
+            .await
+            .0,
+        StatusCode::OK
+    );
+
+    let (status, body) = post_slots(&app, "application/json", r#"{"slots":[100,200]}"#).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_json::from_str::<Value>(&body).unwrap(),
+        serde_json::json!({"results": [
+            {"slot": 100, "status": "confirmed"},
+            {"slot": 200, "status": "quota_exceeded"},
+        ]})
+    );
+
+    let (status, body) = post_slots(&app, "application/x-ndjson", "100\n200\n").await;
+    assert_eq!(status, StatusCode::OK);
+    let lines: Vec<Value> = body
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            serde_json::json!({"slot": 100, "status": "confirmed"}),
+            serde_json::json!({"slot": 200, "status": "quota_exceeded"}),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_only_the_request_calling_upstream_is_charged() {
+    let mut rpc_client = MockRpcApi::new();
+    rpc_client
+        .expect_get_blocks()
+        .times(1)
+        .returning(|start, _| {
+            Box::pin(async move {
+                // Long enough for the second request to join the call in flight
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok(vec![start])
+            })
+        });
+    let app = create_test_app_with_keys(
+        rpc_client,
+        ClientLimits::default(),
+        &[("partner", KEY), ("other", "other-key")],
+    );
+
+    let (first, second) = tokio::join!(
+        get(&app, "/isSlotConfirmed/300", &[("x-api-key", KEY)]),
+        get(&app, "/isSlotConfirmed/300", &[("x-api-key", "other-key")]),
+    );
+    assert_eq!(first.0, StatusCode::OK);
+    assert_eq!(second.0, StatusCode::OK);
+
+    let partner = admin(&app, "GET", "/admin/clients/partner").await;
+    let other = admin(&app, "GET", "/admin/clients/other").await;
+    assert_eq!(
+        partner["rpc_fallbacks"].as_u64().unwrap() + other["rpc_fallbacks"].as_u64().unwrap(),
+        1
+    );
+}
@@ -0,0 +1,119 @@
+use solana_caching_service::service::client_service::{
+    Admission, ClientLimits, ClientRegistry, ClientSettings,
+};
+use std::{
+    fs,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+fn create_keys_file(content: &str, file_path: &str, modified: SystemTime) {
+    fs::write(file_path, content).expect("Failed to write keys file");
+    let file = fs::File::options().write(true).open(file_path).unwrap();
+    file.set_modified(modified).unwrap();
+}
+
+fn settings(keys_file: Option<&str>) -> ClientSettings {
+    ClientSettings {
+        keys: vec![("inline".to_string(), "inline-key".to_string())],
+        keys_file: keys_file.map(Into::into),
+        reload_interval: Duration::from_secs(10),
+        default_limits: ClientLimits {
+            request_quota: 100,
+            ..ClientLimits::default()
+        },
+        quota_window: Duration::from_secs(60),
+    }
+}
+
+#[test]
+fn test_keys_file_limits_override_the_defaults() {
+    let file_path = "test_client_keys.toml";
+    let content =
+        "[[clients]]\nname = \"partner\"\nkey = \"partner-key\"\nrpc_fallback_quota = 10\n";
+    create_keys_file(content, file_path, SystemTime::now());
+
+    let registry = ClientRegistry::load(settings(Some(file_path))).unwrap();
+
+    assert_eq!(registry.client_names(), vec!["inline", "partner"]);
+    let usage = registry.usage_of("partner").unwrap();
+    assert_eq!(
+        usage.limits,
+        ClientLimits {
+            rate_limit_per_sec: 0,
+            request_quota: 100,
+            rpc_fallback_quota: 10,
+        }
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_load_rejects_duplicate_clients_and_unknown_fields() {
+    let file_path = "test_client_keys_invalid.toml";
+
+    create_keys_file(
+        "[[clients]]\nname = \"inline\"\nkey = \"other-key\"\n",
+        file_path,
+        SystemTime::now(),
+    );
+    assert!(ClientRegistry::load(settings(Some(file_path))).is_err());
+
+    create_keys_file(
+        "[[clients]]\nname = \"partner\"\nkey = \"inline-key\"\n",
+        file_path,
+        SystemTime::now(),
+    );
+    assert!(ClientRegistry::load(settings(Some(file_path))).is_err());
+
+    create_keys_file(
+        "[[clients]]\nname = \"partner\"\nkey = \"partner-key\"\nquota = 5\n",
+        file_path,
+        SystemTime::now(),
+    );
+    assert!(ClientRegistry::load(settings(Some(file_path))).is_err());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_rotates_keys_and_keeps_usage() {
+    let file_path = "test_client_keys_reload.toml";
+    let start = SystemTime::now();
+    create_keys_file(
+        "[[clients]]\nname = \"partner\"\nkey = \"old-key\"\n",
+        file_path,
+        start,
+    );
+    let registry = Arc::new(ClientRegistry::load(settings(Some(file_path))).unwrap());
+
+    assert!(matches!(
+        registry.admit(Some("old-key")),
+        Admission::Admitted(_)
+    ));
+    assert!(!registry.reload_if_changed().unwrap());
+
+    create_keys_file(
+        "[[clients]]\nname = \"partner\"\nkey = \"new-key\"\n",
+        file_path,
+        start + Duration::from_secs(60),
+    );
+    assert!(registry.reload_if_changed().unwrap());
+    assert_eq!(registry.admit(Some("old-key")), Admission::Unknown);
+    assert!(matches!(
+        registry.admit(Some("new-key")),
+        Admission::Admitted(_)
+    ));
+    assert_eq!(registry.usage_of("partner").unwrap().total_requests, 2);
+
+    // A broken file is rejected and the current keys stay in use
+    create_keys_file("[[clients]", file_path, start + Duration::from_secs(120));
+    assert!(registry.reload_if_changed().is_err());
+    assert!(matches!(
+        registry.admit(Some("new-key")),
+        Admission::Admitted(_)
+    ));
+
+    fs::remove_file(file_path).unwrap();
+}
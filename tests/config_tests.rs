@@ -1,9 +1,20 @@
-use solana_caching_service::config::Config;
+use solana_caching_service::{
+    commitment::Commitment,
+    config::{
+        Config,
+        cli::CliArgs,
+        sources::{ConfigSources, KEYS, Origin},
+    },
+    logging::LogFormat,
+    metrics::MetricsBackend,
+    rpc::upstream::ApiKeyAuth,
+};
 use std::fs;
 use std::io::{ErrorKind, Write};
+use std::time::Duration;
 
 fn create_temp_env_file(content: &str, file_path: &str) {
-    let mut file = fs::File::create(&file_path).expect("Failed to create temp env file");
+    let mut file = fs::File::create(file_path).expect("Failed to create temp env file");
     file.write_all(content.as_bytes())
         .expect("Failed to write to temp env file");
 }
@@ -78,3 +89,603 @@ CACHE_CAPACITY=100
 
     fs::remove_file(file_path).unwrap();
 }
+
+#[test]
+fn test_from_env_file_parses_fallback_urls() {
+    let file_path = "test_fallback_urls.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nSOLANA_RPC_FALLBACK_URLS=http://a.com/k1, http://b.com/k2,";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(
+        config.rpc_fallback_urls,
+        vec!["http://a.com/k1".to_string(), "http://b.com/k2".to_string()]
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_poll_commitments() {
+    let file_path = "test_poll_commitments.env";
+    let content =
+        "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nPOLL_COMMITMENTS=finalized, confirmed";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(
+        config.poll_commitments,
+        vec![Commitment::Confirmed, Commitment::Finalized]
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_rejects_processed_poll_commitment() {
+    let file_path = "test_processed_poll_commitment.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nPOLL_COMMITMENTS=processed";
+
+    create_temp_env_file(content, file_path);
+
+    let result = Config::from_env_file(file_path);
+
+    assert!(result.is_err());
+    assert_eq!(result.err().unwrap().kind(), ErrorKind::InvalidInput);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_batching_settings() {
+    let file_path = "test_batching_settings.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nFALLBACK_BATCH_WINDOW_MS=0\nFALLBACK_BATCH_MAX_GAP=20";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.batch_window, Duration::ZERO);
+    assert_eq!(config.batch_max_slot_gap, 20);
+    assert_eq!(config.batch_max_size, 256);
+    assert_eq!(config.batch_max_range, 5000);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_snapshot_settings() {
+    let file_path = "test_snapshot_settings.env";
+    let content =
+        "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nSNAPSHOT_PATH=data/snapshot.json";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(
+        config.snapshot_path,
+        Some(std::path::PathBuf::from("data/snapshot.json"))
+    );
+    assert_eq!(config.snapshot_interval, Duration::from_secs(60));
+    assert_eq!(config.disk_store_path, None);
+    assert_eq!(config.disk_store_max_slots, 1_000_000);
+    assert!(!config.health_fail_on_open_circuit);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_disk_store_path() {
+    let file_path = "test_disk_store_path.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nDISK_STORE_PATH=data/slots.log\nDISK_STORE_MAX_SLOTS=5000";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(
+        config.disk_store_path,
+        Some(std::path::PathBuf::from("data/slots.log"))
+    );
+    assert_eq!(config.disk_store_max_slots, 5000);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_shared_cache_settings() {
+    let file_path = "test_shared_cache_settings.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nREDIS_URL=redis://cache:6380\nSHARED_CACHE_TTL_SECS=120";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.redis_url.as_deref(), Some("redis://cache:6380"));
+    assert_eq!(config.shared_cache_ttl, Duration::from_secs(120));
+    assert_eq!(config.shared_cache_timeout, Duration::from_millis(50));
+    assert_eq!(config.shared_cache_retry_interval, Duration::from_secs(5));
+    assert_eq!(config.shared_cache_key_prefix, "solana-caching-service");
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_poll_backfill_settings() {
+    let file_path = "test_poll_backfill_settings.env";
+    let content =
+        "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nPOLL_BACKFILL_CHUNK_SLOTS=250";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.poll_backfill_chunk_size, 250);
+    assert_eq!(config.poll_backfill_concurrency, 4);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_backfill_settings() {
+    let file_path = "test_backfill_settings.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nBACKFILL_MAX_RPC_PER_SEC=2\nBACKFILL_CHECKPOINT_PATH=data/backfill.json";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.backfill_max_rpc_per_sec, 2);
+    assert_eq!(config.backfill_chunk_size, 1000);
+    assert_eq!(config.backfill_retry_backoff, Duration::from_secs(5));
+    assert_eq!(
+        config.backfill_checkpoint_path,
+        Some(std::path::PathBuf::from("data/backfill.json"))
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_metrics_backend() {
+    let file_path = "test_metrics_backend.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nMETRICS_BACKEND=logging";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.metrics_backend, MetricsBackend::Logging);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_rejects_unknown_metrics_backend() {
+    let file_path = "test_unknown_metrics_backend.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nMETRICS_BACKEND=statsd";
+
+    create_temp_env_file(content, file_path);
+
+    let result = Config::from_env_file(file_path);
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_tracing_settings() {
+    let file_path = "test_tracing_settings.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nOTEL_EXPORTER_OTLP_ENDPOINT=http://localhost:4318\nOTEL_SERVICE_NAME=cache-eu\nTRACE_SAMPLE_RATIO=0.25";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(
+        config.otlp_endpoint.as_deref(),
+        Some("http://localhost:4318")
+    );
+    assert_eq!(config.otel_service_name, "cache-eu");
+    assert_eq!(config.trace_sample_ratio, 0.25);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_logging_settings() {
+    let file_path = "test_logging_settings.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nLOG_FORMAT=json\nLOG_FILTER=info,solana_caching_service::service::slot_poller=warn\nLOG_SAMPLE_EVERY=50";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.log_format, LogFormat::Json);
+    assert_eq!(
+        config.log_filter,
+        "info,solana_caching_service::service::slot_poller=warn"
+    );
+    assert_eq!(config.log_sample_every, 50);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_rejects_invalid_log_filter() {
+    let file_path = "test_invalid_log_filter.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nLOG_FILTER=info,solana_caching_service=loud";
+
+    create_temp_env_file(content, file_path);
+
+    let result = Config::from_env_file(file_path);
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_rejects_invalid_redis_url() {
+    let file_path = "test_invalid_redis_url.env";
+    let content =
+        "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nREDIS_URL=redis://cache:6379/primary";
+
+    create_temp_env_file(content, file_path);
+
+    let result = Config::from_env_file(file_path);
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(error.to_string().contains("REDIS_URL"));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_health_settings() {
+    let file_path = "test_health_settings.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nHEALTH_MAX_POLLER_LAG_SLOTS=300\nHEALTH_MAX_POLL_AGE_SECS=90\nHEALTH_FAIL_ON_OPEN_CIRCUIT=true";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.health_max_poller_lag, 300);
+    assert_eq!(config.health_max_poll_age, Duration::from_secs(90));
+    assert!(config.health_fail_on_open_circuit);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_config_fails_if_max_poll_age_does_not_exceed_interval() {
+    let file_path = "test_invalid_poll_age.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nPOLL_INTERVAL_SECONDS=15\nHEALTH_MAX_POLL_AGE_SECS=15";
+
+    create_temp_env_file(content, file_path);
+
+    let result = Config::from_env_file(file_path);
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_admin_token() {
+    let file_path = "test_admin_token.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nADMIN_TOKEN=s3cret";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.admin_token.as_deref(), Some("s3cret"));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_rejects_malformed_number() {
+    let file_path = "test_malformed_number.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nCACHE_CAPACITY=10k";
+
+    create_temp_env_file(content, file_path);
+
+    let result = Config::from_env_file(file_path);
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_load_reports_every_problem_at_once() {
+    let file_path = "test_every_problem.env";
+    let content =
+        "SOLANA_RPC_URL=http://example.com/\nCACHE_CAPACITY=10k\nLOG_FORMAT=xml\nCACHE_SIZE=5";
+
+    create_temp_env_file(content, file_path);
+
+    // Unknown keys fail before the values are parsed
+    let error = Config::load(&ConfigSources::from_env_file(file_path)).unwrap_err();
+    let keys: Vec<&str> = error.problems.iter().map(|p| p.key.as_str()).collect();
+    assert_eq!(keys, vec!["CACHE_SIZE"]);
+
+    create_temp_env_file(&content.replace("\nCACHE_SIZE=5", ""), file_path);
+
+    let error = Config::load(&ConfigSources::from_env_file(file_path)).unwrap_err();
+    let keys: Vec<&str> = error.problems.iter().map(|p| p.key.as_str()).collect();
+    assert_eq!(keys, vec!["API_KEY", "CACHE_CAPACITY", "LOG_FORMAT"]);
+    assert!(
+        error
+            .to_string()
+            .contains("invalid value '10k' from .env file")
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_load_layers_config_file_env_file_and_overrides() {
+    let toml_path = "test_layers.toml";
+    let env_path = "test_layers.env";
+    let toml = "solana_rpc_url = \"http://example.com/\"\napi_key = \"12345\"\ncache_capacity = 200\npoll_commitments = [\"finalized\"]\n\n[health]\nmax_poll_age_secs = 90\n";
+    let content = "CACHE_CAPACITY=300\nLRU_CACHE_CAPACITY=50";
+
+    create_temp_env_file(toml, toml_path);
+    create_temp_env_file(content, env_path);
+
+    let sources = ConfigSources {
+        config_file: Some(toml_path.into()),
+        env_file: Some(env_path.into()),
+        overrides: [("LRU_CACHE_CAPACITY".to_string(), "60".to_string())].into(),
+        ..ConfigSources::default()
+    };
+    let layers = sources.load().unwrap();
+    let config = Config::from_layers(&layers).unwrap();
+
+    assert_eq!(config.api_key, "12345");
+    assert_eq!(config.poll_commitments, vec![Commitment::Finalized]);
+    assert_eq!(config.health_max_poll_age, Duration::from_secs(90));
+    assert_eq!(config.cache_capacity, 300);
+    assert_eq!(config.lru_cache_capacity, 60);
+    assert_eq!(layers.origin("API_KEY"), Origin::ConfigFile);
+    assert_eq!(layers.origin("CACHE_CAPACITY"), Origin::EnvFile);
+    assert_eq!(layers.origin("LRU_CACHE_CAPACITY"), Origin::CommandLine);
+    assert_eq!(layers.origin("MAX_RETRIES"), Origin::Default);
+
+    fs::remove_file(toml_path).unwrap();
+    fs::remove_file(env_path).unwrap();
+}
+
+#[test]
+fn test_cli_args_parse_sources_and_overrides() {
+    let args = CliArgs::try_parse_from([
+        "solana-caching-service",
+        "--config",
+        "service.toml",
+        "--cache-capacity",
+        "500",
+        "--check-config",
+    ])
+    .unwrap();
+
+    assert!(args.check_config);
+    assert_eq!(args.sources.config_file, Some("service.toml".into()));
+    assert_eq!(args.sources.env_file, Some(".env".into()));
+    assert!(!args.sources.env_file_required);
+    assert_eq!(
+        args.sources
+            .overrides
+            .get("CACHE_CAPACITY")
+            .map(String::as_str),
+        Some("500")
+    );
+
+    assert!(CliArgs::try_parse_from(["solana-caching-service", "--cache-size", "5"]).is_err());
+}
+
+#[test]
+fn test_describe_redacts_secrets() {
+    let file_path = "test_describe.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nADMIN_TOKEN=s3cret";
+
+    create_temp_env_file(content, file_path);
+
+    let layers = ConfigSources::from_env_file(file_path).load().unwrap();
+    let config = Config::from_layers(&layers).unwrap();
+    let description = config.describe(&layers);
+
+    assert!(!description.contains("12345"));
+    assert!(!description.contains("s3cret"));
+    assert!(description.contains("API_KEY=<redacted>  # .env file\n"));
+    assert!(description.contains("REDIS_URL=  # default\n"));
+    assert!(description.contains("CACHE_CAPACITY=1000  # default\n"));
+    let keys: Vec<&str> = config.settings().iter().map(|(key, _)| *key).collect();
+    assert_eq!(keys, KEYS);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_listener_settings() {
+    let file_path = "test_listener.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nBIND_ADDRESS=0.0.0.0:8443\nTLS_CERT_PATH=/etc/tls/cert.pem\nTLS_KEY_PATH=/etc/tls/key.pem\nUNIX_SOCKET_PATH=/run/service.sock";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.bind_address, "0.0.0.0:8443".parse().unwrap());
+    assert_eq!(config.tls_cert_path, Some("/etc/tls/cert.pem".into()));
+    assert_eq!(config.tls_key_path, Some("/etc/tls/key.pem".into()));
+    assert_eq!(config.tls_reload_interval, Duration::from_secs(30));
+    assert_eq!(config.unix_socket_path, Some("/run/service.sock".into()));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_config_rejects_plaintext_on_non_loopback_address() {
+    let file_path = "test_plaintext_listener.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nBIND_ADDRESS=0.0.0.0:8000\nTLS_CERT_PATH=/etc/tls/cert.pem";
+
+    create_temp_env_file(content, file_path);
+
+    let error = Config::load(&ConfigSources::from_env_file(file_path)).unwrap_err();
+    let keys: Vec<&str> = error.problems.iter().map(|p| p.key.as_str()).collect();
+    assert_eq!(keys, vec!["TLS_KEY_PATH"]);
+
+    create_temp_env_file(
+        &content.replace("\nTLS_CERT_PATH=/etc/tls/cert.pem", ""),
+        file_path,
+    );
+
+    let error = Config::load(&ConfigSources::from_env_file(file_path)).unwrap_err();
+    let keys: Vec<&str> = error.problems.iter().map(|p| p.key.as_str()).collect();
+    assert_eq!(keys, vec!["BIND_ADDRESS"]);
+
+    create_temp_env_file(
+        &content.replace(
+            "0.0.0.0:8000\nTLS_CERT_PATH=/etc/tls/cert.pem",
+            "[::1]:8000",
+        ),
+        file_path,
+    );
+
+    let config = Config::from_env_file(file_path).unwrap();
+    assert_eq!(config.bind_address, "[::1]:8000".parse().unwrap());
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_client_keys() {
+    let file_path = "test_client_keys.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nCLIENT_API_KEYS=alpha:key-a, beta:key-b\nCLIENT_RPC_FALLBACK_QUOTA=500";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(
+        config.client_api_keys,
+        vec![
+            ("alpha".to_string(), "key-a".to_string()),
+            ("beta".to_string(), "key-b".to_string())
+        ]
+    );
+    assert_eq!(config.client_rpc_fallback_quota, 500);
+    assert_eq!(config.client_quota_window, Duration::from_secs(86400));
+
+    // Errors never echo the keys
+    create_temp_env_file(&content.replace("beta:key-b", "key-b"), file_path);
+
+    let error = Config::load(&ConfigSources::from_env_file(file_path)).unwrap_err();
+    assert_eq!(error.problems[0].key, "CLIENT_API_KEYS");
+    assert!(!error.to_string().contains("key-a"));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_from_env_file_parses_api_key_auth() {
+    let file_path = "test_api_key_auth.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\nAPI_KEY_AUTH=header\nAPI_KEY_HEADER=X-Provider-Key";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+
+    assert_eq!(config.api_key_auth, ApiKeyAuth::Header);
+    assert_eq!(config.api_key_header.as_str(), "x-provider-key");
+
+    // A key that cannot be sent as a header is rejected without being echoed
+    create_temp_env_file(
+        &content.replace("API_KEY=12345", "API_KEY=12\u{7f}345"),
+        file_path,
+    );
+
+    let error = Config::load(&ConfigSources::from_env_file(file_path)).unwrap_err();
+    assert_eq!(error.problems[0].key, "API_KEY");
+    assert!(!error.to_string().contains("345"));
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[test]
+fn test_load_reads_secrets_from_files() {
+    let env_path = "test_secret_files.env";
+    let key_path = "test_secret_files.api_key";
+    let token_path = "test_secret_files.admin_token";
+    let content = format!(
+        "SOLANA_RPC_URL=http://example.com/\nAPI_KEY_FILE={}\nADMIN_TOKEN_FILE={}",
+        key_path, token_path
+    );
+
+    create_temp_env_file(&content, env_path);
+    create_temp_env_file("mounted-key\n", key_path);
+    create_temp_env_file("mounted-token", token_path);
+
+    let layers = ConfigSources::from_env_file(env_path).load().unwrap();
+    let config = Config::from_layers(&layers).unwrap();
+
+    assert_eq!(config.api_key, "mounted-key");
+    assert_eq!(config.admin_token.as_deref(), Some("mounted-token"));
+    assert_eq!(layers.origin("API_KEY"), Origin::SecretFile);
+    let description = config.describe(&layers);
+    assert!(description.contains("API_KEY=<redacted>  # secret file\n"));
+    assert!(!description.contains("mounted-key"));
+
+    // The value and its file in the same layer conflict
+    create_temp_env_file(&format!("{}\nAPI_KEY=12345", content), env_path);
+
+    let error = ConfigSources::from_env_file(env_path).load().unwrap_err();
+    let keys: Vec<&str> = error.problems.iter().map(|p| p.key.as_str()).collect();
+    assert_eq!(keys, vec!["API_KEY"]);
+
+    // A value set in a higher layer wins over the file
+    create_temp_env_file(&content, env_path);
+    let sources = ConfigSources {
+        overrides: [("API_KEY".to_string(), "flag-key".to_string())].into(),
+        ..ConfigSources::from_env_file(env_path)
+    };
+
+    let config = Config::load(&sources).unwrap();
+    assert_eq!(config.api_key, "flag-key");
+
+    fs::remove_file(key_path).unwrap();
+    fs::remove_file(token_path).unwrap();
+
+    let error = ConfigSources::from_env_file(env_path).load().unwrap_err();
+    assert_eq!(error.problems.len(), 2);
+    assert_eq!(error.problems[0].kind, ErrorKind::NotFound);
+
+    fs::remove_file(env_path).unwrap();
+}
+
+#[test]
+fn test_config_debug_redacts_secrets() {
+    let file_path = "test_config_debug.env";
+    let content = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=provider-secret-key\nSOLANA_RPC_FALLBACK_URLS=https://fallback.example.com/other-key";
+
+    create_temp_env_file(content, file_path);
+
+    let config = Config::from_env_file(file_path).unwrap();
+    let debug = format!("{:?}", config);
+
+    assert!(!debug.contains("provider-secret-key"));
+    assert!(!debug.contains("other-key"));
+    assert!(debug.contains("API_KEY: \"<redacted>\""));
+    assert!(debug.contains("CACHE_CAPACITY: \"1000\""));
+
+    fs::remove_file(file_path).unwrap();
+}
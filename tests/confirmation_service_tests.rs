@@ -1,11 +1,13 @@
 use mockall::{mock, predicate::*};
 use solana_caching_service::circuit_breaker::CircuitBreaker;
 use solana_caching_service::{
-    cache::{LruCache, SlotCache},
+    cache::{DiskStore, LruCache, SlotCache, StoredSlot},
+    commitment::Commitment,
     metrics::Metrics,
     rpc::RpcApi,
     service::confirmation_service::{
-        ConfirmationStatus, confirm, confirm_with_lru, confirm_with_lru_and_breaker,
+        ConfirmationStatus, confirm, confirm_many, confirm_with_commitment, confirm_with_lru,
+        confirm_with_lru_and_breaker,
     },
     state::AppState,
 };
@@ -24,6 +26,28 @@ mock! {
     }
 }
 
+mock! {
+    pub CommitmentRpcApi {}
+    impl RpcApi for CommitmentRpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_slot_with_commitment<'a>(
+            &'a self,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
 mock! {
     pub Metrics {}
     impl Metrics for Metrics {
@@ -41,13 +65,13 @@ fn create_test_app_state(
 ) -> AppState {
     let circuit_breaker = Arc::new(CircuitBreaker::new(3, Duration::from_secs(10)));
 
-    AppState {
-        rpc_client: Arc::new(mock_rpc),
+    AppState::new(
+        Arc::new(mock_rpc),
         cache,
         lru_cache,
-        metrics: Arc::new(mock_metrics),
+        Arc::new(mock_metrics),
         circuit_breaker,
-    }
+    )
 }
 
 fn create_test_app_state_with_circuit_breaker(
@@ -57,13 +81,13 @@ fn create_test_app_state_with_circuit_breaker(
     mock_metrics: MockMetrics,
     circuit_breaker: Arc<CircuitBreaker>,
 ) -> AppState {
-    AppState {
-        rpc_client: Arc::new(mock_rpc),
+    AppState::new(
+        Arc::new(mock_rpc),
         cache,
         lru_cache,
-        metrics: Arc::new(mock_metrics),
+        Arc::new(mock_metrics),
         circuit_breaker,
-    }
+    )
 }
 
 #[tokio::test]
@@ -304,3 +328,374 @@ async fn test_cb_service_trips_circuit_and_then_rejects_request() {
     let second_result = confirm_with_lru_and_breaker(&app_state, 201).await;
     assert_eq!(second_result, ConfirmationStatus::Error);
 }
+
+fn create_commitment_test_app_state(
+    mock_rpc: MockCommitmentRpcApi,
+    cache: Arc<SlotCache>,
+    lru_cache: Arc<LruCache>,
+    mock_metrics: MockMetrics,
+) -> AppState {
+    AppState::new(
+        Arc::new(mock_rpc),
+        cache,
+        lru_cache,
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+#[tokio::test]
+async fn test_commitment_service_upgrades_lru_entry_on_finalized_query() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    lru_cache.put(500).await;
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(500), eq(Some(500)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![500]) }));
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(3)
+        .return_const(());
+
+    let app_state =
+        create_commitment_test_app_state(mock_rpc, primary_cache, lru_cache.clone(), mock_metrics);
+
+    // Confirmed is answered from the LRU entry, finalized needs RPC once and is then cached
+    let confirmed = confirm_with_commitment(&app_state, 500, Commitment::Confirmed).await;
+    let finalized = confirm_with_commitment(&app_state, 500, Commitment::Finalized).await;
+    let finalized_again = confirm_with_commitment(&app_state, 500, Commitment::Finalized).await;
+
+    assert_eq!(confirmed, ConfirmationStatus::Confirmed);
+    assert_eq!(finalized, ConfirmationStatus::Confirmed);
+    assert_eq!(finalized_again, ConfirmationStatus::Confirmed);
+    assert_eq!(
+        lru_cache.get_commitment(&500).await,
+        Some(Commitment::Finalized)
+    );
+}
+
+#[tokio::test]
+async fn test_commitment_service_upgrades_primary_cache_entry() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    primary_cache.insert(600).await;
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(600), eq(Some(600)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![600]) }));
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(1)
+        .return_const(());
+
+    let app_state = create_commitment_test_app_state(
+        mock_rpc,
+        primary_cache.clone(),
+        lru_cache.clone(),
+        mock_metrics,
+    );
+
+    let result = confirm_with_commitment(&app_state, 600, Commitment::Finalized).await;
+
+    assert_eq!(result, ConfirmationStatus::Confirmed);
+    assert_eq!(
+        primary_cache.get_commitment(&600).await,
+        Some(Commitment::Finalized)
+    );
+    assert!(lru_cache.get_all_slots().await.is_empty());
+}
+
+#[tokio::test]
+async fn test_commitment_service_queries_processed_at_confirmed() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(700), eq(Some(700)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(1)
+        .return_const(());
+
+    let app_state =
+        create_commitment_test_app_state(mock_rpc, primary_cache, lru_cache, mock_metrics);
+
+    let result = confirm_with_commitment(&app_state, 700, Commitment::Processed).await;
+
+    assert_eq!(result, ConfirmationStatus::NotConfirmed);
+}
+
+#[tokio::test]
+async fn test_concurrent_misses_for_same_slot_share_one_rpc_call() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks()
+        .with(eq(800), eq(Some(800)))
+        .times(1)
+        .returning(|_, _| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(vec![800])
+            })
+        });
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(20)
+        .return_const(());
+
+    let app_state = create_test_app_state(mock_rpc, primary_cache, lru_cache.clone(), mock_metrics);
+
+    let results = futures_util::future::join_all(
+        (0..20).map(|_| confirm_with_lru_and_breaker(&app_state, 800)),
+    )
+    .await;
+
+    assert!(results.iter().all(|r| *r == ConfirmationStatus::Confirmed));
+    assert!(lru_cache.get(&800).await);
+}
+
+#[tokio::test]
+async fn test_concurrent_misses_share_rpc_error() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks()
+        .with(eq(900), eq(Some(900)))
+        .times(1)
+        .returning(|_, _| {
+            let err = ClientError {
+                kind: ClientErrorKind::Custom("RPC Down".into()),
+                request: None,
+            };
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Err(err)
+            })
+        });
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(5)
+        .return_const(());
+
+    let app_state = create_test_app_state(mock_rpc, primary_cache, lru_cache, mock_metrics);
+
+    let results = futures_util::future::join_all(
+        (0..5).map(|_| confirm_with_lru_and_breaker(&app_state, 900)),
+    )
+    .await;
+
+    assert!(results.iter().all(|r| *r == ConfirmationStatus::Error));
+}
+
+#[tokio::test]
+async fn test_commitment_service_answers_skipped_slot_from_primary_cache() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    primary_cache
+        .insert_skipped(800, Commitment::Finalized)
+        .await;
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(2)
+        .return_const(());
+
+    let app_state =
+        create_commitment_test_app_state(mock_rpc, primary_cache, lru_cache, mock_metrics);
+
+    let confirmed = confirm_with_commitment(&app_state, 800, Commitment::Confirmed).await;
+    let finalized = confirm_with_commitment(&app_state, 800, Commitment::Finalized).await;
+
+    assert_eq!(confirmed, ConfirmationStatus::NotConfirmed);
+    assert_eq!(finalized, ConfirmationStatus::NotConfirmed);
+}
+
+#[tokio::test]
+async fn test_commitment_service_caches_skipped_slot_behind_known_tip() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    primary_cache.insert(950).await;
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(900), eq(Some(900)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(1000), eq(Some(1000)), eq(Commitment::Confirmed))
+        .times(2)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(4)
+        .return_const(());
+
+    let app_state =
+        create_commitment_test_app_state(mock_rpc, primary_cache, lru_cache.clone(), mock_metrics);
+
+    // Slot 900 is behind the cached tip, so its absence is final and served from the LRU afterwards
+    let first = confirm_with_commitment(&app_state, 900, Commitment::Confirmed).await;
+    let second = confirm_with_commitment(&app_state, 900, Commitment::Confirmed).await;
+    // Slot 1000 may simply not be produced yet and must be asked again
+    let future = confirm_with_commitment(&app_state, 1000, Commitment::Confirmed).await;
+    let future_again = confirm_with_commitment(&app_state, 1000, Commitment::Confirmed).await;
+
+    assert_eq!(first, ConfirmationStatus::NotConfirmed);
+    assert_eq!(second, ConfirmationStatus::NotConfirmed);
+    assert_eq!(future, ConfirmationStatus::NotConfirmed);
+    assert_eq!(future_again, ConfirmationStatus::NotConfirmed);
+    assert!(lru_cache.is_skipped_at(&900, Commitment::Confirmed).await);
+    assert!(!lru_cache.is_skipped_at(&1000, Commitment::Confirmed).await);
+}
+
+#[tokio::test]
+async fn test_confirm_many_answers_in_input_order() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    primary_cache.insert(5).await;
+    primary_cache.insert_skipped(6, Commitment::Confirmed).await;
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(1000), eq(Some(1002)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![1000]) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(9000), eq(Some(9000)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| {
+            Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+        });
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .times(2)
+        .return_const(());
+
+    let app_state =
+        create_commitment_test_app_state(mock_rpc, primary_cache, lru_cache.clone(), mock_metrics);
+
+    let statuses = confirm_many(&app_state, &[1002, 5, 9000, 6, 1000], Commitment::Processed).await;
+
+    assert_eq!(
+        statuses,
+        vec![
+            ConfirmationStatus::NotConfirmed,
+            ConfirmationStatus::Confirmed,
+            ConfirmationStatus::Error,
+            ConfirmationStatus::NotConfirmed,
+            ConfirmationStatus::Confirmed,
+        ]
+    );
+    assert!(lru_cache.get(&1000).await);
+    // 1002 lies past the highest block of its range, so it may not be produced yet
+    assert!(!lru_cache.is_skipped_at(&1002, Commitment::Confirmed).await);
+}
+
+fn disk_store_path(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("confirmation-tests-{}", std::process::id()));
+    let path = dir.join(format!("{}.log", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn test_commitment_service_answers_from_disk_store_after_lru_eviction() {
+    let disk_store = Arc::new(DiskStore::open(disk_store_path("hit"), 1_000_000).unwrap());
+    disk_store
+        .record_confirmed(700, Commitment::Finalized)
+        .await;
+    disk_store.record_skipped(701, Commitment::Finalized).await;
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc.expect_get_blocks_with_commitment().times(0);
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(2)
+        .return_const(());
+
+    let app_state = create_commitment_test_app_state(
+        mock_rpc,
+        Arc::new(SlotCache::new(10)),
+        lru_cache.clone(),
+        mock_metrics,
+    )
+    .with_disk_store(disk_store);
+
+    let confirmed = confirm_with_commitment(&app_state, 700, Commitment::Finalized).await;
+    let skipped = confirm_with_commitment(&app_state, 701, Commitment::Confirmed).await;
+
+    assert_eq!(confirmed, ConfirmationStatus::Confirmed);
+    assert_eq!(skipped, ConfirmationStatus::NotConfirmed);
+    // Hits are brought back into the LRU cache
+    assert_eq!(
+        lru_cache.get_commitment(&700).await,
+        Some(Commitment::Finalized)
+    );
+}
+
+#[tokio::test]
+async fn test_commitment_service_records_rpc_answers_in_disk_store() {
+    let disk_store = Arc::new(DiskStore::open(disk_store_path("record"), 1_000_000).unwrap());
+    disk_store
+        .record_confirmed(800, Commitment::Confirmed)
+        .await;
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    // A weaker stored observation does not answer a finalized query
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(800), eq(Some(800)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![800]) }));
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .return_const(());
+
+    let app_state = create_commitment_test_app_state(
+        mock_rpc,
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        mock_metrics,
+    )
+    .with_disk_store(disk_store.clone());
+
+    let status = confirm_with_commitment(&app_state, 800, Commitment::Finalized).await;
+
+    assert_eq!(status, ConfirmationStatus::Confirmed);
+    assert_eq!(
+        disk_store.get(&800).await,
+        Some(StoredSlot::Confirmed(Commitment::Finalized))
+    );
+}
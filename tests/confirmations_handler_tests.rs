@@ -0,0 +1,237 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Query, Request, State},
+    http::{StatusCode, header},
+};
+use mockall::{mock, predicate::*};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    handler::{
+        confirmations_handler::{
+            MAX_BATCH_SLOTS, MAX_NDJSON_LINE_BYTES, batch_confirmation_handler,
+        },
+        slot_handler::CommitmentParams,
+    },
+    metrics::Metrics,
+    rpc::RpcApi,
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn create_test_app_state(mock_rpc: MockRpcApi, cache: Arc<SlotCache>) -> AppState {
+    let mut mock_metrics = MockMetrics::new();
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+
+    AppState::new(
+        Arc::new(mock_rpc),
+        cache,
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+fn request(content_type: &str, body: impl Into<Body>) -> Request {
+    Request::builder()
+        .method("POST")
+        .uri("/slots/confirmations")
+        .header(header::CONTENT_TYPE, content_type)
+        .body(body.into())
+        .unwrap()
+}
+
+async fn body_text(response: axum::response::Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[tokio::test]
+async fn test_json_batch_groups_misses_into_one_range_call() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache.insert(100).await;
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(40), eq(Some(60)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![40, 55, 60]) }));
+
+    let app_state = create_test_app_state(mock_rpc, cache);
+
+    let response = batch_confirmation_handler(
+        State(app_state),
+        Query(CommitmentParams::default()),
+        request("application/json", r#"{"slots":[100,40,50,60,40]}"#),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({"results": [
+            {"slot": 100, "status": "confirmed"},
+            {"slot": 40, "status": "confirmed"},
+            {"slot": 50, "status": "not_confirmed"},
+            {"slot": 60, "status": "confirmed"},
+            {"slot": 40, "status": "confirmed"},
+        ]})
+    );
+}
+
+#[tokio::test]
+async fn test_json_batch_rejects_oversized_list() {
+    let app_state = create_test_app_state(MockRpcApi::new(), Arc::new(SlotCache::new(10)));
+    let slots: Vec<u64> = (0..=MAX_BATCH_SLOTS as u64).collect();
+    let body = serde_json::json!({ "slots": slots }).to_string();
+
+    let response = batch_confirmation_handler(
+        State(app_state),
+        Query(CommitmentParams::default()),
+        request("application/json", body),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_json_batch_rejects_malformed_body() {
+    let app_state = create_test_app_state(MockRpcApi::new(), Arc::new(SlotCache::new(10)));
+
+    let response = batch_confirmation_handler(
+        State(app_state),
+        Query(CommitmentParams::default()),
+        request("application/json", r#"{"slots":"all"}"#),
+    )
+    .await;
+
+    assert!(response.status().is_client_error());
+}
+
+#[tokio::test]
+async fn test_ndjson_batch_streams_statuses() {
+    let cache = Arc::new(SlotCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(10), eq(Some(12)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![10, 12]) }));
+
+    let app_state = create_test_app_state(mock_rpc, cache);
+
+    let response = batch_confirmation_handler(
+        State(app_state),
+        Query(CommitmentParams {
+            commitment: Some(Commitment::Finalized),
+        }),
+        request("application/x-ndjson", "10\nnope\n\n11\n12"),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "application/x-ndjson"
+    );
+    let lines: Vec<serde_json::Value> = body_text(response)
+        .await
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            serde_json::json!({"line": 2, "error": "expected a slot number"}),
+            serde_json::json!({"slot": 10, "status": "confirmed"}),
+            serde_json::json!({"slot": 11, "status": "not_confirmed"}),
+            serde_json::json!({"slot": 12, "status": "confirmed"}),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_ndjson_batch_skips_oversized_lines() {
+    let cache = Arc::new(SlotCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(10), eq(Some(11)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![10]) }));
+
+    let app_state = create_test_app_state(mock_rpc, cache);
+    // A line without a newline is read in chunks that each stay under the limit
+    let chunk = "9".repeat(MAX_NDJSON_LINE_BYTES / 2 + 1);
+    let chunks = vec![
+        "10\n".to_string(),
+        chunk.clone(),
+        chunk.clone(),
+        chunk,
+        "\n11\n".to_string(),
+    ];
+    let body = Body::from_stream(futures_util::stream::iter(
+        chunks.into_iter().map(Ok::<_, std::convert::Infallible>),
+    ));
+
+    let response = batch_confirmation_handler(
+        State(app_state),
+        Query(CommitmentParams::default()),
+        request("application/x-ndjson", body),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let lines: Vec<serde_json::Value> = body_text(response)
+        .await
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            serde_json::json!({
+                "line": 2,
+                "error": format!("line is longer than {} bytes", MAX_NDJSON_LINE_BYTES),
+            }),
+            serde_json::json!({"slot": 10, "status": "confirmed"}),
+            serde_json::json!({"slot": 11, "status": "not_confirmed"}),
+        ]
+    );
+}
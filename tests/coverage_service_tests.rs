@@ -0,0 +1,133 @@
+use mockall::{mock, predicate::*};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    metrics::Metrics,
+    rpc::RpcApi,
+    service::coverage_service::{CoverageCounts, CoverageSource, get_range_coverage},
+    state::AppState,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn create_test_app_state(
+    mock_rpc: MockRpcApi,
+    cache: Arc<SlotCache>,
+    mock_metrics: MockMetrics,
+) -> AppState {
+    AppState::new(
+        Arc::new(mock_rpc),
+        cache,
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+#[tokio::test]
+async fn test_coverage_is_answered_from_cache_when_range_is_known() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache.insert(100).await;
+    cache.insert_skipped(101, Commitment::Confirmed).await;
+    cache.insert(102).await;
+
+    let app_state = create_test_app_state(MockRpcApi::new(), cache, MockMetrics::new());
+
+    let coverage = get_range_coverage(&app_state, 100, 102, Commitment::Confirmed)
+        .await
+        .unwrap();
+
+    assert_eq!(coverage.source, CoverageSource::Cache);
+    assert_eq!(coverage.confirmed, vec![100, 102]);
+    assert_eq!(coverage.skipped, vec![101]);
+    assert!(coverage.unknown.is_empty());
+    assert_eq!(
+        coverage.counts,
+        CoverageCounts {
+            confirmed: 2,
+            skipped: 1,
+            unknown: 0
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_coverage_falls_back_to_single_range_call() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache.insert(100).await;
+    let mut mock_rpc = MockRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(100), eq(Some(105)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![100, 102, 103]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .times(1)
+        .return_const(());
+
+    let app_state = create_test_app_state(mock_rpc, cache, mock_metrics);
+
+    let coverage = get_range_coverage(&app_state, 100, 105, Commitment::Finalized)
+        .await
+        .unwrap();
+
+    assert_eq!(coverage.source, CoverageSource::Rpc);
+    assert_eq!(coverage.confirmed, vec![100, 102, 103]);
+    assert_eq!(coverage.skipped, vec![101]);
+    // Nothing is known to be finalized past 103 yet
+    assert_eq!(coverage.unknown, vec![104, 105]);
+    assert_eq!(coverage.counts.unknown, 2);
+}
+
+#[tokio::test]
+async fn test_coverage_returns_none_on_rpc_error() {
+    let mut mock_rpc = MockRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .times(1)
+        .returning(|_, _, _| {
+            Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+        });
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+
+    let app_state = create_test_app_state(mock_rpc, Arc::new(SlotCache::new(10)), mock_metrics);
+
+    let coverage = get_range_coverage(&app_state, 1, 3, Commitment::Confirmed).await;
+
+    assert!(coverage.is_none());
+}
@@ -0,0 +1,163 @@
+use solana_caching_service::{
+    cache::{DiskStore, StoredSlot},
+    commitment::Commitment,
+};
+use std::{fs::OpenOptions, io::Write, path::PathBuf};
+
+const MAX_SLOTS: usize = 1_000_000;
+
+fn store_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("disk-store-tests-{}", std::process::id()));
+    let path = dir.join(format!("{}.log", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn test_records_survive_reopen() {
+    let path = store_path("reopen");
+    {
+        let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+        store.record_confirmed(100, Commitment::Confirmed).await;
+        store.record_confirmed(100, Commitment::Finalized).await;
+        store.record_skipped(101, Commitment::Finalized).await;
+    }
+
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+
+    assert_eq!(store.len().await, 2);
+    assert_eq!(
+        store.get(&100).await,
+        Some(StoredSlot::Confirmed(Commitment::Finalized))
+    );
+    assert!(store.is_skipped_at(&101, Commitment::Confirmed).await);
+    assert_eq!(store.get(&102).await, None);
+}
+
+#[tokio::test]
+async fn test_weaker_observations_do_not_replace_stored_ones() {
+    let path = store_path("supersede");
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+
+    store.record_confirmed(100, Commitment::Finalized).await;
+    store.record_confirmed(100, Commitment::Confirmed).await;
+    store.record_skipped(100, Commitment::Confirmed).await;
+    assert_eq!(
+        store.get(&100).await,
+        Some(StoredSlot::Confirmed(Commitment::Finalized))
+    );
+
+    store.record_skipped(101, Commitment::Confirmed).await;
+    store.record_confirmed(101, Commitment::Confirmed).await;
+    assert!(store.is_confirmed_at(&101, Commitment::Confirmed).await);
+
+    let log_len = std::fs::metadata(&path).unwrap().len();
+    store.record_confirmed(100, Commitment::Confirmed).await;
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().len(),
+        log_len,
+        "Nothing new is appended"
+    );
+}
+
+#[tokio::test]
+async fn test_torn_tail_is_dropped_on_open() {
+    let path = store_path("torn");
+    {
+        let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+        store.record_confirmed(100, Commitment::Confirmed).await;
+        store.record_confirmed(101, Commitment::Confirmed).await;
+    }
+    let intact_len = std::fs::metadata(&path).unwrap().len();
+    // A crash in the middle of an append leaves part of a record behind
+    let mut log = OpenOptions::new().append(true).open(&path).unwrap();
+    log.write_all(&[0xAB; 5]).unwrap();
+    drop(log);
+
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+    assert_eq!(store.len().await, 2);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), intact_len);
+
+    store.record_confirmed(102, Commitment::Confirmed).await;
+    drop(store);
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+    assert!(store.is_confirmed_at(&102, Commitment::Confirmed).await);
+}
+
+#[tokio::test]
+async fn test_open_rejects_foreign_files() {
+    let path = store_path("foreign");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, b"not a slot log").unwrap();
+
+    assert!(DiskStore::open(&path, MAX_SLOTS).is_err());
+}
+
+#[tokio::test]
+async fn test_compaction_keeps_latest_observation_per_slot() {
+    let path = store_path("compact");
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+    for slot in 0..10 {
+        store.record_confirmed(slot, Commitment::Confirmed).await;
+        store.record_confirmed(slot, Commitment::Finalized).await;
+    }
+    let log_len = std::fs::metadata(&path).unwrap().len();
+
+    store.compact().await.unwrap();
+    assert!(std::fs::metadata(&path).unwrap().len() < log_len);
+
+    store.record_skipped(10, Commitment::Finalized).await;
+    drop(store);
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+    assert_eq!(store.len().await, 11);
+    assert!(store.is_confirmed_at(&3, Commitment::Finalized).await);
+    assert!(store.is_skipped_at(&10, Commitment::Finalized).await);
+}
+
+#[tokio::test]
+async fn test_oldest_writes_are_evicted_and_compacted_away() {
+    let path = store_path("max-slots");
+    let store = DiskStore::open(&path, 10).unwrap();
+    for slot in 0..20 {
+        store.record_confirmed(slot, Commitment::Finalized).await;
+    }
+
+    assert_eq!(store.len().await, 10);
+    assert_eq!(store.get(&9).await, None);
+    assert!(store.is_confirmed_at(&10, Commitment::Finalized).await);
+
+    // Written last, so it outlives slots written before it
+    store.record_confirmed(5, Commitment::Finalized).await;
+    assert!(store.is_confirmed_at(&5, Commitment::Finalized).await);
+    assert_eq!(store.get(&10).await, None);
+
+    let log_len = std::fs::metadata(&path).unwrap().len();
+    store.compact().await.unwrap();
+    assert!(std::fs::metadata(&path).unwrap().len() < log_len);
+    drop(store);
+
+    // Compaction keeps the write order, a smaller maximum on reopen evicts the oldest writes
+    let store = DiskStore::open(&path, 5).unwrap();
+    assert_eq!(store.len().await, 5);
+    assert_eq!(store.get(&15).await, None);
+    assert!(store.is_confirmed_at(&16, Commitment::Finalized).await);
+    assert!(store.is_confirmed_at(&5, Commitment::Finalized).await);
+}
+
+#[tokio::test]
+async fn test_old_slots_are_kept_after_a_tip_slot() {
+    let path = store_path("old-after-tip");
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+
+    store
+        .record_confirmed(350_000_000, Commitment::Confirmed)
+        .await;
+    store.record_confirmed(1_000, Commitment::Finalized).await;
+    store.record_skipped(1_001, Commitment::Finalized).await;
+    drop(store);
+
+    let store = DiskStore::open(&path, MAX_SLOTS).unwrap();
+    assert_eq!(store.len().await, 3);
+    assert!(store.is_confirmed_at(&1_000, Commitment::Finalized).await);
+    assert!(store.is_skipped_at(&1_001, Commitment::Finalized).await);
+}
@@ -0,0 +1,193 @@
+use futures_util::future::join_all;
+use mockall::{mock, predicate::*};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    metrics::Metrics,
+    rpc::RpcApi,
+    service::{
+        confirmation_service::{ConfirmationStatus, confirm_with_commitment},
+        fallback_batcher::{BatcherSettings, FallbackBatcher, plan_ranges},
+    },
+    state::AppState,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn test_settings() -> BatcherSettings {
+    BatcherSettings {
+        window: Duration::from_millis(20),
+        max_batch_size: 64,
+        max_slot_gap: 10,
+        max_range: 100,
+    }
+}
+
+fn create_batched_app_state(
+    mock_rpc: MockRpcApi,
+    cache: Arc<SlotCache>,
+    lru_cache: Arc<LruCache>,
+) -> AppState {
+    let mut mock_metrics = MockMetrics::new();
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache,
+        lru_cache,
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let batcher = FallbackBatcher::new(app_state.clone(), test_settings());
+    app_state.with_batcher(Arc::new(batcher))
+}
+
+#[test]
+fn test_plan_ranges_merges_nearby_slots() {
+    let ranges = plan_ranges(&[105, 100, 103, 100, 200, 208], 10, 100);
+
+    assert_eq!(ranges, vec![(100, 105), (200, 208)]);
+}
+
+#[test]
+fn test_plan_ranges_splits_ranges_exceeding_max_range() {
+    let ranges = plan_ranges(&[0, 5, 10, 15, 20], 10, 12);
+
+    assert_eq!(ranges, vec![(0, 10), (15, 20)]);
+}
+
+#[tokio::test]
+async fn test_concurrent_misses_share_one_range_call() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(100), eq(Some(105)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![100, 101, 103, 105]) }));
+
+    let app_state = create_batched_app_state(mock_rpc, primary_cache, lru_cache.clone());
+
+    let statuses = join_all(
+        [100, 103, 104, 105]
+            .map(|slot| confirm_with_commitment(&app_state, slot, Commitment::Confirmed)),
+    )
+    .await;
+
+    assert_eq!(
+        statuses,
+        vec![
+            ConfirmationStatus::Confirmed,
+            ConfirmationStatus::Confirmed,
+            ConfirmationStatus::NotConfirmed,
+            ConfirmationStatus::Confirmed,
+        ]
+    );
+    assert!(lru_cache.get(&103).await);
+    assert!(
+        !lru_cache.get(&101).await,
+        "Only requested slots are cached"
+    );
+}
+
+#[tokio::test]
+async fn test_distant_slots_and_commitments_are_resolved_separately() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(100), eq(Some(100)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![100]) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(500), eq(Some(500)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![]) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(101), eq(Some(101)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![101]) }));
+
+    let app_state = create_batched_app_state(mock_rpc, primary_cache, lru_cache.clone());
+
+    let (first, second, third) = tokio::join!(
+        confirm_with_commitment(&app_state, 100, Commitment::Confirmed),
+        confirm_with_commitment(&app_state, 500, Commitment::Confirmed),
+        confirm_with_commitment(&app_state, 101, Commitment::Finalized),
+    );
+
+    assert_eq!(first, ConfirmationStatus::Confirmed);
+    assert_eq!(second, ConfirmationStatus::NotConfirmed);
+    assert_eq!(third, ConfirmationStatus::Confirmed);
+    assert_eq!(
+        lru_cache.get_commitment(&101).await,
+        Some(Commitment::Finalized)
+    );
+}
+
+#[tokio::test]
+async fn test_range_error_fans_out_to_every_waiter() {
+    let primary_cache = Arc::new(SlotCache::new(10));
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(100), eq(Some(102)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| {
+            Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+        });
+
+    let app_state = create_batched_app_state(mock_rpc, primary_cache, lru_cache.clone());
+
+    let statuses = join_all(
+        [100, 102].map(|slot| confirm_with_commitment(&app_state, slot, Commitment::Confirmed)),
+    )
+    .await;
+
+    assert_eq!(
+        statuses,
+        vec![ConfirmationStatus::Error, ConfirmationStatus::Error]
+    );
+    assert!(!lru_cache.get(&100).await);
+}
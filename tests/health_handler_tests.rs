@@ -0,0 +1,169 @@
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use mockall::mock;
+use serde_json::Value;
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    handler::health_handler::{liveness_handler, readiness_handler},
+    metrics::Metrics,
+    rpc::RpcApi,
+    service::health_service::HealthSettings,
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn create_test_app_state() -> AppState {
+    AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(MockMetrics::new()),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+// A warmed up instance whose last poll just succeeded
+fn create_healthy_app_state() -> AppState {
+    let app_state = create_test_app_state();
+    app_state.poller.mark_warmed_up();
+    app_state.poller.mark_polled();
+    app_state.poller.set_upstream_reachable(true);
+    app_state.poller.set_lag(Commitment::Confirmed, 3);
+    app_state
+}
+
+async fn read_json(response: axum::response::Response) -> (StatusCode, Value) {
+    let status = response.status();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&body_bytes).unwrap())
+}
+
+#[tokio::test]
+async fn test_readiness_handler_fails_until_warmed_up() {
+    let app_state = create_test_app_state();
+    app_state.poller.mark_polled();
+
+    let response = readiness_handler(State(app_state.clone()))
+        .await
+        .into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    app_state.poller.mark_warmed_up();
+
+    let response = readiness_handler(State(app_state)).await.into_response();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_readiness_handler_reports_health_signals() {
+    let app_state = create_healthy_app_state();
+    app_state.cache.insert(100).await;
+    app_state.lru_cache.put(50).await;
+
+    let (status, body) = read_json(readiness_handler(State(app_state)).await.into_response()).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "ready");
+    assert_eq!(body["failures"], serde_json::json!([]));
+    assert_eq!(body["warmed_up"], true);
+    assert!(body["last_poll_age_ms"].as_u64().is_some());
+    assert_eq!(body["poller_lag_slots"]["confirmed"], 3);
+    assert_eq!(body["circuit_breaker"], "closed");
+    assert_eq!(body["upstream_reachable"], true);
+    assert_eq!(
+        body["caches"],
+        serde_json::json!({"primary": {"len": 1, "capacity": 10}, "lru": {"len": 1, "capacity": 10}})
+    );
+}
+
+#[tokio::test]
+async fn test_readiness_handler_fails_when_lag_exceeds_threshold() {
+    let app_state = create_healthy_app_state().with_health_settings(HealthSettings {
+        max_poller_lag: 2,
+        ..HealthSettings::default()
+    });
+
+    let (status, body) = read_json(readiness_handler(State(app_state)).await.into_response()).await;
+
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(body["status"], "not_ready");
+    assert_eq!(
+        body["failures"],
+        serde_json::json!(["confirmed poller lag of 3 slots exceeds 2"])
+    );
+}
+
+#[tokio::test]
+async fn test_readiness_handler_fails_when_poller_is_stuck() {
+    let app_state = create_healthy_app_state().with_health_settings(HealthSettings {
+        max_poll_age: Duration::from_millis(10),
+        ..HealthSettings::default()
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+
+    let response = readiness_handler(State(app_state)).await.into_response();
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_readiness_handler_follows_open_circuit_setting() {
+    let app_state = create_healthy_app_state();
+    let trip = || async { Err::<(), _>("boom") };
+    for _ in 0..3 {
+        let _ = app_state.circuit_breaker.execute(trip).await;
+    }
+
+    // Reported, but still ready by default
+    let (status, body) = read_json(
+        readiness_handler(State(app_state.clone()))
+            .await
+            .into_response(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["circuit_breaker"], "open");
+
+    let app_state = app_state.with_health_settings(HealthSettings {
+        fail_on_open_circuit: true,
+        ..HealthSettings::default()
+    });
+    let response = readiness_handler(State(app_state)).await.into_response();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_liveness_handler_succeeds_while_not_ready() {
+    let app_state = create_test_app_state();
+
+    let (status, body) = read_json(liveness_handler(State(app_state)).await.into_response()).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["status"], "alive");
+    assert_eq!(body["warmed_up"], false);
+    assert_eq!(body["last_poll_age_ms"], Value::Null);
+}
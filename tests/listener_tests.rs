@@ -0,0 +1,218 @@
+use axum::{Router, routing::get};
+use rcgen::{CertifiedKey, generate_simple_self_signed};
+use rustls::{ClientConfig, RootCertStore};
+use rustls_pki_types::ServerName;
+use solana_caching_service::listener::{
+    ListenerSettings, ReloadableTls, TlsListener, TlsSettings, serve,
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpListener,
+    sync::broadcast,
+};
+use tokio_rustls::TlsConnector;
+
+fn create_test_router() -> Router {
+    Router::new().route("/", get(|| async { "pong" }))
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("{}-{}", std::process::id(), name))
+}
+
+// Writes a new self-signed certificate for localhost, with a modification time after the last one
+fn write_certificate(cert_path: &Path, key_path: &Path, modified: SystemTime) -> CertifiedKey {
+    let certified = generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    fs::write(cert_path, certified.cert.pem()).unwrap();
+    fs::write(key_path, certified.key_pair.serialize_pem()).unwrap();
+    for path in [cert_path, key_path] {
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(modified).unwrap();
+    }
+    certified
+}
+
+async fn get_root<S: AsyncRead + AsyncWrite + Unpin>(mut stream: S) -> String {
+    stream
+        .write_all(b"GET / HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+async fn get_root_over_tls(port: u16, trusted: &CertifiedKey) -> std::io::Result<String> {
+    let mut roots = RootCertStore::empty();
+    roots.add(trusted.cert.der().clone()).unwrap();
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .unwrap()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    let stream = tokio::net::TcpStream::connect(("127.0.0.1", port)).await?;
+    let stream = TlsConnector::from(Arc::new(config))
+        .connect(ServerName::try_from("localhost").unwrap(), stream)
+        .await?;
+    Ok(get_root(stream).await)
+}
+
+#[tokio::test]
+async fn test_tls_listener_serves_and_reloads_certificate() {
+    let cert_path = temp_path("reload-cert.pem");
+    let key_path = temp_path("reload-key.pem");
+    let start = SystemTime::now();
+    let first = write_certificate(&cert_path, &key_path, start);
+
+    let tls = Arc::new(
+        ReloadableTls::load(TlsSettings {
+            cert_path: cert_path.clone(),
+            key_path: key_path.clone(),
+            reload_interval: Duration::from_secs(30),
+        })
+        .unwrap(),
+    );
+    let tcp = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = tcp.local_addr().unwrap().port();
+    let listener = TlsListener::new(tcp, tls.clone()).unwrap();
+    tokio::spawn(async move { axum::serve(listener, create_test_router()).await });
+
+    assert!(
+        get_root_over_tls(port, &first)
+            .await
+            .unwrap()
+            .ends_with("pong")
+    );
+    assert!(!tls.reload_if_changed().unwrap());
+
+    let second = write_certificate(&cert_path, &key_path, start + Duration::from_secs(60));
+    assert!(tls.reload_if_changed().unwrap());
+    assert!(get_root_over_tls(port, &first).await.is_err());
+    assert!(
+        get_root_over_tls(port, &second)
+            .await
+            .unwrap()
+            .ends_with("pong")
+    );
+
+    // A broken key is rejected and the last good certificate stays in use
+    fs::write(&key_path, "not a key").unwrap();
+    assert!(tls.reload_if_changed().is_err());
+    assert!(
+        get_root_over_tls(port, &second)
+            .await
+            .unwrap()
+            .ends_with("pong")
+    );
+
+    fs::remove_file(cert_path).unwrap();
+    fs::remove_file(key_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_load_rejects_missing_certificate() {
+    let result = ReloadableTls::load(TlsSettings {
+        cert_path: temp_path("missing-cert.pem"),
+        key_path: temp_path("missing-key.pem"),
+        reload_interval: Duration::from_secs(30),
+    });
+
+    assert!(result.is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_serve_listens_on_unix_socket_and_removes_it_on_shutdown() {
+    let socket_path = temp_path("service.sock");
+    // A socket left behind by a previous run is replaced
+    let _ = fs::remove_file(&socket_path);
+    drop(std::os::unix::net::UnixListener::bind(&socket_path).unwrap());
+    let settings = ListenerSettings {
+        bind_address: "127.0.0.1:0".parse().unwrap(),
+        tls: None,
+        unix_socket_path: Some(socket_path.clone()),
+    };
+    let (shutdown_tx, _) = broadcast::channel(1);
+
+    let server = {
+        let shutdown_tx = shutdown_tx.clone();
+        tokio::spawn(async move { serve(create_test_router(), &settings, &shutdown_tx).await })
+    };
+
+    let mut stream = None;
+    for _ in 0..50 {
+        if let Ok(connected) = tokio::net::UnixStream::connect(&socket_path).await {
+            stream = Some(connected);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let response = get_root(stream.expect("Unix socket not listening")).await;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert!(response.ends_with("pong"));
+
+    shutdown_tx.send(()).unwrap();
+    server.await.unwrap().unwrap();
+    assert!(!socket_path.exists());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_serve_refuses_to_replace_a_file_that_is_not_a_socket() {
+    let socket_path = temp_path("not-a-socket.sock");
+    fs::write(&socket_path, "keep me").unwrap();
+    let settings = ListenerSettings {
+        bind_address: "127.0.0.1:0".parse().unwrap(),
+        tls: None,
+        unix_socket_path: Some(socket_path.clone()),
+    };
+    let (shutdown_tx, _) = broadcast::channel(1);
+
+    let error = serve(create_test_router(), &settings, &shutdown_tx)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read_to_string(&socket_path).unwrap(), "keep me");
+    let _ = shutdown_tx.send(());
+    fs::remove_file(&socket_path).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_serve_refuses_a_socket_another_process_listens_on() {
+    let socket_path = temp_path("in-use.sock");
+    let _ = fs::remove_file(&socket_path);
+    let running = std::os::unix::net::UnixListener::bind(&socket_path).unwrap();
+    // Nothing may keep listening on the TCP port once the start failed
+    let bind_address = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let settings = ListenerSettings {
+        bind_address,
+        tls: None,
+        unix_socket_path: Some(socket_path.clone()),
+    };
+    let (shutdown_tx, _) = broadcast::channel(1);
+
+    let error = serve(create_test_router(), &settings, &shutdown_tx)
+        .await
+        .unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::AddrInUse);
+    assert!(
+        std::os::unix::net::UnixStream::connect(&socket_path).is_ok(),
+        "The running instance keeps its socket"
+    );
+    assert!(std::net::TcpListener::bind(bind_address).is_ok());
+    drop(running);
+    fs::remove_file(&socket_path).unwrap();
+}
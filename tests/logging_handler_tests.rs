@@ -0,0 +1,107 @@
+use axum::{Json, extract::State, http::StatusCode, response::Response};
+use mockall::mock;
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    handler::logging_handler::{get_log_filter_handler, set_log_filter_handler},
+    logging::LogLevels,
+    metrics::LoggingMetrics,
+    rpc::RpcApi,
+    service::logging_service::LogFilter,
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use tracing_subscriber::{EnvFilter, Layer, fmt, layer::SubscriberExt, reload};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+fn create_test_app_state() -> AppState {
+    AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(LoggingMetrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+async fn read_body(response: Response) -> (StatusCode, String) {
+    let status = response.status();
+    let body_bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body_bytes.to_vec()).unwrap())
+}
+
+fn filter(directives: &str) -> Json<LogFilter> {
+    Json(LogFilter {
+        filter: directives.to_string(),
+    })
+}
+
+#[tokio::test]
+async fn test_log_filter_can_be_changed_at_runtime() {
+    let (reload_filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+    let _subscriber = tracing_subscriber::registry().with(fmt::layer().with_filter(reload_filter));
+    let app_state =
+        create_test_app_state().with_log_levels(Arc::new(LogLevels::new(handle, "info")));
+
+    let (status, body) = read_body(
+        set_log_filter_handler(
+            State(app_state.clone()),
+            filter("warn,solana_caching_service=debug"),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, r#"{"filter":"warn,solana_caching_service=debug"}"#);
+
+    let (status, body) = read_body(get_log_filter_handler(State(app_state)).await).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, r#"{"filter":"warn,solana_caching_service=debug"}"#);
+}
+
+#[tokio::test]
+async fn test_invalid_log_filter_is_rejected() {
+    let (reload_filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+    let _subscriber = tracing_subscriber::registry().with(fmt::layer().with_filter(reload_filter));
+    let app_state =
+        create_test_app_state().with_log_levels(Arc::new(LogLevels::new(handle, "info")));
+
+    let (status, _) = read_body(
+        set_log_filter_handler(
+            State(app_state.clone()),
+            filter("info,solana_caching_service=loud"),
+        )
+        .await,
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = read_body(get_log_filter_handler(State(app_state)).await).await;
+    assert_eq!(body, r#"{"filter":"info"}"#);
+}
+
+#[tokio::test]
+async fn test_log_filter_not_found_without_reloadable_logging() {
+    let app_state = create_test_app_state();
+
+    let (status, _) = read_body(get_log_filter_handler(State(app_state.clone())).await).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) =
+        read_body(set_log_filter_handler(State(app_state), filter("debug")).await).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,92 @@
+use solana_caching_service::{
+    logging::{LogFormat, LogLevels, LogSampler, RedactingMakeWriter, parse_log_filter},
+    utils::redact::Redactor,
+};
+use std::{
+    io::Write,
+    sync::{Arc, Mutex},
+};
+use tracing_subscriber::{EnvFilter, Layer, fmt, layer::SubscriberExt, reload};
+
+#[test]
+fn test_log_format_from_str() {
+    assert_eq!("text".parse::<LogFormat>(), Ok(LogFormat::Text));
+    assert_eq!(" JSON ".parse::<LogFormat>(), Ok(LogFormat::Json));
+    assert!("yaml".parse::<LogFormat>().is_err());
+}
+
+#[test]
+fn test_parse_log_filter_accepts_per_target_levels() {
+    assert!(parse_log_filter("info,solana_caching_service::service::slot_poller=warn").is_ok());
+    assert!(parse_log_filter("info,solana_caching_service=loud").is_err());
+}
+
+#[test]
+fn test_log_sampler_lets_one_in_every_n_through() {
+    let sampler = LogSampler::new();
+
+    let sampled: Vec<Option<u64>> = (0..7).map(|_| sampler.sample(3)).collect();
+
+    assert_eq!(
+        sampled,
+        vec![Some(0), None, None, Some(2), None, None, Some(2)]
+    );
+}
+
+#[test]
+fn test_log_sampler_without_sampling_lets_all_through() {
+    let sampler = LogSampler::new();
+
+    assert!((0..5).all(|_| sampler.sample(1) == Some(0)));
+    assert!((0..5).all(|_| sampler.sample(0) == Some(0)));
+}
+
+#[test]
+fn test_log_levels_keep_previous_filter_on_invalid_directives() {
+    let (filter, handle) = reload::Layer::new(EnvFilter::new("info"));
+    let _subscriber = tracing_subscriber::registry().with(fmt::layer().with_filter(filter));
+    let log_levels = LogLevels::new(handle, "info");
+
+    assert!(log_levels.set("warn,solana_caching_service=debug").is_ok());
+    assert_eq!(log_levels.current(), "warn,solana_caching_service=debug");
+
+    assert!(log_levels.set("warn,solana_caching_service=loud").is_err());
+    assert_eq!(log_levels.current(), "warn,solana_caching_service=debug");
+}
+
+#[test]
+fn test_redacting_writer_scrubs_secrets_from_log_lines() {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let writer = {
+        let output = output.clone();
+        move || SharedBuffer(output.clone())
+    };
+    let redactor = Arc::new(Redactor::new(["provider-secret-key".to_string()]));
+    let subscriber = tracing_subscriber::registry()
+        .with(fmt::layer().with_writer(RedactingMakeWriter::new(writer, redactor)));
+
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::warn!(
+            "Request to https://rpc.example.com/provider-secret-key failed: {}",
+            "error sending request for url (https://rpc.example.com/provider-secret-key)"
+        );
+    });
+
+    let logged = String::from_utf8(output.lock().unwrap().clone()).unwrap();
+    assert!(!logged.contains("provider-secret-key"));
+    assert!(logged.contains("https://rpc.example.com/<redacted> failed"));
+    assert_eq!(logged.matches("<redacted>").count(), 2);
+}
+
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
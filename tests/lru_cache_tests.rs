@@ -1,4 +1,8 @@
-use solana_caching_service::cache::LruCache;
+use solana_caching_service::{
+    cache::LruCache,
+    commitment::Commitment,
+    slot_info::{BlockMetadata, SlotInfo},
+};
 
 #[tokio::test]
 async fn test_lru_put_and_get() {
@@ -88,3 +92,106 @@ async fn test_get_all_slots_returns_correct_order() {
 
     assert_eq!(cache.get_all_slots().await, vec![2, 3, 1]);
 }
+
+#[tokio::test]
+async fn test_lru_records_highest_commitment() {
+    let cache = LruCache::new(3);
+
+    cache.put_with_commitment(1, Commitment::Confirmed).await;
+    assert_eq!(cache.get_commitment(&1).await, Some(Commitment::Confirmed));
+
+    cache.put_with_commitment(1, Commitment::Finalized).await;
+    assert_eq!(cache.get_commitment(&1).await, Some(Commitment::Finalized));
+
+    cache.put(1).await;
+    assert_eq!(cache.get_commitment(&1).await, Some(Commitment::Finalized));
+    assert!(cache.get(&1).await);
+
+    cache.put_with_commitment(2, Commitment::Processed).await;
+    assert!(!cache.get(&2).await);
+}
+
+#[tokio::test]
+async fn test_lru_remembers_skipped_slots() {
+    let cache = LruCache::new(3);
+
+    cache.put_skipped(1, Commitment::Confirmed).await;
+    cache.put(2).await;
+
+    assert!(cache.is_skipped_at(&1, Commitment::Confirmed).await);
+    assert!(!cache.is_skipped_at(&1, Commitment::Finalized).await);
+    assert!(!cache.get(&1).await);
+    assert_eq!(cache.get_commitment(&1).await, None);
+    assert_eq!(cache.get_all_slots().await, vec![2]);
+
+    // A confirmed block is never downgraded by a skip observation
+    cache.put_skipped(2, Commitment::Confirmed).await;
+    assert!(cache.get(&2).await);
+
+    // An observed block replaces the skip observation
+    cache.put(1).await;
+    assert!(cache.get(&1).await);
+    assert!(!cache.is_skipped_at(&1, Commitment::Confirmed).await);
+}
+
+#[tokio::test]
+async fn test_lru_merges_slot_info() {
+    let cache = LruCache::new(3);
+    let block = BlockMetadata {
+        blockhash: "hash".to_string(),
+        parent_slot: 9,
+        block_height: Some(5),
+        block_time: None,
+    };
+
+    cache
+        .put_info(SlotInfo::new(10, Commitment::Confirmed).with_block(block.clone()))
+        .await;
+    cache.put_with_commitment(10, Commitment::Finalized).await;
+
+    let info = cache.get_info(&10).await.unwrap();
+    assert_eq!(info.commitment, Commitment::Finalized);
+    assert_eq!(info.block, Some(block));
+    assert_eq!(cache.get_info(&11).await, None);
+}
+
+#[tokio::test]
+async fn test_lru_pinned_keys_are_not_evicted() {
+    let cache = LruCache::new(2);
+
+    cache.put(1).await;
+    assert!(cache.pin(1).await);
+    assert!(!cache.pin(99).await, "Only cached keys can be pinned");
+    cache.put(2).await;
+    cache.put(3).await;
+
+    assert!(cache.get(&1).await, "Pinned key should survive eviction");
+    assert!(!cache.get(&2).await);
+    assert_eq!(cache.pinned().await, vec![1]);
+
+    assert!(cache.unpin(&1).await);
+    assert!(!cache.unpin(&1).await);
+    cache.put(4).await;
+    cache.put(5).await;
+    assert!(
+        !cache.get(&1).await,
+        "Unpinned key should be evictable again"
+    );
+}
+
+#[tokio::test]
+async fn test_lru_resize_clear_and_remove() {
+    let cache = LruCache::new(4);
+    for key in 1..=4 {
+        cache.put(key).await;
+    }
+
+    assert_eq!(cache.resize(2).await, 2);
+    assert_eq!(cache.capacity().await, 2);
+    assert_eq!(cache.get_all_slots().await, vec![4, 3]);
+
+    assert!(cache.remove(&4).await);
+    assert!(!cache.remove(&4).await);
+    assert_eq!(cache.clear().await, 1);
+    assert!(cache.is_empty().await);
+}
@@ -0,0 +1,77 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use mockall::mock;
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    metrics::{LoggingMetrics, Metrics, PrometheusMetrics},
+    routes::create_router,
+    rpc::RpcApi,
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use tower::ServiceExt;
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+fn create_test_app_state(metrics: Arc<dyn Metrics + Send + Sync>) -> AppState {
+    AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        metrics,
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+async fn get(app_state: &AppState, uri: &str) -> (StatusCode, String) {
+    let response = create_router(app_state.clone())
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint_exposes_requests_and_cache_lookups() {
+    let app_state = create_test_app_state(Arc::new(PrometheusMetrics::new()));
+    app_state.cache.insert(100).await;
+
+    let (status, _) = get(&app_state, "/isSlotConfirmed/100").await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = get(&app_state, "/metrics").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(
+        body.contains("http_requests_total{route=\"/isSlotConfirmed/{slot}\",status=\"200\"} 1")
+    );
+    assert!(body.contains("cache_lookups_total{result=\"hit\",tier=\"primary\"} 1"));
+    assert!(body.contains("is_slot_confirmed_duration_seconds_count 1"));
+    assert!(body.contains("circuit_breaker_state{state=\"closed\"} 1"));
+}
+
+#[tokio::test]
+async fn test_metrics_endpoint_is_absent_with_logging_backend() {
+    let app_state = create_test_app_state(Arc::new(LoggingMetrics));
+
+    let (status, _) = get(&app_state, "/metrics").await;
+
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
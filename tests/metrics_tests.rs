@@ -0,0 +1,57 @@
+use solana_caching_service::{
+    circuit_breaker::CircuitState,
+    commitment::Commitment,
+    metrics::{CacheTier, LoggingMetrics, Metrics, MetricsBackend, PrometheusMetrics},
+};
+use std::time::Duration;
+
+#[test]
+fn test_logging_metrics_are_not_rendered() {
+    assert_eq!(LoggingMetrics.render(), None);
+}
+
+#[test]
+fn test_prometheus_metrics_render_recorded_values() {
+    let metrics = PrometheusMetrics::new();
+
+    metrics.record_latest_slot(1234);
+    metrics.record_get_blocks_elapsed(Duration::from_millis(30));
+    metrics.record_is_slot_confirmed_elapsed(Duration::from_micros(200));
+    metrics.record_cache_lookup(CacheTier::Primary, true);
+    metrics.record_cache_lookup(CacheTier::Lru, false);
+    metrics.record_cache_lookup(CacheTier::Lru, false);
+    metrics.record_retries("get_blocks", 2);
+    metrics.record_poller_lag(Commitment::Finalized, 32);
+    metrics.record_http_request("/slot/{slot}", 404);
+
+    let rendered = metrics.render().unwrap();
+
+    assert!(rendered.contains("latest_slot 1234"));
+    assert!(rendered.contains("get_blocks_duration_seconds_count 1"));
+    assert!(rendered.contains("is_slot_confirmed_duration_seconds_bucket{le=\"0.0004\"} 1"));
+    assert!(rendered.contains("cache_lookups_total{result=\"hit\",tier=\"primary\"} 1"));
+    assert!(rendered.contains("cache_lookups_total{result=\"miss\",tier=\"lru\"} 2"));
+    assert!(rendered.contains("rpc_retries_total{operation=\"get_blocks\"} 2"));
+    assert!(rendered.contains("poller_lag_slots{commitment=\"finalized\"} 32"));
+    assert!(rendered.contains("http_requests_total{route=\"/slot/{slot}\",status=\"404\"} 1"));
+}
+
+#[test]
+fn test_prometheus_circuit_state_marks_only_the_current_state() {
+    let metrics = PrometheusMetrics::new();
+
+    metrics.record_circuit_state(CircuitState::Closed);
+    metrics.record_circuit_state(CircuitState::Open);
+    let rendered = metrics.render().unwrap();
+
+    assert!(rendered.contains("circuit_breaker_state{state=\"open\"} 1"));
+    assert!(rendered.contains("circuit_breaker_state{state=\"closed\"} 0"));
+    assert!(rendered.contains("circuit_breaker_state{state=\"half_open\"} 0"));
+}
+
+#[test]
+fn test_metrics_backend_from_str() {
+    assert_eq!("Prometheus".parse(), Ok(MetricsBackend::Prometheus));
+    assert_eq!("logging".parse(), Ok(MetricsBackend::Logging));
+    assert!("statsd".parse::<MetricsBackend>().is_err());
+}
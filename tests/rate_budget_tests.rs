@@ -0,0 +1,25 @@
+use solana_caching_service::utils::rate_budget::RateBudget;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn test_first_acquire_is_immediate() {
+    let budget = RateBudget::new(1);
+
+    let started = Instant::now();
+    budget.acquire().await;
+
+    assert!(started.elapsed() < Duration::from_millis(50));
+}
+
+#[tokio::test]
+async fn test_acquires_are_spaced_by_the_rate() {
+    let budget = RateBudget::new(20);
+
+    let started = Instant::now();
+    for _ in 0..5 {
+        budget.acquire().await;
+    }
+
+    // Four gaps of 50ms after the immediate first call
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
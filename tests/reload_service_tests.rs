@@ -0,0 +1,276 @@
+use mockall::mock;
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    config::{Config, sources::ConfigSources},
+    metrics::LoggingMetrics,
+    rpc::RpcApi,
+    service::client_service::{Admission, ClientRegistry, ClientSettings},
+    service::health_service::is_ready,
+    service::reload_service::{ConfigReload, ConfigReloader, ReloadReport, reload_config},
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{fs, future::Future, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+const BASE: &str = "SOLANA_RPC_URL=http://example.com/\nAPI_KEY=12345\n";
+
+fn create_test_app_state(file_path: &str, content: &str) -> AppState {
+    fs::write(file_path, format!("{}{}", BASE, content)).unwrap();
+    let config = Config::from_env_file(file_path).unwrap();
+    AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(config.cache_capacity)),
+        Arc::new(LruCache::new(config.lru_cache_capacity)),
+        Arc::new(LoggingMetrics),
+        Arc::new(CircuitBreaker::new(
+            config.circuit_failure_threshold,
+            config.circuit_open_duration,
+        )),
+    )
+    .with_config_reloader(Arc::new(ConfigReloader::new(
+        ConfigSources::from_env_file(file_path),
+        config,
+    )))
+}
+
+#[tokio::test]
+async fn test_reload_applies_capacities_and_thresholds_keeping_cached_slots() {
+    let file_path = "test_reload_applies.env";
+    let app_state = create_test_app_state(
+        file_path,
+        "CACHE_CAPACITY=5\nLRU_CACHE_CAPACITY=5\nCIRCUIT_FAILURE_THRESHOLD=3",
+    );
+    for slot in 1..=5 {
+        app_state.cache.insert(slot).await;
+        app_state.lru_cache.put(slot).await;
+    }
+
+    fs::write(
+        file_path,
+        format!(
+            "{}CACHE_CAPACITY=10\nLRU_CACHE_CAPACITY=3\nCIRCUIT_FAILURE_THRESHOLD=7\nCIRCUIT_OPEN_DURATION_SECS=45",
+            BASE
+        ),
+    )
+    .unwrap();
+    let reload = reload_config(&app_state).await;
+
+    assert_eq!(
+        reload,
+        ConfigReload::Applied(ReloadReport {
+            applied: vec![
+                "CIRCUIT_FAILURE_THRESHOLD".to_string(),
+                "CIRCUIT_OPEN_DURATION_SECS".to_string(),
+                "CACHE_CAPACITY".to_string(),
+                "LRU_CACHE_CAPACITY".to_string(),
+            ],
+            restart_required: false,
+        })
+    );
+    assert_eq!(app_state.cache.capacity(), 10);
+    assert_eq!(app_state.cache.len().await, 5, "Cached slots are kept");
+    assert_eq!(app_state.lru_cache.get_all_slots().await, vec![5, 4, 3]);
+    assert_eq!(app_state.circuit_breaker.failure_threshold(), 7);
+    assert_eq!(
+        app_state.circuit_breaker.open_duration(),
+        Duration::from_secs(45)
+    );
+    let current = app_state.config_reloader.as_ref().unwrap().current().await;
+    assert_eq!(current.cache_capacity, 10);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_rejects_retries_exceeding_poll_interval() {
+    let file_path = "test_reload_rejects.env";
+    let app_state = create_test_app_state(file_path, "CACHE_CAPACITY=5");
+
+    fs::write(
+        file_path,
+        format!(
+            "{}CACHE_CAPACITY=50\nPOLL_INTERVAL_SECONDS=1\nMAX_RETRIES=5\nINITIAL_BACKOFF_MS=500",
+            BASE
+        ),
+    )
+    .unwrap();
+    let reload = reload_config(&app_state).await;
+
+    assert!(matches!(reload, ConfigReload::Rejected(message) if message.contains("MAX_RETRIES")));
+    assert_eq!(
+        app_state.cache.capacity(),
+        5,
+        "Running config stays in effect"
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_reports_settings_needing_restart() {
+    let file_path = "test_reload_restart.env";
+    let app_state = create_test_app_state(file_path, "POLL_INTERVAL_SECONDS=15");
+
+    fs::write(
+        file_path,
+        format!(
+            "{}POLL_INTERVAL_SECONDS=20\nREDIS_URL=redis://cache:6379",
+            BASE
+        ),
+    )
+    .unwrap();
+    let reload = reload_config(&app_state).await;
+
+    assert_eq!(
+        reload,
+        ConfigReload::Applied(ReloadReport {
+            applied: vec!["POLL_INTERVAL_SECONDS".to_string()],
+            restart_required: true,
+        })
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_settings_needing_restart_keep_their_running_value() {
+    let file_path = "test_reload_running_value.env";
+    let app_state = create_test_app_state(file_path, "POLL_BACKFILL_CHUNK_SLOTS=1000");
+
+    fs::write(
+        file_path,
+        format!("{}POLL_BACKFILL_CHUNK_SLOTS=10\nCACHE_CAPACITY=50", BASE),
+    )
+    .unwrap();
+    reload_config(&app_state).await;
+    let reloader = app_state.config_reloader.as_ref().unwrap();
+    let current = reloader.current().await;
+    assert_eq!(current.cache_capacity, 50);
+    assert_eq!(current.poll_backfill_chunk_size, 1000, "Not applied");
+
+    // Still pending on the next reload, which compares against the running value
+    fs::write(
+        file_path,
+        format!(
+            "{}POLL_BACKFILL_CHUNK_SLOTS=10\nCACHE_CAPACITY=50\nMAX_RETRIES=2",
+            BASE
+        ),
+    )
+    .unwrap();
+    let reload = reload_config(&app_state).await;
+
+    assert_eq!(
+        reload,
+        ConfigReload::Applied(ReloadReport {
+            applied: vec!["MAX_RETRIES".to_string()],
+            restart_required: true,
+        })
+    );
+    assert_eq!(reloader.current().await.poll_backfill_chunk_size, 1000);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_applies_readiness_thresholds_with_poll_interval() {
+    let file_path = "test_reload_health.env";
+    let app_state = create_test_app_state(file_path, "POLL_INTERVAL_SECONDS=15");
+    app_state.poller.mark_warmed_up();
+    app_state.poller.mark_polled();
+
+    fs::write(
+        file_path,
+        format!(
+            "{}POLL_INTERVAL_SECONDS=90\nHEALTH_MAX_POLL_AGE_SECS=120\nHEALTH_FAIL_ON_OPEN_CIRCUIT=true",
+            BASE
+        ),
+    )
+    .unwrap();
+    let reload = reload_config(&app_state).await;
+
+    assert_eq!(
+        reload,
+        ConfigReload::Applied(ReloadReport {
+            applied: vec![
+                "POLL_INTERVAL_SECONDS".to_string(),
+                "HEALTH_MAX_POLL_AGE_SECS".to_string(),
+                "HEALTH_FAIL_ON_OPEN_CIRCUIT".to_string(),
+            ],
+            restart_required: false,
+        })
+    );
+    let health = app_state.health.read().unwrap().clone();
+    assert_eq!(health.max_poll_age, Duration::from_secs(120));
+    assert!(health.fail_on_open_circuit);
+    assert!(is_ready(&app_state).await);
+
+    fs::remove_file(file_path).unwrap();
+}
+
+#[tokio::test]
+async fn test_reload_is_unavailable_without_config_source() {
+    let app_state = AppState::new(
+        Arc::new(MockRpcApi::new()),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(LoggingMetrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+
+    assert_eq!(reload_config(&app_state).await, ConfigReload::Unavailable);
+}
+
+#[tokio::test]
+async fn test_reload_applies_client_keys() {
+    let file_path = "test_reload_client_keys.env";
+    let app_state = create_test_app_state(file_path, "CLIENT_API_KEYS=partner:old-key");
+    let config = app_state.config_reloader.as_ref().unwrap().current().await;
+    let registry =
+        Arc::new(ClientRegistry::load(ClientSettings::from_config(&config).unwrap()).unwrap());
+    let app_state = app_state.with_client_registry(registry.clone());
+
+    fs::write(
+        file_path,
+        format!(
+            "{}CLIENT_API_KEYS=partner:new-key\nCLIENT_REQUEST_QUOTA=100",
+            BASE
+        ),
+    )
+    .unwrap();
+    let reload = reload_config(&app_state).await;
+
+    assert_eq!(
+        reload,
+        ConfigReload::Applied(ReloadReport {
+            applied: vec![
+                "CLIENT_API_KEYS".to_string(),
+                "CLIENT_REQUEST_QUOTA".to_string(),
+            ],
+            restart_required: false,
+        })
+    );
+    assert_eq!(registry.admit(Some("old-key")), Admission::Unknown);
+    assert!(matches!(
+        registry.admit(Some("new-key")),
+        Admission::Admitted(_)
+    ));
+    assert_eq!(
+        registry.usage_of("partner").unwrap().limits.request_quota,
+        100
+    );
+
+    fs::remove_file(file_path).unwrap();
+}
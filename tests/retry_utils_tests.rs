@@ -56,10 +56,10 @@ async fn test_retry_fails_after_all_attempts() {
     let operation = || {
         let attempts_clone = attempts.clone();
         async move {
-            let _ = {
+            {
                 let mut num = attempts_clone.lock().unwrap();
                 *num += 1;
-            };
+            }
             Err("Always fails")
         }
     };
@@ -83,9 +83,7 @@ async fn test_transient_retry_fails_immediately_on_non_transient_error() {
     let operation = || {
         let attempts_clone = attempts.clone();
         async move {
-            let _ = {
-                *attempts_clone.lock().unwrap() += 1;
-            };
+            *attempts_clone.lock().unwrap() += 1;
             Err(TestError {
                 message: "Permanent failure".into(),
                 is_transient: false,
@@ -0,0 +1,164 @@
+use mockall::{mock, predicate::*};
+use solana_caching_service::rpc::{RpcApi, RpcPool};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use std::{future::Future, io, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+fn transient_error() -> ClientError {
+    ClientError {
+        kind: ClientErrorKind::Io(io::Error::new(io::ErrorKind::TimedOut, "test timeout")),
+        request: None,
+    }
+}
+
+fn create_pool(primary: MockRpcApi, fallback: MockRpcApi) -> RpcPool {
+    RpcPool::new(vec![
+        ("primary".to_string(), Arc::new(primary)),
+        ("fallback".to_string(), Arc::new(fallback)),
+    ])
+}
+
+#[tokio::test]
+async fn test_pool_prefers_primary_when_equally_healthy() {
+    let mut primary = MockRpcApi::new();
+    let mut fallback = MockRpcApi::new();
+
+    primary
+        .expect_get_slot()
+        .times(1)
+        .returning(|| Box::pin(async { Ok(100) }));
+    fallback.expect_get_slot().times(0);
+
+    let pool = create_pool(primary, fallback);
+
+    assert_eq!(pool.get_slot().await.unwrap(), 100);
+}
+
+#[tokio::test]
+async fn test_pool_fails_over_on_transient_error() {
+    let mut primary = MockRpcApi::new();
+    let mut fallback = MockRpcApi::new();
+
+    primary
+        .expect_get_blocks()
+        .with(eq(10), eq(Some(20)))
+        .times(1)
+        .returning(|_, _| Box::pin(async { Err(transient_error()) }));
+    fallback
+        .expect_get_blocks()
+        .with(eq(10), eq(Some(20)))
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(vec![10, 12, 20]) }));
+
+    let pool = create_pool(primary, fallback);
+
+    assert_eq!(
+        pool.get_blocks(10, Some(20)).await.unwrap(),
+        vec![10, 12, 20]
+    );
+}
+
+#[tokio::test]
+async fn test_pool_does_not_fail_over_on_non_transient_error() {
+    let mut primary = MockRpcApi::new();
+    let mut fallback = MockRpcApi::new();
+
+    primary.expect_get_slot().times(1).returning(|| {
+        let err = ClientError {
+            kind: ClientErrorKind::Custom("Invalid request".into()),
+            request: None,
+        };
+        Box::pin(async { Err(err) })
+    });
+    fallback.expect_get_slot().times(0);
+
+    let pool = create_pool(primary, fallback);
+
+    let result = pool.get_slot().await;
+    assert!(matches!(
+        result.err().unwrap().kind,
+        ClientErrorKind::Custom(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_pool_returns_last_error_when_all_endpoints_fail() {
+    let mut primary = MockRpcApi::new();
+    let mut fallback = MockRpcApi::new();
+
+    primary
+        .expect_get_slot()
+        .times(1)
+        .returning(|| Box::pin(async { Err(transient_error()) }));
+    fallback
+        .expect_get_slot()
+        .times(1)
+        .returning(|| Box::pin(async { Err(transient_error()) }));
+
+    let pool = create_pool(primary, fallback);
+
+    let result = pool.get_slot().await;
+    assert!(matches!(result.err().unwrap().kind, ClientErrorKind::Io(_)));
+}
+
+#[tokio::test]
+async fn test_pool_routes_away_from_failing_endpoint() {
+    let mut primary = MockRpcApi::new();
+    let mut fallback = MockRpcApi::new();
+
+    // Only the first call reaches the primary, afterwards the fallback is healthier
+    primary
+        .expect_get_slot()
+        .times(1)
+        .returning(|| Box::pin(async { Err(transient_error()) }));
+    fallback
+        .expect_get_slot()
+        .times(3)
+        .returning(|| Box::pin(async { Ok(42) }));
+
+    let pool = create_pool(primary, fallback);
+
+    for _ in 0..3 {
+        assert_eq!(pool.get_slot().await.unwrap(), 42);
+    }
+
+    let health = pool.health_snapshot();
+    assert_eq!(health[0].name, "primary");
+    assert!(health[0].error_rate > 0.0);
+    assert_eq!(health[1].error_rate, 0.0);
+    assert!(health[1].score < health[0].score);
+}
+
+#[tokio::test]
+async fn test_pool_routes_to_lowest_latency_endpoint() {
+    let mut primary = MockRpcApi::new();
+    let mut fallback = MockRpcApi::new();
+
+    primary.expect_get_slot().returning(|| {
+        Box::pin(async {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(1)
+        })
+    });
+    fallback
+        .expect_get_slot()
+        .returning(|| Box::pin(async { Ok(2) }));
+
+    let pool = create_pool(primary, fallback).with_ewma_alpha(1.0);
+
+    // The slow primary is tried first, then the unmeasured fallback looks faster
+    assert_eq!(pool.get_slot().await.unwrap(), 1);
+    assert_eq!(pool.get_slot().await.unwrap(), 2);
+    assert_eq!(pool.get_slot().await.unwrap(), 2);
+}
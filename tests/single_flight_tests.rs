@@ -0,0 +1,83 @@
+use solana_caching_service::utils::single_flight::SingleFlight;
+use std::sync::{
+    Arc,
+    atomic::{AtomicU32, Ordering},
+};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_concurrent_calls_share_one_execution() {
+    let flight = Arc::new(SingleFlight::<u64, Result<u64, String>>::new());
+    let executions = Arc::new(AtomicU32::new(0));
+
+    let mut handles = Vec::new();
+    for _ in 0..10 {
+        let flight = flight.clone();
+        let executions = executions.clone();
+        handles.push(tokio::spawn(async move {
+            flight
+                .run(1, || async move {
+                    executions.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    Err("RPC down".to_string())
+                })
+                .await
+        }));
+    }
+
+    for handle in handles {
+        assert_eq!(handle.await.unwrap(), Err("RPC down".to_string()));
+    }
+    assert_eq!(executions.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_distinct_keys_and_sequential_calls_run_separately() {
+    let flight = SingleFlight::<u64, u64>::new();
+    let executions = AtomicU32::new(0);
+
+    let run = |key: u64| {
+        let executions = &executions;
+        flight.run(key, move || async move {
+            executions.fetch_add(1, Ordering::SeqCst);
+            key * 2
+        })
+    };
+
+    let (a, b) = tokio::join!(run(1), run(2));
+    let c = run(1).await;
+
+    assert_eq!((a, b, c), (2, 4, 2));
+    assert_eq!(executions.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_waiter_takes_over_when_leader_is_cancelled() {
+    let flight = Arc::new(SingleFlight::<u64, u64>::new());
+
+    let leader = {
+        let flight = flight.clone();
+        tokio::spawn(async move {
+            flight
+                .run(7, || async {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    1
+                })
+                .await
+        })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    let follower = {
+        let flight = flight.clone();
+        tokio::spawn(async move { flight.run(7, || async { 2 }).await })
+    };
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    leader.abort();
+
+    let value = tokio::time::timeout(Duration::from_secs(1), follower)
+        .await
+        .expect("follower should not wait for the cancelled leader")
+        .unwrap();
+    assert_eq!(value, 2);
+}
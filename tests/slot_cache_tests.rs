@@ -1,4 +1,8 @@
-use solana_caching_service::cache::SlotCache;
+use solana_caching_service::{
+    cache::SlotCache,
+    commitment::Commitment,
+    slot_info::{BlockMetadata, SlotInfo},
+};
 
 #[tokio::test]
 async fn test_insert_and_contains() {
@@ -69,3 +73,248 @@ async fn test_no_duplicate_insertion() {
     assert!(cache.contains(&3).await);
     assert!(cache.contains(&4).await);
 }
+
+#[tokio::test]
+async fn test_commitment_is_upgraded_but_never_downgraded() {
+    let cache = SlotCache::new(5);
+
+    cache
+        .insert_with_commitment(100, Commitment::Confirmed)
+        .await;
+    assert_eq!(
+        cache.get_commitment(&100).await,
+        Some(Commitment::Confirmed)
+    );
+    assert!(!cache.contains_at(&100, Commitment::Finalized).await);
+
+    cache
+        .insert_with_commitment(100, Commitment::Finalized)
+        .await;
+    assert_eq!(
+        cache.get_commitment(&100).await,
+        Some(Commitment::Finalized)
+    );
+
+    cache
+        .insert_with_commitment(100, Commitment::Processed)
+        .await;
+    assert_eq!(
+        cache.get_commitment(&100).await,
+        Some(Commitment::Finalized)
+    );
+    assert!(cache.contains(&100).await);
+    assert_eq!(cache.get_all_slots().await, vec![100]);
+}
+
+#[tokio::test]
+async fn test_processed_slots_do_not_count_as_confirmed() {
+    let cache = SlotCache::new(5);
+
+    cache
+        .insert_with_commitment(100, Commitment::Processed)
+        .await;
+
+    assert!(!cache.contains(&100).await);
+    assert!(cache.contains_at(&100, Commitment::Processed).await);
+    assert_eq!(cache.get_latest_cached_slot().await, None);
+    assert_eq!(
+        cache.get_latest_slot_at(Commitment::Processed).await,
+        Some(100)
+    );
+}
+
+#[tokio::test]
+async fn test_latest_slot_per_commitment() {
+    let cache = SlotCache::new(10);
+
+    cache
+        .insert_with_commitment(105, Commitment::Confirmed)
+        .await;
+    cache
+        .insert_with_commitment(90, Commitment::Finalized)
+        .await;
+
+    assert_eq!(cache.get_latest_cached_slot().await, Some(105));
+    assert_eq!(
+        cache.get_latest_slot_at(Commitment::Finalized).await,
+        Some(90)
+    );
+
+    assert!(cache.upgrade(105, Commitment::Finalized).await);
+    assert!(!cache.upgrade(200, Commitment::Finalized).await);
+    assert_eq!(
+        cache.get_latest_slot_at(Commitment::Finalized).await,
+        Some(105)
+    );
+}
+
+#[tokio::test]
+async fn test_skipped_slots_are_remembered_per_commitment() {
+    let cache = SlotCache::new(10);
+
+    cache.insert_skipped(101, Commitment::Confirmed).await;
+
+    assert!(cache.is_skipped_at(&101, Commitment::Confirmed).await);
+    assert!(!cache.is_skipped_at(&101, Commitment::Finalized).await);
+    assert!(!cache.contains(&101).await);
+    assert!(cache.get_all_slots().await.is_empty());
+
+    cache.insert_skipped(101, Commitment::Finalized).await;
+    assert!(cache.is_skipped_at(&101, Commitment::Finalized).await);
+}
+
+#[tokio::test]
+async fn test_block_observation_overrides_skipped_slot() {
+    let cache = SlotCache::new(10);
+
+    cache.insert_skipped(101, Commitment::Confirmed).await;
+    cache
+        .insert_with_commitment(101, Commitment::Processed)
+        .await;
+    assert!(
+        cache.is_skipped_at(&101, Commitment::Confirmed).await,
+        "A weaker observation does not override the skip"
+    );
+
+    cache.insert(101).await;
+    assert!(!cache.is_skipped_at(&101, Commitment::Confirmed).await);
+    assert!(cache.contains(&101).await);
+
+    cache.insert_skipped(101, Commitment::Confirmed).await;
+    assert!(!cache.is_skipped_at(&101, Commitment::Confirmed).await);
+}
+
+#[tokio::test]
+async fn test_skipped_slots_are_bounded_by_capacity() {
+    let cache = SlotCache::new(2);
+
+    for slot in 1..=3 {
+        cache.insert_skipped(slot, Commitment::Confirmed).await;
+    }
+
+    assert!(!cache.is_skipped_at(&1, Commitment::Confirmed).await);
+    assert!(cache.is_skipped_at(&2, Commitment::Confirmed).await);
+    assert!(cache.is_skipped_at(&3, Commitment::Confirmed).await);
+}
+
+#[tokio::test]
+async fn test_promoted_skip_frees_its_place_in_the_skip_order() {
+    let cache = SlotCache::new(3);
+    for slot in 1..=3 {
+        cache.insert_skipped(slot, Commitment::Confirmed).await;
+    }
+
+    cache.insert_with_commitment(2, Commitment::Confirmed).await;
+    cache.insert_skipped(4, Commitment::Confirmed).await;
+
+    assert_eq!(
+        cache.skipped_entries().await,
+        vec![
+            (1, Commitment::Confirmed),
+            (3, Commitment::Confirmed),
+            (4, Commitment::Confirmed),
+        ],
+        "Only real skips count against the capacity"
+    );
+    assert!(cache.is_skipped_at(&1, Commitment::Confirmed).await);
+}
+
+#[tokio::test]
+async fn test_merge_info_keeps_highest_commitment() {
+    let cache = SlotCache::new(10);
+    let block = BlockMetadata {
+        blockhash: "hash".to_string(),
+        parent_slot: 99,
+        block_height: Some(90),
+        block_time: Some(1_700_000_000),
+    };
+
+    assert!(
+        cache
+            .merge_info(SlotInfo::new(100, Commitment::Confirmed).with_block(block.clone()))
+            .await
+            .is_none(),
+        "Only cached slots are merged"
+    );
+
+    cache
+        .insert_with_commitment(100, Commitment::Finalized)
+        .await;
+    let merged = cache
+        .merge_info(SlotInfo::new(100, Commitment::Confirmed).with_block(block.clone()))
+        .await
+        .unwrap();
+
+    assert_eq!(merged.commitment, Commitment::Finalized);
+    assert_eq!(merged.block, Some(block));
+    assert_eq!(cache.get_info(&100).await, Some(merged));
+}
+
+#[tokio::test]
+async fn test_resize_evicts_oldest_slots() {
+    let cache = SlotCache::new(5);
+    for slot in 1..=5 {
+        cache.insert(slot).await;
+    }
+    cache.insert_skipped(6, Commitment::Finalized).await;
+
+    assert_eq!(cache.resize(3).await, 2);
+    assert_eq!(cache.capacity(), 3);
+    assert!(!cache.contains(&2).await);
+    assert!(cache.contains(&3).await);
+
+    cache.insert(7).await;
+    assert!(!cache.contains(&3).await, "New capacity applies to inserts");
+}
+
+#[tokio::test]
+async fn test_remove_and_clear() {
+    let cache = SlotCache::new(5);
+    cache.insert(1).await;
+    cache.insert(2).await;
+    cache.insert_skipped(3, Commitment::Finalized).await;
+
+    assert!(cache.remove(&1).await);
+    assert!(cache.remove(&3).await);
+    assert!(!cache.remove(&1).await);
+    assert!(!cache.is_skipped_at(&3, Commitment::Finalized).await);
+
+    assert_eq!(cache.clear().await, 1);
+    assert!(cache.is_empty().await);
+    assert_eq!(cache.get_latest_cached_slot().await, None);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_remove_and_insert_keep_order_in_sync() {
+    let cache = std::sync::Arc::new(SlotCache::new(1000));
+    let mut tasks = Vec::new();
+    for task in 0..8 {
+        let cache = cache.clone();
+        tasks.push(tokio::spawn(async move {
+            for slot in 0..200 {
+                if task % 2 == 0 {
+                    cache.insert(slot).await;
+                } else {
+                    cache.remove(&slot).await;
+                }
+            }
+        }));
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // Every cached slot is in the eviction order exactly once, and nothing else is
+    let mut ordered = cache.get_all_slots().await;
+    ordered.sort_unstable();
+    let entries: Vec<u64> = cache.entries().await.iter().map(|info| info.slot).collect();
+    assert_eq!(ordered.len(), entries.len());
+    ordered.dedup();
+    assert_eq!(ordered.len(), entries.len());
+    for slot in 0..200 {
+        assert_eq!(
+            cache.contains(&slot).await,
+            ordered.binary_search(&slot).is_ok()
+        );
+    }
+}
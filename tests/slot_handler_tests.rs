@@ -1,15 +1,20 @@
 use axum::response::IntoResponse;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
 };
 use mockall::{mock, predicate::*};
 use solana_caching_service::circuit_breaker::CircuitBreaker;
 use solana_caching_service::{
     cache::{LruCache, SlotCache},
-    handler::slot_handler::check_slot_confirmation_handler,
+    commitment::Commitment,
+    handler::slot_handler::{
+        CommitmentParams, RangeParams, check_slot_confirmation_handler, get_slot_info_handler,
+        get_slot_range_handler,
+    },
     metrics::Metrics,
     rpc::RpcApi,
+    slot_info::{BlockMetadata, SlotInfo},
     state::AppState,
 };
 use solana_client::client_error::{ClientError, ClientErrorKind};
@@ -44,13 +49,13 @@ fn create_test_app_state(
 ) -> AppState {
     let circuit_breaker = Arc::new(CircuitBreaker::new(3, Duration::from_secs(10)));
 
-    AppState {
-        rpc_client: Arc::new(mock_rpc),
+    AppState::new(
+        Arc::new(mock_rpc),
         cache,
         lru_cache,
-        metrics: Arc::new(mock_metrics),
+        Arc::new(mock_metrics),
         circuit_breaker,
-    }
+    )
 }
 
 #[tokio::test]
@@ -68,9 +73,13 @@ async fn test_handler_returns_200_ok_on_confirmed() {
 
     let app_state = create_test_app_state(mock_rpc, cache, lru_cache, mock_metrics);
 
-    let response = check_slot_confirmation_handler(State(app_state), Path(100))
-        .await
-        .into_response();
+    let response = check_slot_confirmation_handler(
+        State(app_state),
+        Path(100),
+        Query(CommitmentParams::default()),
+    )
+    .await
+    .into_response();
 
     assert_eq!(response.status(), StatusCode::OK);
 }
@@ -93,9 +102,13 @@ async fn test_handler_returns_404_not_found() {
 
     let app_state = create_test_app_state(mock_rpc, cache, lru_cache, mock_metrics);
 
-    let response = check_slot_confirmation_handler(State(app_state), Path(200))
-        .await
-        .into_response();
+    let response = check_slot_confirmation_handler(
+        State(app_state),
+        Path(200),
+        Query(CommitmentParams::default()),
+    )
+    .await
+    .into_response();
 
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
@@ -122,9 +135,164 @@ async fn test_handler_returns_500_on_rpc_error() {
 
     let app_state = create_test_app_state(mock_rpc, cache, lru_cache, mock_metrics);
 
-    let response = check_slot_confirmation_handler(State(app_state), Path(300))
+    let response = check_slot_confirmation_handler(
+        State(app_state),
+        Path(300),
+        Query(CommitmentParams::default()),
+    )
+    .await
+    .into_response();
+
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_handler_answers_finalized_query_against_finalized_level() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache.insert(100).await;
+    cache
+        .insert_with_commitment(101, Commitment::Finalized)
+        .await;
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+
+    // Slot 100 is only confirmed in the cache, so the finalized query falls back to RPC
+    mock_rpc
+        .expect_get_blocks()
+        .with(eq(100), eq(Some(100)))
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(vec![]) }));
+
+    mock_metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .times(2)
+        .return_const(());
+
+    let app_state = create_test_app_state(mock_rpc, cache, lru_cache, mock_metrics);
+
+    let finalized = || {
+        Query(CommitmentParams {
+            commitment: Some(Commitment::Finalized),
+        })
+    };
+
+    let response =
+        check_slot_confirmation_handler(State(app_state.clone()), Path(101), finalized())
+            .await
+            .into_response();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = check_slot_confirmation_handler(State(app_state), Path(100), finalized())
         .await
         .into_response();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
 
-    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+#[tokio::test]
+async fn test_range_handler_rejects_inverted_range() {
+    let app_state = create_test_app_state(
+        MockRpcApi::new(),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        MockMetrics::new(),
+    );
+
+    let response = get_slot_range_handler(
+        State(app_state),
+        Query(RangeParams {
+            start: 10,
+            end: 5,
+            commitment: None,
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_range_handler_returns_coverage() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache.insert(5).await;
+    cache.insert_skipped(6, Commitment::Confirmed).await;
+
+    let app_state = create_test_app_state(
+        MockRpcApi::new(),
+        cache,
+        Arc::new(LruCache::new(10)),
+        MockMetrics::new(),
+    );
+
+    let response = get_slot_range_handler(
+        State(app_state),
+        Query(RangeParams {
+            start: 5,
+            end: 6,
+            commitment: None,
+        }),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["source"], "cache");
+    assert_eq!(body["confirmed"], serde_json::json!([5]));
+    assert_eq!(body["skipped"], serde_json::json!([6]));
+    assert_eq!(body["counts"]["unknown"], 0);
+}
+
+#[tokio::test]
+async fn test_slot_info_handler_returns_cached_record() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache.insert(100).await;
+    cache
+        .merge_info(
+            SlotInfo::new(100, Commitment::Confirmed).with_block(BlockMetadata {
+                blockhash: "hash-100".to_string(),
+                parent_slot: 99,
+                block_height: Some(90),
+                block_time: Some(1_700_000_000),
+            }),
+        )
+        .await;
+
+    let app_state = create_test_app_state(
+        MockRpcApi::new(),
+        cache,
+        Arc::new(LruCache::new(10)),
+        MockMetrics::new(),
+    );
+
+    let response = get_slot_info_handler(
+        State(app_state.clone()),
+        Path(100),
+        Query(CommitmentParams::default()),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["slot"], 100);
+    assert_eq!(body["commitment"], "confirmed");
+    assert_eq!(body["blockhash"], "hash-100");
+    assert_eq!(body["parent_slot"], 99);
+    assert_eq!(body["block_height"], 90);
+    assert_eq!(body["block_time"], 1_700_000_000);
+    assert!(body["observed_at"].as_u64().is_some());
+
+    // The mock has no block for slot 101
+    let response = get_slot_info_handler(
+        State(app_state),
+        Path(101),
+        Query(CommitmentParams::default()),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
@@ -0,0 +1,161 @@
+use mockall::{mock, predicate::*};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    metrics::Metrics,
+    rpc::RpcApi,
+    service::slot_info_service::{SlotInfoLookup, get_slot_info},
+    slot_info::{BlockMetadata, SlotInfo},
+    state::AppState,
+};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_block_metadata<'a>(
+            &'a self,
+            slot: u64,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Option<BlockMetadata>, ClientError>> + Send + 'a>>;
+    }
+}
+
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn block(slot: u64) -> BlockMetadata {
+    BlockMetadata {
+        blockhash: format!("hash-{}", slot),
+        parent_slot: slot - 1,
+        block_height: Some(slot - 10),
+        block_time: Some(1_700_000_000),
+    }
+}
+
+fn create_test_app_state(
+    mock_rpc: MockRpcApi,
+    cache: Arc<SlotCache>,
+    lru_cache: Arc<LruCache>,
+) -> AppState {
+    AppState::new(
+        Arc::new(mock_rpc),
+        cache,
+        lru_cache,
+        Arc::new(MockMetrics::new()),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+#[tokio::test]
+async fn test_slot_info_is_served_from_primary_cache() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache
+        .insert_with_commitment(100, Commitment::Finalized)
+        .await;
+    cache
+        .merge_info(SlotInfo::new(100, Commitment::Confirmed).with_block(block(100)))
+        .await;
+
+    let app_state = create_test_app_state(MockRpcApi::new(), cache, Arc::new(LruCache::new(10)));
+
+    let SlotInfoLookup::Found(info) = get_slot_info(&app_state, 100, Commitment::Finalized).await
+    else {
+        panic!("expected a cached slot");
+    };
+
+    assert_eq!(info.commitment, Commitment::Finalized);
+    assert_eq!(info.block, Some(block(100)));
+}
+
+#[tokio::test]
+async fn test_slot_info_fills_missing_block_of_cached_slot() {
+    let cache = Arc::new(SlotCache::new(10));
+    cache
+        .insert_with_commitment(100, Commitment::Finalized)
+        .await;
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_block_metadata()
+        .with(eq(100), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|slot, _| Box::pin(async move { Ok(Some(block(slot))) }));
+
+    let app_state = create_test_app_state(mock_rpc, cache.clone(), Arc::new(LruCache::new(10)));
+
+    let first = get_slot_info(&app_state, 100, Commitment::Confirmed).await;
+    let second = get_slot_info(&app_state, 100, Commitment::Confirmed).await;
+
+    assert_eq!(first, second);
+    let cached = cache.get_info(&100).await.unwrap();
+    assert_eq!(cached.commitment, Commitment::Finalized);
+    assert_eq!(cached.block, Some(block(100)));
+}
+
+#[tokio::test]
+async fn test_slot_info_fallback_populates_lru_cache() {
+    let lru_cache = Arc::new(LruCache::new(10));
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_block_metadata()
+        .with(eq(42), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|slot, _| Box::pin(async move { Ok(Some(block(slot))) }));
+
+    let app_state =
+        create_test_app_state(mock_rpc, Arc::new(SlotCache::new(10)), lru_cache.clone());
+
+    let first = get_slot_info(&app_state, 42, Commitment::Processed).await;
+    let second = get_slot_info(&app_state, 42, Commitment::Confirmed).await;
+
+    assert!(matches!(first, SlotInfoLookup::Found(ref info) if info.block == Some(block(42))));
+    assert_eq!(first, second);
+    assert!(lru_cache.get(&42).await);
+}
+
+#[tokio::test]
+async fn test_slot_info_reports_missing_block_and_errors() {
+    let mut mock_rpc = MockRpcApi::new();
+
+    mock_rpc
+        .expect_get_block_metadata()
+        .with(eq(7), always())
+        .returning(|_, _| Box::pin(async { Ok(None) }));
+    mock_rpc
+        .expect_get_block_metadata()
+        .with(eq(8), always())
+        .returning(|_, _| {
+            Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+        });
+
+    let app_state = create_test_app_state(
+        mock_rpc,
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+    );
+
+    assert_eq!(
+        get_slot_info(&app_state, 7, Commitment::Confirmed).await,
+        SlotInfoLookup::NotConfirmed
+    );
+    assert_eq!(
+        get_slot_info(&app_state, 8, Commitment::Confirmed).await,
+        SlotInfoLookup::Error
+    );
+}
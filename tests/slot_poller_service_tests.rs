@@ -1,16 +1,29 @@
 use mockall::{Sequence, mock, predicate::*};
 use solana_caching_service::{
-    cache::SlotCache,
+    cache::{LruCache, SlotCache},
     circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
     metrics::Metrics,
     rpc::RpcApi,
     service::slot_poller::{
-        poll, poll_with_retry, poll_with_transient_retry, poll_with_transient_retry_and_signals,
-        poll_with_transient_retry_and_signals_and_breaker,
+        PollSchedule, PollerSettings, poll, poll_with_commitments, poll_with_retry,
+        poll_with_transient_retry, poll_with_transient_retry_and_signals,
+        poll_with_transient_retry_and_signals_and_breaker, split_into_chunks,
     },
+    slot_info::BlockMetadata,
+    state::AppState,
 };
 use solana_client::client_error::{ClientError, ClientErrorKind};
-use std::{future::Future, io, pin::Pin, sync::Arc, time::Duration};
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::Duration,
+};
 use tokio::sync::broadcast;
 
 mock! {
@@ -26,6 +39,33 @@ mock! {
     }
 }
 
+mock! {
+    pub CommitmentRpcApi {}
+    impl RpcApi for CommitmentRpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_slot_with_commitment<'a>(
+            &'a self,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_block_metadata<'a>(
+            &'a self,
+            slot: u64,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Option<BlockMetadata>, ClientError>> + Send + 'a>>;
+    }
+}
+
 mock! {
     pub Metrics {}
     impl Metrics for Metrics {
@@ -460,9 +500,11 @@ async fn test_final_poller_works_when_circuit_is_closed() {
         cache.clone(),
         metrics,
         circuit_breaker,
-        Duration::from_millis(10),
-        3,
-        Duration::from_millis(5),
+        PollSchedule {
+            poll_interval: Duration::from_millis(10),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(5),
+        },
         shutdown_rx,
     );
 
@@ -512,9 +554,11 @@ async fn test_final_poller_is_blocked_by_open_circuit() {
         cache.clone(),
         metrics,
         circuit_breaker,
-        Duration::from_millis(20),
-        0,
-        Duration::from_millis(5),
+        PollSchedule {
+            poll_interval: Duration::from_millis(20),
+            max_retries: 0,
+            initial_backoff: Duration::from_millis(5),
+        },
         shutdown_rx,
     );
 
@@ -522,3 +566,754 @@ async fn test_final_poller_is_blocked_by_open_circuit() {
 
     assert_eq!(cache.get_latest_cached_slot().await, None);
 }
+
+#[tokio::test]
+async fn test_commitment_poller_tracks_each_level() {
+    let cache = Arc::new(SlotCache::new(20));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .with(eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(100) }));
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .with(eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(96) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(81), eq(Some(100)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![92, 95, 98]) }));
+    // Both levels share the cache, so both backfill from the floor below the highest tip
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(81), eq(Some(96)), eq(Commitment::Finalized))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![88, 92, 95]) }));
+
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .times(2)
+        .return_const(());
+    // Only the freshest tracked level reports the latest slot
+    mock_metrics
+        .expect_record_latest_slot()
+        .with(eq(98))
+        .times(1)
+        .return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        // Only the warm-up round runs while the test waits
+        poll_interval: Duration::from_secs(1),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed, Commitment::Finalized],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state, settings, shutdown_rx);
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    let _ = shutdown_tx.send(());
+
+    assert_eq!(cache.get_commitment(&88).await, Some(Commitment::Finalized));
+    assert_eq!(cache.get_commitment(&92).await, Some(Commitment::Finalized));
+    assert_eq!(cache.get_commitment(&95).await, Some(Commitment::Finalized));
+    assert_eq!(cache.get_commitment(&98).await, Some(Commitment::Confirmed));
+    assert_eq!(cache.get_latest_cached_slot().await, Some(98));
+    assert_eq!(
+        cache.get_latest_slot_at(Commitment::Finalized).await,
+        Some(95)
+    );
+}
+
+#[tokio::test]
+async fn test_warm_up_completes_when_a_level_is_below_the_shared_floor() {
+    let cache = Arc::new(SlotCache::new(10));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .with(eq(Commitment::Confirmed))
+        .returning(|_| Box::pin(async { Ok(100) }));
+    // Further behind the confirmed tip than the cache holds
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .with(eq(Commitment::Finalized))
+        .returning(|_| Box::pin(async { Ok(60) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(91), eq(Some(100)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|start, end, _| {
+            let blocks = (start..=end.unwrap()).collect();
+            Box::pin(async move { Ok(blocks) })
+        });
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_secs(1),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed, Commitment::Finalized],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    let _ = shutdown_tx.send(());
+
+    assert!(app_state.poller.is_warmed_up());
+    assert_eq!(app_state.poller.cursor(Commitment::Finalized), Some(60));
+    assert!(
+        cache
+            .missing_ranges(91, 100, Commitment::Confirmed)
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_commitment_poller_reconciles_range_behind_pushed_slots() {
+    let cache = Arc::new(SlotCache::new(50));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let mut seq = Sequence::new();
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| Box::pin(async { Ok(96) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(47), eq(Some(96)), eq(Commitment::Finalized))
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![90]) }));
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_| Box::pin(async { Ok(125) }));
+    // The pushed root 120 must not move the start of the reconciliation range
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(97), eq(Some(125)), eq(Commitment::Finalized))
+        .times(1)
+        .in_sequence(&mut seq)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![100, 120]) }));
+
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(40),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Finalized],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state, settings, shutdown_rx);
+
+    // The warm-up round runs right away, the next one after the poll interval
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    cache
+        .insert_with_commitment(120, Commitment::Finalized)
+        .await;
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let _ = shutdown_tx.send(());
+
+    assert!(cache.contains_at(&90, Commitment::Finalized).await);
+    assert!(cache.contains_at(&100, Commitment::Finalized).await);
+}
+
+#[tokio::test]
+async fn test_commitment_poller_records_skipped_slots() {
+    let cache = Arc::new(SlotCache::new(20));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .with(eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(100) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(81), eq(Some(100)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| {
+            Box::pin(async {
+                Ok(vec![
+                    81, 82, 83, 84, 85, 86, 87, 88, 89, 90, 91, 93, 94, 95, 96, 97, 98, 99, 100,
+                ])
+            })
+        });
+
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        // Only the warm-up round runs while the test waits
+        poll_interval: Duration::from_secs(1),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state, settings, shutdown_rx);
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    let _ = shutdown_tx.send(());
+
+    assert!(cache.is_skipped_at(&92, Commitment::Confirmed).await);
+    assert!(cache.is_skipped_at(&92, Commitment::Processed).await);
+    assert!(!cache.is_skipped_at(&92, Commitment::Finalized).await);
+    assert!(!cache.is_skipped_at(&93, Commitment::Confirmed).await);
+    assert!(cache.contains(&93).await);
+}
+
+#[tokio::test]
+async fn test_commitment_poller_fetches_block_metadata() {
+    let cache = Arc::new(SlotCache::new(20));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .times(1)
+        .returning(|_| Box::pin(async { Ok(100) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(81), eq(Some(100)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![98, 100]) }));
+    mock_rpc
+        .expect_get_block_metadata()
+        .with(eq(98), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _| {
+            Box::pin(async {
+                Ok(Some(BlockMetadata {
+                    blockhash: "hash-98".to_string(),
+                    parent_slot: 97,
+                    block_height: Some(88),
+                    block_time: Some(1_700_000_000),
+                }))
+            })
+        });
+    // A block that is not available yet is simply left without metadata
+    mock_rpc
+        .expect_get_block_metadata()
+        .with(eq(100), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _| Box::pin(async { Ok(None) }));
+
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        // Only the warm-up round runs while the test waits
+        poll_interval: Duration::from_secs(1),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: true,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state, settings, shutdown_rx);
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    let _ = shutdown_tx.send(());
+
+    let info = cache.get_info(&98).await.unwrap();
+    assert_eq!(info.block.unwrap().blockhash, "hash-98");
+    assert!(cache.get_info(&100).await.unwrap().block.is_none());
+}
+
+#[test]
+fn test_split_into_chunks_bounds_every_chunk() {
+    let chunks = split_into_chunks(&[(100, 104), (200, 212)], 5);
+
+    assert_eq!(chunks, vec![(100, 104), (200, 204), (205, 209), (210, 212)]);
+}
+
+#[tokio::test]
+async fn test_commitment_poller_catches_up_in_bounded_chunks() {
+    let cache = Arc::new(SlotCache::new(100));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1250) }));
+    // The stall left the cursor at 1000, only the last CACHE_CAPACITY slots are fetched
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .withf(|start, end, _| *start >= 1151 && end.is_some_and(|end| end - start < 30))
+        .times(4)
+        .returning(|start, end, _| {
+            let blocks = (start..=end.unwrap())
+                .filter(|slot| slot % 2 == 0)
+                .collect();
+            Box::pin(async move { Ok(blocks) })
+        });
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    app_state.poller.set_cursor(Commitment::Confirmed, 1000);
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(20),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 30,
+        backfill_concurrency: 2,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    let _ = shutdown_tx.send(());
+
+    assert!(
+        cache
+            .missing_ranges(1151, 1250, Commitment::Confirmed)
+            .await
+            .is_empty()
+    );
+    assert_eq!(app_state.poller.cursor(Commitment::Confirmed), Some(1250));
+    let status = app_state.poller.status();
+    assert_eq!(
+        status[0].catch_up, None,
+        "Progress is cleared once caught up"
+    );
+}
+
+#[tokio::test]
+async fn test_commitment_poller_refetches_holes_left_by_failed_chunks() {
+    // Sized so that the warm-up starts at slot 110
+    let cache = Arc::new(SlotCache::new(11));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let first_chunk_calls = Arc::new(AtomicUsize::new(0));
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(120) }));
+    let calls = first_chunk_calls.clone();
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(110), eq(Some(114)), eq(Commitment::Confirmed))
+        .returning(move |_, _, _| {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+            } else {
+                Box::pin(async { Ok(vec![110, 111, 112, 113, 114]) })
+            }
+        });
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(115), eq(Some(119)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![115, 116, 117, 118, 119]) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(120), eq(Some(120)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![120]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(20),
+        max_retries: 0,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 5,
+        backfill_concurrency: 1,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state, settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    let _ = shutdown_tx.send(());
+
+    assert_eq!(first_chunk_calls.load(Ordering::SeqCst), 2);
+    assert!(
+        cache
+            .missing_ranges(110, 120, Commitment::Confirmed)
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_commitment_poller_warms_up_to_capacity_right_away() {
+    let cache = Arc::new(SlotCache::new(100));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(|_| Box::pin(async { Ok(1000) }));
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .withf(|start, end, _| *start >= 901 && end.is_some_and(|end| end - start < 30))
+        .times(4)
+        .returning(|start, end, _| {
+            let blocks = (start..=end.unwrap()).collect();
+            Box::pin(async move { Ok(blocks) })
+        });
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_secs(1),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 30,
+        backfill_concurrency: 2,
+        log_sample_every: 1,
+    };
+    assert!(!app_state.poller.is_warmed_up());
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = shutdown_tx.send(());
+
+    assert!(app_state.poller.is_warmed_up());
+    assert_eq!(cache.get_all_slots().await.len(), 100);
+    assert!(
+        cache
+            .missing_ranges(901, 1000, Commitment::Confirmed)
+            .await
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_commitment_poller_stays_cold_until_warm_up_succeeds() {
+    let cache = Arc::new(SlotCache::new(20));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let get_slot_calls = Arc::new(AtomicUsize::new(0));
+
+    let calls = get_slot_calls.clone();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(move |_| {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+            } else {
+                Box::pin(async { Ok(100) })
+            }
+        });
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(81), eq(Some(100)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![90, 100]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(40),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!app_state.poller.is_warmed_up());
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let _ = shutdown_tx.send(());
+    assert!(app_state.poller.is_warmed_up());
+    assert!(cache.contains(&100).await);
+}
+
+#[tokio::test]
+async fn test_commitment_poller_records_health_signals() {
+    let cache = Arc::new(SlotCache::new(20));
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let get_slot_calls = Arc::new(AtomicUsize::new(0));
+
+    let calls = get_slot_calls.clone();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(move |_| {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                Box::pin(async { Err(ClientError::from(ClientErrorKind::Custom("boom".into()))) })
+            } else {
+                Box::pin(async { Ok(100) })
+            }
+        });
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![90, 98]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        cache.clone(),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(40),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(!app_state.poller.is_upstream_reachable());
+    assert!(app_state.poller.since_last_success().is_none());
+
+    tokio::time::sleep(Duration::from_millis(40)).await;
+    let _ = shutdown_tx.send(());
+    assert!(app_state.poller.is_upstream_reachable());
+    assert!(app_state.poller.since_last_success().is_some());
+    assert_eq!(
+        app_state.poller.lags().get(&Commitment::Confirmed),
+        Some(&2)
+    );
+}
+
+#[tokio::test]
+async fn test_paused_commitment_poller_skips_rounds_until_resumed() {
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let get_slot_calls = Arc::new(AtomicUsize::new(0));
+
+    let calls = get_slot_calls.clone();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(100) })
+        });
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![98, 100]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        Arc::new(SlotCache::new(20)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    app_state.poller.set_paused(true);
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(20),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(70)).await;
+    assert_eq!(get_slot_calls.load(Ordering::SeqCst), 0);
+
+    app_state.poller.set_paused(false);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = shutdown_tx.send(());
+    assert!(get_slot_calls.load(Ordering::SeqCst) > 0);
+    assert!(app_state.cache.contains(&100).await);
+}
+
+#[tokio::test]
+async fn test_commitment_poller_switches_to_updated_interval() {
+    let mut mock_rpc = MockCommitmentRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let get_slot_calls = Arc::new(AtomicUsize::new(0));
+
+    let calls = get_slot_calls.clone();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .returning(move |_| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(100) })
+        });
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .returning(|_, _, _| Box::pin(async { Ok(vec![98, 100]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let app_state = AppState::new(
+        Arc::new(mock_rpc),
+        Arc::new(SlotCache::new(20)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    );
+    let settings = PollerSettings {
+        poll_interval: Duration::from_secs(3600),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+    app_state.poller.update_settings(PollerSettings {
+        poll_interval: Duration::from_millis(20),
+        commitments: vec![],
+        ..settings.clone()
+    });
+
+    poll_with_commitments(app_state.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(90)).await;
+    let _ = shutdown_tx.send(());
+
+    // Tracked levels are kept even though the update carried none
+    assert!(get_slot_calls.load(Ordering::SeqCst) >= 3);
+}
@@ -0,0 +1,239 @@
+use futures_util::{SinkExt, StreamExt};
+use solana_caching_service::{
+    cache::SlotCache,
+    commitment::Commitment,
+    service::slot_source::{
+        PubsubSlotSource, SlotSource, SlotSourceError, SlotUpdate, ingest_slot_updates,
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::{
+    accept_async, accept_hdr_async,
+    tungstenite::{
+        Message,
+        handshake::server::{Request, Response},
+        http::{HeaderMap, HeaderValue},
+    },
+};
+
+fn slot_notification(slot: u64) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","method":"slotNotification","params":{{"result":{{"parent":{},"root":{},"slot":{}}},"subscription":0}}}}"#,
+        slot - 1,
+        slot - 32,
+        slot
+    )
+}
+
+fn root_notification(slot: u64) -> String {
+    format!(
+        r#"{{"jsonrpc":"2.0","method":"rootNotification","params":{{"result":{},"subscription":1}}}}"#,
+        slot
+    )
+}
+
+/// Local stand-in for the pubsub endpoint. Every accepted connection answers the two
+/// subscription requests, plays the next script of notifications and closes the socket.
+async fn spawn_pubsub_stand_in(scripts: Vec<Vec<String>>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        for script in scripts {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+
+            for subscription in 0..2 {
+                let request = ws.next().await.unwrap().unwrap().into_text().unwrap();
+                let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "result": subscription,
+                    "id": request["id"],
+                });
+                ws.send(Message::Text(response.to_string().into()))
+                    .await
+                    .unwrap();
+            }
+
+            for message in script {
+                ws.send(Message::Text(message.into())).await.unwrap();
+            }
+            let _ = ws.close(None).await;
+        }
+    });
+
+    format!("ws://{}", addr)
+}
+
+#[tokio::test]
+async fn test_pubsub_source_maps_notifications_to_commitments() {
+    let url =
+        spawn_pubsub_stand_in(vec![vec![slot_notification(100), root_notification(68)]]).await;
+    let source = PubsubSlotSource::new(url);
+    let (tx, mut rx) = mpsc::channel(16);
+
+    let result = source.run(tx).await;
+
+    assert!(result.is_ok());
+    assert_eq!(
+        rx.recv().await,
+        Some(SlotUpdate {
+            slot: 100,
+            commitment: Commitment::Processed
+        })
+    );
+    assert_eq!(
+        rx.recv().await,
+        Some(SlotUpdate {
+            slot: 68,
+            commitment: Commitment::Finalized
+        })
+    );
+}
+
+#[tokio::test]
+async fn test_pubsub_source_reports_rejected_subscription() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        let _ = ws.next().await;
+        let error =
+            r#"{"jsonrpc":"2.0","error":{"code":-32601,"message":"Method not found"},"id":1}"#;
+        ws.send(Message::Text(error.into())).await.unwrap();
+    });
+
+    let source = PubsubSlotSource::new(format!("ws://{}", addr));
+    let (tx, _rx) = mpsc::channel(16);
+
+    let result = source.run(tx).await;
+
+    assert!(matches!(result, Err(SlotSourceError::Subscription(_))));
+}
+
+#[tokio::test]
+async fn test_ingestion_feeds_cache_and_reconnects_after_drop() {
+    let url = spawn_pubsub_stand_in(vec![
+        vec![slot_notification(100), root_notification(70)],
+        vec![slot_notification(101), root_notification(100)],
+    ])
+    .await;
+    let cache = Arc::new(SlotCache::new(20));
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    ingest_slot_updates(
+        Arc::new(PubsubSlotSource::new(url)),
+        cache.clone(),
+        Duration::from_millis(10),
+        shutdown_rx,
+    );
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let _ = shutdown_tx.send(());
+
+    assert_eq!(cache.get_commitment(&70).await, Some(Commitment::Finalized));
+    assert_eq!(
+        cache.get_commitment(&101).await,
+        Some(Commitment::Processed)
+    );
+    // The root notification from the second connection upgrades slot 100
+    assert_eq!(
+        cache.get_commitment(&100).await,
+        Some(Commitment::Finalized)
+    );
+    assert!(!cache.contains(&101).await);
+}
+
+#[tokio::test]
+async fn test_pubsub_source_gives_up_on_a_stalled_socket() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let _ws = accept_async(stream).await.unwrap();
+        // Keeps the connection open without ever sending anything
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    });
+
+    let source = PubsubSlotSource::new(format!("ws://{}", addr))
+        .with_read_timeout(Duration::from_millis(50));
+    let (tx, _rx) = mpsc::channel(16);
+
+    let result = tokio::time::timeout(Duration::from_secs(5), source.run(tx))
+        .await
+        .expect("A stalled socket must not block the source");
+
+    assert!(matches!(result, Err(SlotSourceError::Idle(_))));
+}
+
+#[tokio::test]
+async fn test_ingestion_reconnects_after_stalled_socket() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let stalled = accept_async(stream).await.unwrap();
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut ws = accept_async(stream).await.unwrap();
+        ws.send(Message::Text(slot_notification(100).into()))
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        drop(stalled);
+    });
+    let cache = Arc::new(SlotCache::new(20));
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    ingest_slot_updates(
+        Arc::new(
+            PubsubSlotSource::new(format!("ws://{}", addr))
+                .with_read_timeout(Duration::from_millis(50)),
+        ),
+        cache.clone(),
+        Duration::from_millis(10),
+        shutdown_rx,
+    );
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let _ = shutdown_tx.send(());
+
+    assert_eq!(
+        cache.get_commitment(&100).await,
+        Some(Commitment::Processed)
+    );
+}
+
+// The handshake callback signature, with its large error response, is set by tungstenite
+#[allow(clippy::result_large_err)]
+#[tokio::test]
+async fn test_pubsub_source_sends_headers_with_handshake() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (header_tx, header_rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let _ws = accept_hdr_async(stream, |request: &Request, response: Response| {
+            let _ = header_tx.send(request.headers().get("x-api-key").cloned());
+            Ok(response)
+        })
+        .await
+        .unwrap();
+    });
+
+    let mut headers = HeaderMap::new();
+    headers.insert("x-api-key", HeaderValue::from_static("provider-secret-key"));
+    let source = PubsubSlotSource::new(format!("ws://{}", addr)).with_headers(headers);
+    let (tx, _rx) = mpsc::channel(16);
+
+    let _ = source.run(tx).await;
+
+    assert_eq!(
+        header_rx.await.unwrap(),
+        Some(HeaderValue::from_static("provider-secret-key"))
+    );
+}
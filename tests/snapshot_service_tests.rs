@@ -0,0 +1,221 @@
+use mockall::{mock, predicate::*};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    commitment::Commitment,
+    metrics::Metrics,
+    rpc::RpcApi,
+    service::{
+        slot_poller::{PollerSettings, poll_with_commitments},
+        snapshot_service::{
+            load_snapshot, restore_snapshot, spawn_snapshot_task, take_snapshot, write_snapshot,
+        },
+    },
+    slot_info::{BlockMetadata, SlotInfo},
+    state::AppState,
+};
+use solana_client::client_error::ClientError;
+use std::{future::Future, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
+use tokio::sync::broadcast;
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+        fn get_slot_with_commitment<'a>(
+            &'a self,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks_with_commitment<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+            commitment: Commitment,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+fn snapshot_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("slot-snapshot-tests-{}", std::process::id()));
+    let path = dir.join(format!("{}.json", name));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn create_app_state(mock_rpc: MockRpcApi, mock_metrics: MockMetrics) -> AppState {
+    AppState::new(
+        Arc::new(mock_rpc),
+        Arc::new(SlotCache::new(50)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(mock_metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+#[tokio::test]
+async fn test_snapshot_round_trip_restores_both_tiers() {
+    let path = snapshot_path("round_trip");
+    let source = create_app_state(MockRpcApi::new(), MockMetrics::new());
+
+    source
+        .cache
+        .insert_info(
+            SlotInfo::new(100, Commitment::Finalized).with_block(BlockMetadata {
+                blockhash: "hash-100".to_string(),
+                parent_slot: 99,
+                block_height: Some(90),
+                block_time: Some(1_700_000_000),
+            }),
+        )
+        .await;
+    source.cache.insert(102).await;
+    source
+        .cache
+        .insert_skipped(101, Commitment::Confirmed)
+        .await;
+    source.lru_cache.put(7).await;
+    source.lru_cache.put_skipped(8, Commitment::Finalized).await;
+    source.lru_cache.put(9).await;
+    source.poller.set_cursor(Commitment::Confirmed, 102);
+    source.poller.set_cursor(Commitment::Finalized, 100);
+
+    write_snapshot(&source, &path).await.unwrap();
+    let snapshot = load_snapshot(&path)
+        .await
+        .unwrap()
+        .expect("snapshot exists");
+
+    let restored = create_app_state(MockRpcApi::new(), MockMetrics::new());
+    restore_snapshot(&restored, snapshot).await;
+
+    assert_eq!(
+        restored.lru_cache.entries().await,
+        source.lru_cache.entries().await,
+        "Recency order is kept"
+    );
+    assert_eq!(
+        restored.cache.get_info(&100).await,
+        source.cache.get_info(&100).await
+    );
+    assert!(restored.cache.contains(&102).await);
+    assert!(
+        restored
+            .cache
+            .is_skipped_at(&101, Commitment::Confirmed)
+            .await
+    );
+    assert!(restored.lru_cache.get(&7).await);
+    assert!(
+        restored
+            .lru_cache
+            .is_skipped_at(&8, Commitment::Finalized)
+            .await
+    );
+    assert_eq!(restored.poller.cursors(), source.poller.cursors());
+}
+
+#[tokio::test]
+async fn test_load_snapshot_returns_none_without_file() {
+    let path = snapshot_path("missing");
+
+    assert!(load_snapshot(&path).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_load_snapshot_rejects_other_versions() {
+    let path = snapshot_path("old_version");
+    let app_state = create_app_state(MockRpcApi::new(), MockMetrics::new());
+    let mut snapshot = take_snapshot(&app_state).await;
+    snapshot.version += 1;
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, serde_json::to_vec(&snapshot).unwrap()).unwrap();
+
+    assert!(load_snapshot(&path).await.is_err());
+}
+
+#[tokio::test]
+async fn test_snapshot_task_writes_final_snapshot_on_shutdown() {
+    let path = snapshot_path("shutdown");
+    let app_state = create_app_state(MockRpcApi::new(), MockMetrics::new());
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+
+    let task = spawn_snapshot_task(
+        app_state.clone(),
+        path.clone(),
+        Duration::from_secs(3600),
+        shutdown_rx,
+    );
+    app_state.cache.insert(42).await;
+    let _ = shutdown_tx.send(());
+    task.await.unwrap();
+
+    let snapshot = load_snapshot(&path)
+        .await
+        .unwrap()
+        .expect("snapshot exists");
+    assert_eq!(snapshot.primary.len(), 1);
+    assert_eq!(snapshot.primary[0].slot, 42);
+}
+
+#[tokio::test]
+async fn test_restored_cursor_backfills_gap_to_tip() {
+    let path = snapshot_path("backfill");
+    let source = create_app_state(MockRpcApi::new(), MockMetrics::new());
+    source.cache.insert(100).await;
+    source.poller.set_cursor(Commitment::Confirmed, 100);
+    write_snapshot(&source, &path).await.unwrap();
+
+    let mut mock_rpc = MockRpcApi::new();
+    let mut mock_metrics = MockMetrics::new();
+    mock_rpc
+        .expect_get_slot_with_commitment()
+        .with(eq(Commitment::Confirmed))
+        .returning(|_| Box::pin(async { Ok(130) }));
+    // The poller resumes right after the snapshot cursor instead of near the tip
+    mock_rpc
+        .expect_get_blocks_with_commitment()
+        .with(eq(101), eq(Some(130)), eq(Commitment::Confirmed))
+        .times(1)
+        .returning(|_, _, _| Box::pin(async { Ok(vec![105, 130]) }));
+    mock_metrics
+        .expect_record_get_blocks_elapsed()
+        .return_const(());
+    mock_metrics.expect_record_latest_slot().return_const(());
+
+    let restored = create_app_state(mock_rpc, mock_metrics);
+    restore_snapshot(&restored, load_snapshot(&path).await.unwrap().unwrap()).await;
+
+    let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
+    let settings = PollerSettings {
+        poll_interval: Duration::from_millis(20),
+        max_retries: 3,
+        initial_backoff: Duration::from_millis(5),
+        commitments: vec![Commitment::Confirmed],
+        fetch_block_metadata: false,
+        backfill_chunk_size: 1000,
+        backfill_concurrency: 4,
+        log_sample_every: 1,
+    };
+    poll_with_commitments(restored.clone(), settings, shutdown_rx);
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let _ = shutdown_tx.send(());
+
+    assert!(restored.cache.contains(&100).await);
+    assert!(restored.cache.contains(&105).await);
+    assert!(restored.cache.contains(&130).await);
+    assert_eq!(restored.poller.cursor(Commitment::Confirmed), Some(130));
+}
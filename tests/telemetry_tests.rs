@@ -0,0 +1,230 @@
+use axum::{
+    body::Body,
+    http::{HeaderMap, HeaderValue, Request, StatusCode},
+};
+use mockall::mock;
+use opentelemetry::trace::{SpanId, TraceContextExt, TraceId};
+use solana_caching_service::{
+    cache::{LruCache, SlotCache},
+    circuit_breaker::CircuitBreaker,
+    metrics::Metrics,
+    routes::create_router,
+    rpc::RpcApi,
+    state::AppState,
+    telemetry::{TracingSettings, init_tracer_provider, otel_layer, parent_context},
+};
+use solana_client::client_error::ClientError;
+use std::{
+    future::Future,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    pin::Pin,
+    sync::{Arc, mpsc},
+    thread,
+    time::Duration,
+};
+use tower::ServiceExt;
+use tracing_subscriber::layer::SubscriberExt;
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+mock! {
+    pub Metrics {}
+    impl Metrics for Metrics {
+        fn record_latest_slot(&self, slot: u64);
+        fn record_get_blocks_elapsed(&self, elapsed: Duration);
+        fn record_is_slot_confirmed_elapsed(&self, elapsed: Duration);
+    }
+}
+
+const TRACE_ID: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+const PARENT_SPAN_ID: &str = "00f067aa0ba902b7";
+
+fn traceparent() -> String {
+    format!("00-{}-{}-01", TRACE_ID, PARENT_SPAN_ID)
+}
+
+// Stand-in for an OTLP/HTTP collector: answers one export request and hands over
+// its path and protobuf body
+fn spawn_collector() -> (String, mpsc::Receiver<(String, Vec<u8>)>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line).unwrap();
+        let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if line.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = line.split_once(':')
+                && name.eq_ignore_ascii_case("content-length")
+            {
+                content_length = value.trim().parse().unwrap();
+            }
+        }
+        let mut body = vec![0; content_length];
+        reader.read_exact(&mut body).unwrap();
+
+        let mut stream = stream;
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\n\r\n")
+            .unwrap();
+        tx.send((path, body)).unwrap();
+    });
+    (endpoint, rx)
+}
+
+fn create_test_app_state(rpc_client: MockRpcApi) -> AppState {
+    let mut metrics = MockMetrics::new();
+    metrics.expect_record_get_blocks_elapsed().returning(|_| ());
+    metrics
+        .expect_record_is_slot_confirmed_elapsed()
+        .returning(|_| ());
+    AppState::new(
+        Arc::new(rpc_client),
+        Arc::new(SlotCache::new(10)),
+        Arc::new(LruCache::new(10)),
+        Arc::new(metrics),
+        Arc::new(CircuitBreaker::new(3, Duration::from_secs(10))),
+    )
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+fn test_parent_context_reads_traceparent() {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "traceparent",
+        HeaderValue::from_str(&traceparent()).unwrap(),
+    );
+
+    let context = parent_context(&headers);
+    let span_context = context.span().span_context().clone();
+
+    assert!(span_context.is_remote());
+    assert!(span_context.is_sampled());
+    assert_eq!(
+        span_context.trace_id(),
+        TraceId::from_hex(TRACE_ID).unwrap()
+    );
+    assert_eq!(
+        span_context.span_id(),
+        SpanId::from_hex(PARENT_SPAN_ID).unwrap()
+    );
+}
+
+#[test]
+fn test_parent_context_ignores_malformed_traceparent() {
+    let mut headers = HeaderMap::new();
+    headers.insert("traceparent", HeaderValue::from_static("00-not-a-trace-01"));
+
+    let context = parent_context(&headers);
+
+    assert!(!context.span().span_context().is_valid());
+}
+
+#[tokio::test]
+async fn test_rpc_fallback_is_exported_under_incoming_trace() {
+    let (endpoint, exports) = spawn_collector();
+    let provider = init_tracer_provider(&TracingSettings {
+        endpoint,
+        service_name: "test-service".to_string(),
+        sample_ratio: 0.0,
+    })
+    .unwrap();
+    let subscriber = tracing_subscriber::registry().with(otel_layer(&provider));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let mut rpc_client = MockRpcApi::new();
+    rpc_client
+        .expect_get_blocks()
+        .returning(|slot, _| Box::pin(async move { Ok(vec![slot]) }));
+    let app = create_router(create_test_app_state(rpc_client));
+
+    let request = Request::builder()
+        .uri("/isSlotConfirmed/42")
+        .header("traceparent", traceparent())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The sampled flag of the parent wins over a zero sample ratio
+    provider.force_flush().unwrap();
+    let (path, body) = exports.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert_eq!(path, "/v1/traces");
+    assert!(contains(
+        &body,
+        &TraceId::from_hex(TRACE_ID).unwrap().to_bytes()
+    ));
+    assert!(contains(
+        &body,
+        &SpanId::from_hex(PARENT_SPAN_ID).unwrap().to_bytes()
+    ));
+    for expected in [
+        "test-service",
+        "GET /isSlotConfirmed/{slot}",
+        "is_slot_confirmed",
+        "cached_status",
+        "miss",
+        "confirm_via_rpc",
+        "circuit_breaker",
+        "closed",
+    ] {
+        assert!(contains(&body, expected.as_bytes()), "missing {}", expected);
+    }
+}
+
+#[tokio::test]
+async fn test_cache_hit_records_answering_tier() {
+    let (endpoint, exports) = spawn_collector();
+    let provider = init_tracer_provider(&TracingSettings {
+        endpoint,
+        service_name: "test-service".to_string(),
+        sample_ratio: 1.0,
+    })
+    .unwrap();
+    let subscriber = tracing_subscriber::registry().with(otel_layer(&provider));
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let app_state = create_test_app_state(MockRpcApi::new());
+    app_state.cache.insert(42).await;
+    let app = create_router(app_state);
+
+    let request = Request::builder()
+        .uri("/isSlotConfirmed/42")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    provider.force_flush().unwrap();
+    let (_, body) = exports.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert!(contains(&body, b"cached_status"));
+    assert!(contains(&body, b"primary"));
+    assert!(!contains(&body, b"confirm_via_rpc"));
+}
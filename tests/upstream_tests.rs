@@ -0,0 +1,174 @@
+use axum::{Json, Router, http::HeaderMap, http::Uri};
+use mockall::mock;
+use reqwest::header::HeaderName;
+use serde_json::json;
+use solana_caching_service::{
+    rpc::{
+        RedactingRpc, RpcApi,
+        upstream::{ApiKeyAuth, UpstreamAuth},
+    },
+    utils::{error_utils::IsTransient, redact::Redactor},
+};
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    nonblocking::rpc_client::RpcClient,
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+use tokio::net::TcpListener;
+
+mock! {
+    pub RpcApi {}
+    impl RpcApi for RpcApi {
+        fn get_slot<'a>(&'a self) -> Pin<Box<dyn Future<Output = Result<u64, ClientError>> + Send + 'a>>;
+        fn get_blocks<'a>(
+            &'a self,
+            start_slot: u64,
+            end_slot: Option<u64>,
+        ) -> Pin<Box<dyn Future<Output = Result<Vec<u64>, ClientError>> + Send + 'a>>;
+    }
+}
+
+const API_KEY: &str = "provider-secret-key";
+
+type Requests = Arc<Mutex<Vec<(String, HeaderMap)>>>;
+
+/// Local stand-in for the provider, answering every call with slot 42 and recording
+/// the path and headers of each request
+async fn spawn_provider_stand_in() -> (String, Requests) {
+    let requests = Requests::default();
+    let recorded = requests.clone();
+    let app = Router::new().fallback(move |uri: Uri, headers: HeaderMap| {
+        let recorded = recorded.clone();
+        async move {
+            recorded
+                .lock()
+                .unwrap()
+                .push((uri.path().to_string(), headers));
+            Json(json!({"jsonrpc": "2.0", "result": 42, "id": 1}))
+        }
+    });
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+    (format!("http://{}/", addr), requests)
+}
+
+async fn call_with(auth: ApiKeyAuth) -> (String, HeaderMap) {
+    let (url, requests) = spawn_provider_stand_in().await;
+    let upstream =
+        UpstreamAuth::new(API_KEY, auth, HeaderName::from_static("x-provider-key")).unwrap();
+
+    let client = upstream.rpc_client(&url).unwrap();
+    assert_eq!(client.get_slot().await.unwrap(), 42);
+
+    requests.lock().unwrap().pop().unwrap()
+}
+
+#[tokio::test]
+async fn test_url_auth_appends_key_to_path() {
+    let (path, headers) = call_with(ApiKeyAuth::Url).await;
+
+    assert_eq!(path, format!("/{}", API_KEY));
+    assert!(!headers.contains_key("x-provider-key"));
+    assert!(!headers.contains_key("authorization"));
+}
+
+#[tokio::test]
+async fn test_header_auth_sends_key_in_configured_header() {
+    let (path, headers) = call_with(ApiKeyAuth::Header).await;
+
+    assert_eq!(path, "/");
+    assert_eq!(headers["x-provider-key"], API_KEY);
+    assert!(
+        headers.contains_key("solana-client"),
+        "Default headers kept"
+    );
+}
+
+#[tokio::test]
+async fn test_bearer_auth_sends_authorization_header() {
+    let (path, headers) = call_with(ApiKeyAuth::Bearer).await;
+
+    assert_eq!(path, "/");
+    assert_eq!(headers["authorization"], format!("Bearer {}", API_KEY));
+}
+
+#[test]
+fn test_api_key_auth_from_str() {
+    assert_eq!("url".parse::<ApiKeyAuth>(), Ok(ApiKeyAuth::Url));
+    assert_eq!(" Bearer ".parse::<ApiKeyAuth>(), Ok(ApiKeyAuth::Bearer));
+    assert!("query".parse::<ApiKeyAuth>().is_err());
+}
+
+#[tokio::test]
+async fn test_redacting_rpc_strips_key_from_connection_errors() {
+    // Nothing listens on the port once the listener is dropped
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .await
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let url = format!("http://{}/{}", addr, API_KEY);
+    let unredacted = RpcClient::new(url.clone()).get_slot().await.unwrap_err();
+    assert!(format!("{} {:?}", unredacted, unredacted).contains(API_KEY));
+
+    let client = RedactingRpc::new(
+        Arc::new(RpcClient::new(url)),
+        Arc::new(Redactor::new([API_KEY.to_string()])),
+    );
+    let error = RpcApi::get_slot(&client).await.unwrap_err();
+
+    assert!(!format!("{} {:?}", error, error).contains(API_KEY));
+    assert!(error.is_transient(), "Still retried and failed over");
+}
+
+#[tokio::test]
+async fn test_redacting_rpc_scrubs_error_messages() {
+    let mut inner = MockRpcApi::new();
+    inner.expect_get_blocks().returning(|_, _| {
+        Box::pin(async {
+            Err(ClientError::from(ClientErrorKind::Custom(format!(
+                "request to https://rpc.example.com/{} failed",
+                API_KEY
+            ))))
+        })
+    });
+    let client = RedactingRpc::new(
+        Arc::new(inner),
+        Arc::new(Redactor::new([API_KEY.to_string()])),
+    );
+
+    let error = client.get_blocks(10, Some(20)).await.unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "Custom: request to https://rpc.example.com/<redacted> failed"
+    );
+}
+
+#[test]
+fn test_redactor_replaces_longest_secret_first() {
+    let redactor = Redactor::new([
+        "key".to_string(),
+        "https://fallback.example.com/key".to_string(),
+        String::new(),
+    ]);
+
+    assert_eq!(
+        redactor.redact("fallback https://fallback.example.com/key, primary key"),
+        "fallback <redacted>, primary <redacted>"
+    );
+    assert_eq!(redactor.redact("nothing to hide"), "nothing to hide");
+}
+
+#[test]
+fn test_upstream_auth_rejects_key_unfit_for_header() {
+    let header = HeaderName::from_static("x-api-key");
+
+    assert!(UpstreamAuth::new("bad\nkey", ApiKeyAuth::Header, header.clone()).is_err());
+    assert!(UpstreamAuth::new("bad\nkey", ApiKeyAuth::Url, header).is_ok());
+}